@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aptos_fuzzer::{AptosFuzzerInput, AptosFuzzerState, AptosMoveExecutor};
+use libafl::executors::Executor;
+use libafl::inputs::Input;
+use libafl::observers::MapObserver;
+
+/// Edges reached by the inputs executed so far
+struct Coverage {
+    executor: AptosMoveExecutor<(), ()>,
+    state: AptosFuzzerState,
+    covered: Vec<bool>,
+}
+
+impl Coverage {
+    fn new(state: AptosFuzzerState) -> Self {
+        let mut executor = AptosMoveExecutor::new();
+        if state.has_custom_environment() {
+            executor = executor.with_environment_of(state.aptos_state());
+        }
+        Self {
+            executor,
+            state,
+            covered: Vec::new(),
+        }
+    }
+
+    /// Execute `input` and record its edges; true if it reached a new one
+    fn add(&mut self, input: &AptosFuzzerInput) -> bool {
+        if let Err(err) = self.executor.run_target(&mut (), &mut self.state, &mut (), input) {
+            eprintln!("Execution failed: {err}");
+            return false;
+        }
+
+        let edges = self.executor.pc_observer().to_vec();
+        self.covered.resize(edges.len(), false);
+        let mut reached_new = false;
+        for (covered, hits) in self.covered.iter_mut().zip(edges) {
+            if hits > 0 && !*covered {
+                *covered = true;
+                reached_new = true;
+            }
+        }
+        reached_new
+    }
+}
+
+/// Copy to `output` the inputs of `corpus_dir` that reach an edge no shorter
+/// input reaches
+pub fn minimize(corpus_dir: &Path, output: &Path, state: AptosFuzzerState) {
+    let mut inputs = AptosFuzzerState::load_inputs_from_dir(corpus_dir);
+    // Shorter sequences run first so they are the ones kept
+    inputs.sort_by_key(|input| input.calls().len());
+    if let Err(err) = fs::create_dir_all(output) {
+        panic!("failed to create {}: {err}", output.display());
+    }
+
+    let total = inputs.len();
+    let mut coverage = Coverage::new(state);
+    let mut kept = 0;
+    for input in inputs {
+        if coverage.add(&input) && save(&input, output) {
+            kept += 1;
+        }
+    }
+    println!("Kept {kept} of {total} inputs in {}", output.display());
+}
+
+/// Copy into `corpus_dir` the inputs of `sources` that reach an edge its own
+/// inputs don't
+pub fn merge(sources: &[PathBuf], corpus_dir: &Path, state: AptosFuzzerState) {
+    let mut coverage = Coverage::new(state);
+    for input in AptosFuzzerState::load_inputs_from_dir(corpus_dir) {
+        coverage.add(&input);
+    }
+
+    let mut merged = 0;
+    for source in sources {
+        let inputs = AptosFuzzerState::load_inputs_from_dir(source);
+        if inputs.is_empty() {
+            eprintln!("No inputs in {}", source.display());
+        }
+        for input in inputs {
+            if coverage.add(&input) && save(&input, corpus_dir) {
+                merged += 1;
+            }
+        }
+    }
+    println!("Merged {merged} inputs into {}", corpus_dir.display());
+}
+
+fn save(input: &AptosFuzzerInput, dir: &Path) -> bool {
+    let path = dir.join(input.generate_name(None));
+    match input.to_file(&path) {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("Failed to save {}: {err}", path.display());
+            false
+        }
+    }
+}
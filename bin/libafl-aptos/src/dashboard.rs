@@ -0,0 +1,109 @@
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use fuzzer_core::progress::Progress;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::watch;
+
+/// Time between redraws, and at most until a key press is handled
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Show the snapshots of a `CoreFuzzer` campaign until it ends. `q`, Esc or
+/// Ctrl-C set `stop`, ending the campaign once the inputs in flight finish.
+pub fn show(progress: watch::Receiver<Progress>, stop: Arc<AtomicBool>) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let shown = follow(&mut terminal, progress, &stop);
+    ratatui::restore();
+    shown
+}
+
+fn follow(
+    terminal: &mut DefaultTerminal,
+    mut progress: watch::Receiver<Progress>,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    loop {
+        // The fuzzer is gone if the campaign failed before finishing
+        if progress.has_changed().is_err() {
+            return Ok(());
+        }
+        let snapshot = progress.borrow_and_update().clone();
+        terminal.draw(|frame| draw(frame, &snapshot))?;
+        if snapshot.finished {
+            return Ok(());
+        }
+
+        if !event::poll(REDRAW_INTERVAL)? {
+            continue;
+        }
+        // Raw mode turns Ctrl-C into a key press instead of a signal
+        if let Event::Key(key) = event::read()? {
+            let interrupt = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if key.kind == KeyEventKind::Press && (interrupt || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)) {
+                stop.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, progress: &Progress) {
+    let [campaign, coverage] = Layout::vertical([Constraint::Length(9), Constraint::Min(0)]).areas(frame.area());
+
+    let last_violation = progress.last_violation.as_deref().unwrap_or("-");
+    let rpc = match progress.rpc_latency() {
+        Some(latency) => format!(
+            "{}ms over {} requests",
+            latency.as_millis(),
+            progress.endpoints.iter().map(|endpoint| endpoint.requests).sum::<u64>()
+        ),
+        None => "-".to_string(),
+    };
+    let lines = vec![
+        Line::from(format!(
+            "iterations      {}/{}",
+            progress.iterations, progress.total_iterations
+        )),
+        Line::from(format!("execs/sec       {:.0}", progress.execs_per_sec())),
+        Line::from(format!("elapsed         {}s", progress.elapsed.as_secs())),
+        Line::from(format!("corpus          {}", progress.corpus_size)),
+        Line::from(format!("violations      {}", progress.violations)),
+        Line::from(format!("last violation  {last_violation}")),
+        Line::from(format!("fork rpc        {rpc}")),
+    ];
+    let title = if progress.finished {
+        "campaign (finished)"
+    } else {
+        "campaign (q to stop)"
+    };
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), campaign);
+
+    let functions = progress
+        .coverage
+        .as_ref()
+        .map(|coverage| coverage.functions.as_slice())
+        .unwrap_or_default();
+    let rows = functions.iter().map(|function| {
+        let percentage = match function.instruction_count {
+            0 => 0.0,
+            count => function.instructions_hit as f64 * 100.0 / count as f64,
+        };
+        Row::new(vec![
+            function.function.clone(),
+            format!("{}/{}", function.instructions_hit, function.instruction_count),
+            format!("{percentage:.1}%"),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [Constraint::Min(30), Constraint::Length(12), Constraint::Length(8)],
+    )
+    .header(Row::new(vec!["function", "instructions", "covered"]))
+    .block(Block::bordered().title("coverage"));
+    frame.render_widget(table, coverage);
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use aptos_fuzzer::FindingsDb;
+
+fn open(path: &Path) -> FindingsDb {
+    FindingsDb::open(path).unwrap_or_else(|e| panic!("{e:#}"))
+}
+
+/// Print every recorded finding, most recently hit first
+pub fn list(path: &Path) {
+    let findings = open(path).findings().unwrap_or_else(|e| panic!("{e:#}"));
+    println!("=== Findings: {} in {} ===", findings.len(), path.display());
+    for finding in findings {
+        println!(
+            "#{} [{}x, last in campaign {}] {}: {} ({})",
+            finding.id,
+            finding.occurrences,
+            finding.last_campaign,
+            finding.target,
+            finding.description,
+            finding.location
+        );
+    }
+}
+
+/// Print a finding with the campaigns it was first and last hit in
+pub fn show(path: &Path, id: i64) {
+    let db = open(path);
+    let Some(finding) = db.finding(id).unwrap_or_else(|e| panic!("{e:#}")) else {
+        eprintln!("No finding #{id} in {}", path.display());
+        return;
+    };
+    println!("=== Finding #{} ===", finding.id);
+    println!("Target: {}", finding.target);
+    println!("Location: {}", finding.location);
+    println!("Description: {}", finding.description);
+    println!("Campaigns hitting it: {}", finding.occurrences);
+    if let Some(artifact) = &finding.artifact {
+        println!("Reproduce with: {artifact}");
+    }
+
+    for (label, campaign_id) in [
+        ("First seen", finding.first_campaign),
+        ("Last seen", finding.last_campaign),
+    ] {
+        let Some(campaign) = db.campaign(campaign_id).unwrap_or_else(|e| panic!("{e:#}")) else {
+            continue;
+        };
+        println!(
+            "{label} in campaign {} (started at {})",
+            campaign.id, campaign.started_at
+        );
+        if let Some(version) = &campaign.package_version {
+            println!("  Package version: {version}");
+        }
+        if let Some(seed) = campaign.seed {
+            println!("  Seed: {seed}");
+        }
+        println!("  Config: {}", campaign.config);
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::thread;
+
+use aptos_adapter::AptosAdapter;
+use fuzzer_core::fuzzer::CoreFuzzer;
+use fuzzer_core::html::HtmlReporter;
+use fuzzer_core::reporter::ConsoleReporter;
+use fuzzer_core::FuzzerConfig;
+use tracing::warn;
+
+use crate::dashboard;
+
+/// Config fuzzing `target`, an `address::module::function`
+pub fn config(target: &str) -> FuzzerConfig {
+    let Some((module, function)) = target.rsplit_once("::") else {
+        panic!("invalid function {target}: expected ADDRESS::MODULE::FUNCTION");
+    };
+    let Some((address, module)) = module.rsplit_once("::") else {
+        panic!("invalid function {target}: expected ADDRESS::MODULE::FUNCTION");
+    };
+    // The adapter runs on its own state, so there is no RPC to talk to
+    FuzzerConfig::new(
+        String::new(),
+        address.to_string(),
+        module.to_string(),
+        function.to_string(),
+    )
+}
+
+/// Fuzz `config`'s function with [`CoreFuzzer`] on `adapter` and print the
+/// report, also written as HTML to `html_report` if given. With `tui` the
+/// campaign is followed on a dashboard until it ends. Ctrl-C ends the
+/// campaign once the inputs in flight finish; it is still saved and
+/// reported.
+pub fn run(adapter: AptosAdapter, config: FuzzerConfig, html_report: Option<&Path>, tui: bool) {
+    let runtime = tokio::runtime::Runtime::new().unwrap_or_else(|e| panic!("failed to start the runtime: {e}"));
+    let mut fuzzer = runtime
+        .block_on(CoreFuzzer::new(adapter, config))
+        .unwrap_or_else(|e| panic!("{e:#}"));
+    let stop = fuzzer.stop_flag();
+    let dashboard = if tui {
+        let progress = fuzzer.subscribe();
+        Some(thread::spawn(move || dashboard::show(progress, stop)))
+    } else {
+        if let Err(err) = ctrlc::set_handler(move || stop.store(true, Ordering::Relaxed)) {
+            warn!(%err, "failed to install the interrupt handler");
+        }
+        None
+    };
+    let result = runtime.block_on(fuzzer.run());
+    // Closes the dashboard even if the campaign failed before its last
+    // snapshot
+    drop(fuzzer);
+    if let Some(dashboard) = dashboard {
+        match dashboard.join() {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => warn!(%err, "dashboard failed"),
+            Err(_) => warn!("dashboard panicked"),
+        }
+    }
+    let result = result.unwrap_or_else(|e| panic!("{e:#}"));
+
+    ConsoleReporter::new()
+        .print_fuzzing_result(&result)
+        .unwrap_or_else(|e| panic!("{e:#}"));
+    if let Some(path) = html_report {
+        HtmlReporter::new()
+            .write(path, &result)
+            .unwrap_or_else(|e| panic!("failed to write {}: {e:#}", path.display()));
+        println!("HTML report written to {}", path.display());
+    }
+}
@@ -1,20 +1,62 @@
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
+use aptos_adapter::{AptosAdapter, Network};
+use aptos_fuzzer::executor::aptos_custom_state::AptosCustomState;
+use aptos_fuzzer::executor::{ForkConfig, RestStateFetcher};
+use aptos_fuzzer::package::PACKAGE_SUMMARY_PREFIX;
+use aptos_fuzzer::seeding::{onchain_seeds, source_seeds};
+use aptos_fuzzer::templates::template_seeds;
 use aptos_fuzzer::{
-    AbortCodeFeedback, AbortCodeObjective, AptosFuzzerMutator, AptosFuzzerState, AptosMoveExecutor,
-    ShiftOverflowObjective,
+    budget_share, AbortPolicy, AptosFuzzerInput, AptosFuzzerState, CampaignBuilder, Detector, EntryTarget,
+    EventInvariants, FeatureFlags, FindingsDb, FunctionSummary, FuzzCampaign, FuzzerSetup, GasMode,
+    PackageSummaryMetadata, ViewChecks, WriteRules,
 };
-use clap::Parser;
+use aptos_move_core_types::account_address::AccountAddress;
+use clap::{Parser, Subcommand, ValueEnum};
 use libafl::corpus::Corpus;
-use libafl::events::SimpleEventManager;
-use libafl::feedbacks::{EagerOrFeedback, MaxMapFeedback, StateInitializer};
+use libafl::events::{
+    ClientDescription, Event, EventConfig, EventFirer, EventRestarter, Launcher, LlmpRestartingEventManager,
+};
+use libafl::feedbacks::StateInitializer;
 use libafl::fuzzer::Fuzzer;
-use libafl::monitors::SimpleMonitor;
+use libafl::monitors::stats::{AggregatorOps, UserStats, UserStatsValue};
+use libafl::monitors::tui::TuiMonitor;
+use libafl::monitors::MultiMonitor;
 use libafl::schedulers::QueueScheduler;
 use libafl::stages::StdMutationalStage;
-use libafl::state::HasCorpus;
-use libafl::{Evaluator, StdFuzzer};
+use libafl::state::{HasCorpus, HasExecutions, HasSolutions};
+use libafl::{Evaluator, HasMetadata, StdFuzzer};
+use libafl_bolts::core_affinity::Cores;
+use libafl_bolts::shmem::{ShMemProvider, StdShMemProvider};
 use libafl_bolts::tuples::tuple_list;
+use tracing::{info, info_span, warn};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::EnvFilter;
+
+mod corpus;
+mod dashboard;
+mod findings;
+mod function;
+mod replay;
+mod report;
+mod server;
+
+/// Executions between saves of the feedback history
+const HISTORY_SAVE_INTERVAL: u64 = 10_000;
+
+/// Executions between checks of a package campaign's per-function budget
+const BUDGET_CHECK_INTERVAL: u64 = 1_000;
+
+/// File the clients' output, or the `function` command's logs, go to while
+/// the dashboard is shown
+const TUI_LOG_FILE: &str = "libafl-aptos.log";
 
 #[derive(Debug, Parser)]
 #[command(author, version, about = "LibAFL-based fuzzer for Aptos Move modules")]
@@ -23,54 +65,869 @@ struct Cli {
     #[arg(long = "abi-path", value_name = "ABI_PATH")]
     abi_path: Option<PathBuf>,
 
-    /// Path to a compiled Move module to publish before fuzzing
+    /// Path to a compiled Move module, package build directory or .mrb bundle
+    /// to publish before fuzzing
     #[arg(long = "module-path", value_name = "MODULE_PATH")]
     module_path: Option<PathBuf>,
+
+    /// Compiled Move script (`.mv`) or directory of scripts to fuzz as script
+    /// payloads, e.g. a package's `bytecode_scripts` build directory
+    #[arg(long = "script-path", value_name = "SCRIPT_PATH")]
+    script_path: Option<PathBuf>,
+
+    /// Sender accounts to create, fund and rotate through (repeatable)
+    #[arg(long = "sender", value_name = "ADDRESS", default_values = ["0xa11ce", "0xb0b"])]
+    senders: Vec<String>,
+
+    /// Directory where interesting inputs are saved and resumed from
+    #[arg(long = "corpus-dir", value_name = "CORPUS_DIR", default_value = "corpus")]
+    corpus_dir: PathBuf,
+
+    /// Directory where inputs triggering objectives are saved
+    #[arg(long = "solutions-dir", value_name = "SOLUTIONS_DIR", default_value = "solutions")]
+    solutions_dir: PathBuf,
+
+    /// Cores to run fuzzing clients on, e.g. `0-15` or `0,2,4`
+    #[arg(long = "cores", value_name = "CORES", default_value = "0")]
+    cores: String,
+
+    /// Port of the LLMP broker that syncs the clients' corpora
+    #[arg(long = "broker-port", value_name = "PORT", default_value_t = 1337)]
+    broker_port: u16,
+
+    /// Keep each execution's writes in the state instead of rolling them back
+    #[arg(long = "persist-writes")]
+    persist_writes: bool,
+
+    /// Executions slower than this are saved as timeouts; ten times longer
+    /// kills the client
+    #[arg(long = "timeout-ms", value_name = "MILLIS", default_value_t = 1000)]
+    timeout_ms: u64,
+
+    /// Bug classes to save solutions for, comma-separated:
+    /// `shift_overflow`, `arithmetic`, `timeout`, `abort`,
+    /// `instruction_ceiling`, `event_invariant`, `write_rule`, `view_check`
+    /// and `bare_vm_divergence`. Defaults to all of them, but
+    /// `shift_overflow` for `movevm`.
+    #[arg(long = "detectors", value_name = "DETECTORS", value_delimiter = ',')]
+    detectors: Option<Vec<Detector>>,
+
+    /// Executions running more instructions than this are saved as
+    /// timeouts. Off by default. The budget is checked between calls, so a
+    /// call looping forever is still only stopped by --timeout-ms.
+    #[arg(long = "gas-budget", value_name = "INSTRUCTIONS")]
+    gas_budget: Option<u64>,
+
+    /// Executions running more instructions than this are saved as
+    /// potential DoS, with the most expensive call and its arguments; unlike
+    /// the budget they run to completion
+    #[arg(long = "instruction-ceiling", value_name = "INSTRUCTIONS")]
+    instruction_ceiling: Option<u64>,
+
+    /// Network to fork state from: `aptos`, `aptos-testnet`, `movement`,
+    /// `movement-testnet`, or the fullnode REST API of any network compatible
+    /// with Aptos's, e.g. `https://fullnode.mainnet.aptoslabs.com/v1`. Known
+    /// networks also run with their chain ID
+    #[arg(long = "network", alias = "fork-url", value_name = "NETWORK")]
+    network: Option<Network>,
+
+    /// Ledger version to fork at; defaults to the latest one
+    #[arg(long = "fork-version", value_name = "VERSION", requires = "network")]
+    fork_version: Option<u64>,
+
+    /// Chain ID to run the forked network with instead of the known one or
+    /// its on-chain one
+    #[arg(long = "chain-id", value_name = "ID", requires = "network")]
+    chain_id: Option<u8>,
+
+    /// Gas schedule to install on chain: `unmetered`, `release` for the
+    /// framework's schedule or a JSON `GasScheduleV2` file. Defaults to none,
+    /// or the on-chain schedule when forking. Calls still run without a gas
+    /// meter: natives read their gas parameters from it, and only fee-payer
+    /// and multisig transactions are charged with it.
+    #[arg(long = "gas-schedule", value_name = "SCHEDULE")]
+    gas_schedule: Option<GasMode>,
+
+    /// Feature flags to run with: `default`, the REST URL of a fullnode to
+    /// pull the network's current flags from, or a JSON list of enabled flag
+    /// numbers. Defaults to the release's flags, or the on-chain ones when
+    /// forking.
+    #[arg(long = "features", value_name = "FLAGS")]
+    features: Option<FeatureFlags>,
+
+    /// Framework release bundle (`.mrb`) to deploy over the framework the
+    /// fuzzer is built with, e.g. the release running on mainnet
+    #[arg(long = "framework-bundle", value_name = "PATH")]
+    framework_bundle: Option<PathBuf>,
+
+    /// Table entries to seed: a JSON list of `{"handle", "key", "value"}`
+    /// with hex-encoded BCS key and value, or a BCS file
+    #[arg(long = "table-fixture", value_name = "PATH")]
+    table_fixture: Option<PathBuf>,
+
+    /// State snapshot to load after setup, as exported by `replay
+    /// --export-state`
+    #[arg(long = "state-snapshot", value_name = "PATH")]
+    state_snapshot: Option<PathBuf>,
+
+    /// New version of the package to upgrade to mid-campaign, keeping the
+    /// resources stored so far: those of the snapshot or fork, and with
+    /// `--persist-writes` those the campaign wrote. Commands replaying saved
+    /// inputs run against it from the start.
+    #[arg(long = "upgrade-module-path", value_name = "PATH")]
+    upgrade_module_path: Option<PathBuf>,
+
+    /// Executions of each client before the package is upgraded
+    #[arg(long = "upgrade-after", value_name = "EXECUTIONS", default_value_t = 100_000)]
+    upgrade_after: u64,
+
+    /// Also seed with up to this many recent successful on-chain calls of
+    /// the target functions, found through `--indexer-url` and replayed with
+    /// their original arguments
+    #[arg(long = "seed-from-chain", value_name = "COUNT", requires_all = ["network", "indexer_url"])]
+    seed_from_chain: Option<usize>,
+
+    /// Indexer GraphQL API of the forked network, e.g.
+    /// `https://api.mainnet.aptoslabs.com/v1/graphql`
+    #[arg(long = "indexer-url", value_name = "URL")]
+    indexer_url: Option<String>,
+
+    /// Move package whose sources and tests are scanned for literal
+    /// arguments of the target functions to seed with
+    #[arg(long = "source-path", value_name = "DIR")]
+    source_path: Option<PathBuf>,
+
+    /// Also seed with flash loan, price manipulation and sandwich sequences
+    /// of the seed calls, matched to their parts by name and signature
+    #[arg(long = "sequence-templates")]
+    sequence_templates: bool,
+
+    /// TOML or JSON file sorting abort codes into `expected` guards and
+    /// `bug`s; only bugs are solutions. Without it every abort is one.
+    #[arg(long = "abort-policy", value_name = "PATH")]
+    abort_policy: Option<PathBuf>,
+
+    /// TOML or JSON file of invariants on the emitted events: event types
+    /// that must or must never be emitted, and bounds on their fields
+    #[arg(long = "event-invariants", value_name = "PATH")]
+    event_invariants: Option<PathBuf>,
+
+    /// TOML or JSON file of rules on what calls may write, e.g. a balance
+    /// that must never decrease or a resource that must never be deleted
+    #[arg(long = "write-rules", value_name = "PATH")]
+    write_rules: Option<PathBuf>,
+
+    /// TOML or JSON file of view functions called after every call, whose
+    /// results must be conserved, move one way only or stay within bounds
+    #[arg(long = "view-checks", value_name = "PATH")]
+    view_checks: Option<PathBuf>,
+
+    /// Also call every entry function in a bare MoveVM session and save
+    /// inputs whose outcome there differs from the transaction's, pointing
+    /// at the VM or the argument encoding rather than the target. Not for
+    /// `movevm`.
+    #[arg(long = "compare-bare-vm")]
+    compare_bare_vm: bool,
+
+    /// Fuzz every entry function of the deployed Aptos package in turn,
+    /// found from the bytecode instead of ABI files, and save a
+    /// per-function summary
+    #[arg(long = "package-only")]
+    package_only: bool,
+
+    /// Average seconds each function is fuzzed for with `--package-only`;
+    /// functions scoring higher for arithmetic bugs get more
+    #[arg(long = "function-budget-secs", value_name = "SECONDS", default_value_t = 60)]
+    function_budget_secs: u64,
+
+    /// Show a terminal dashboard instead of the monitor's log lines, or of
+    /// the `function` command's; the clients' output or the command's logs
+    /// go to `libafl-aptos.log`
+    #[arg(long = "tui")]
+    tui: bool,
+
+    /// Format of the log lines; filter them with `RUST_LOG`, e.g.
+    /// `RUST_LOG=aptos_fuzzer=debug` for a span per execution
+    #[arg(long = "log-format", value_name = "FORMAT", value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
+    /// SQLite database `report` records what the solutions trigger in, so
+    /// campaigns against the same modules can be compared; read by `findings`
+    #[arg(long = "findings-db", value_name = "PATH")]
+    findings_db: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-fn main() {
-    let cli = Cli::parse();
-    println!("Starting Aptos Move Fuzzer...");
-
-    // Build coverage feedback on top of executor's pc observer
-    let mut executor = AptosMoveExecutor::new();
-    let cov_feedback = MaxMapFeedback::new(executor.pc_observer());
-    let mut feedback = EagerOrFeedback::new(cov_feedback, AbortCodeFeedback::new());
-    let objective = EagerOrFeedback::new(ShiftOverflowObjective::new(), AbortCodeObjective::new());
-
-    let mon = SimpleMonitor::new(|s| println!("{s}"));
-    let mut mgr = SimpleEventManager::new(mon);
-    let scheduler = QueueScheduler::new();
-
-    let abi = cli
-        .abi_path
-        .clone()
-        .unwrap_or_else(|| panic!("--abi-path is required (no fallback)."));
-    let module = cli
-        .module_path
-        .clone()
-        .unwrap_or_else(|| panic!("--module-path is required (no fallback)."));
-    let mut state = AptosFuzzerState::new(Some(abi), Some(module));
-    let _ = feedback.init_state(&mut state);
-    let mut fuzzer = StdFuzzer::new(scheduler, feedback, objective);
-
-    let mutator = AptosFuzzerMutator::default();
-    let mut stages = tuple_list!(StdMutationalStage::new(mutator));
-
-    println!(
-        "Starting fuzzing loop with {} initial inputs in corpus",
-        state.corpus().count()
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run a fuzzing campaign (the default without a subcommand)
+    Fuzz,
+
+    /// Execute a saved solution once and print a reproduction report
+    Replay {
+        /// Input file from the solutions (or corpus) directory
+        #[arg(value_name = "SOLUTION")]
+        solution: PathBuf,
+
+        /// Export the state left by the replayed sequence, e.g. to reuse
+        /// setup transactions across campaigns
+        #[arg(long = "export-state", value_name = "PATH")]
+        export_state: Option<PathBuf>,
+    },
+
+    /// Maintain the corpus directory
+    #[command(subcommand)]
+    Corpus(CorpusCommand),
+
+    /// Summarize the corpus and re-execute every solution to show what it
+    /// triggers
+    Report,
+
+    /// Browse the findings recorded in `--findings-db`
+    #[command(subcommand)]
+    Findings(FindingsCommand),
+
+    /// Fuzz every entry and public function of compiled modules, calling
+    /// them directly in a bare MoveVM session rather than as transactions.
+    /// Faster, but natives needing a transaction (events, tables) are
+    /// unavailable, so it suits pure library code such as math modules. The
+    /// session keeps no trace and no changes:
+    ///
+    /// - coverage is the index and bit length of each return value, not the
+    ///   instructions run;
+    /// - shifts aren't traced, so `--detectors shift_overflow` is refused;
+    /// - writes are discarded, so write rules and `--persist-writes` see none.
+    Movevm {
+        /// Module, package directory or bundle to fuzz
+        #[arg(long = "module", value_name = "PATH")]
+        module: PathBuf,
+    },
+
+    /// Fuzz one entry function with the fuzzer-core engine instead: boundary
+    /// and dictionary stages, guard steering and its report, on single calls
+    /// rather than transaction sequences. `--module-path` must be a single
+    /// compiled module; it is deployed over the fork if there is one. The
+    /// first `--sender` calls it.
+    Function {
+        /// Function to fuzz, as `ADDRESS::MODULE::FUNCTION`
+        #[arg(value_name = "FUNCTION")]
+        target: String,
+
+        /// Initial arguments, without the leading signers; defaults for the
+        /// rest
+        #[arg(long = "args", value_name = "ARG", num_args = 1..)]
+        args: Vec<String>,
+
+        /// Type arguments of a generic function
+        #[arg(long = "type-args", value_name = "TYPE", num_args = 1..)]
+        type_args: Vec<String>,
+
+        #[arg(long = "iterations", value_name = "COUNT", default_value_t = 1_000_000)]
+        iterations: u64,
+
+        #[arg(long = "timeout-secs", value_name = "SECONDS", default_value_t = 300)]
+        timeout_secs: u64,
+
+        /// Keep fuzzing after the first violation and report every distinct
+        /// one
+        #[arg(long = "keep-going")]
+        keep_going: bool,
+
+        /// Also write the report as HTML
+        #[arg(long = "html-report", value_name = "PATH")]
+        html_report: Option<PathBuf>,
+    },
+
+    /// Serve JSON-RPC for a remote controller to start, stop and watch
+    /// in-process campaigns and pull their findings. Unauthenticated.
+    Serve {
+        /// Address to listen on, one line of JSON-RPC 2.0 per request; a
+        /// bare port is bound on localhost
+        #[arg(long = "listen", value_name = "ADDR", default_value = "127.0.0.1:9090")]
+        listen: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum FindingsCommand {
+    /// List every finding, most recently hit first
+    List,
+
+    /// Show a finding and the campaigns it was first and last hit in
+    Show {
+        #[arg(value_name = "ID")]
+        id: i64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum CorpusCommand {
+    /// Keep only the inputs needed to reach the corpus's coverage
+    Minimize {
+        /// Directory the minimized corpus is written to
+        #[arg(long = "output", value_name = "DIR")]
+        output: PathBuf,
+    },
+
+    /// Add the inputs of other corpora that reach new coverage
+    Merge {
+        /// Corpus directories to merge into `--corpus-dir`
+        #[arg(value_name = "SOURCE", required = true)]
+        sources: Vec<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, for aggregating the logs of many runs
+    Json,
+}
+
+/// Install the global log subscriber, at `info` unless `RUST_LOG` says
+/// otherwise
+fn init_logging(format: LogFormat, file: Option<&Path>) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let writer = match file {
+        Some(path) => {
+            let file = File::create(path).unwrap_or_else(|e| panic!("failed to create {}: {e}", path.display()));
+            BoxMakeWriter::new(Mutex::new(file))
+        }
+        None => BoxMakeWriter::new(io::stdout),
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_ansi(file.is_none());
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().with_current_span(true).with_span_list(false).init(),
+    }
+}
+
+/// State for commands executing saved inputs outside a campaign, with the
+/// same setup a fuzzing client gets, upgraded right away if an upgrade is
+/// configured. No ABIs: the saved inputs are the only transaction sequences.
+fn offline_state(setup: &FuzzerSetup) -> AptosFuzzerState {
+    let mut state = setup.new_state().unwrap_or_else(|e| panic!("{e}"));
+    setup.upgrade_now(&mut state).unwrap_or_else(|e| panic!("{e}"));
+    state
+}
+
+/// Builder of the clients' setup from the options shared by every command
+fn campaign_builder(cli: &Cli, senders: Vec<AccountAddress>, fork: Option<ForkConfig>) -> CampaignBuilder {
+    let mut builder = FuzzCampaign::builder()
+        .with_senders(senders)
+        .with_corpus_dir(&cli.corpus_dir)
+        .with_solutions_dir(&cli.solutions_dir)
+        .with_persist_writes(cli.persist_writes)
+        .with_bare_vm_comparison(cli.compare_bare_vm)
+        .with_timeout(Duration::from_millis(cli.timeout_ms))
+        .with_detectors(&detectors(cli));
+    if let Some(path) = &cli.module_path {
+        builder = builder.with_module_path(path);
+    }
+    if let Some(fork) = fork {
+        builder = builder.with_fork(fork);
+    }
+    if let Some(path) = &cli.framework_bundle {
+        builder = builder.with_framework_bundle(path);
+    }
+    if let Some(path) = &cli.state_snapshot {
+        builder = builder.with_state_snapshot(path);
+    }
+    if let Some(path) = &cli.table_fixture {
+        builder = builder.with_table_fixture(path);
+    }
+    if let Some(mode) = &cli.gas_schedule {
+        builder = builder.with_gas_mode(mode.clone());
+    }
+    if let Some(FeatureFlags(features)) = &cli.features {
+        builder = builder.with_features(features.clone());
+    }
+    if let Some(chain_id) = cli.network.as_ref().and_then(|network| network.chain_id) {
+        builder = builder.with_chain_id(chain_id);
+    }
+    if let Some(path) = &cli.upgrade_module_path {
+        builder = builder.with_upgrade(path, cli.upgrade_after);
+    }
+    if let Some(gas_budget) = cli.gas_budget {
+        builder = builder.with_gas_budget(gas_budget);
+    }
+    if let Some(ceiling) = cli.instruction_ceiling {
+        builder = builder.with_instruction_ceiling(ceiling);
+    }
+    if let Some(path) = &cli.abort_policy {
+        let policy =
+            AbortPolicy::from_file(path).unwrap_or_else(|e| panic!("invalid --abort-policy {}: {e:#}", path.display()));
+        builder = builder.with_abort_policy(policy);
+    }
+    if let Some(path) = &cli.event_invariants {
+        let invariants = EventInvariants::from_file(path)
+            .unwrap_or_else(|e| panic!("invalid --event-invariants {}: {e:#}", path.display()));
+        builder = builder.with_event_invariants(invariants);
+    }
+    if let Some(path) = &cli.write_rules {
+        let rules =
+            WriteRules::from_file(path).unwrap_or_else(|e| panic!("invalid --write-rules {}: {e:#}", path.display()));
+        builder = builder.with_write_rules(rules);
+    }
+    if let Some(path) = &cli.view_checks {
+        let checks =
+            ViewChecks::from_file(path).unwrap_or_else(|e| panic!("invalid --view-checks {}: {e:#}", path.display()));
+        builder = builder.with_view_checks(checks);
+    }
+    builder
+}
+
+/// The `--detectors` to run with; bare MoveVM sessions trace no shifts
+fn detectors(cli: &Cli) -> Vec<Detector> {
+    let bare_vm = matches!(cli.command, Some(Command::Movevm { .. }));
+    match &cli.detectors {
+        Some(detectors) if bare_vm && detectors.contains(&Detector::ShiftOverflow) => {
+            panic!("movevm traces no shifts, so --detectors shift_overflow is unsupported")
+        }
+        Some(detectors) => detectors.clone(),
+        None => Detector::ALL
+            .into_iter()
+            .filter(|detector| !bare_vm || *detector != Detector::ShiftOverflow)
+            .collect(),
+    }
+}
+
+/// What a campaign fuzzes, for telling apart findings of different modules:
+/// the published modules, or the forked network
+fn campaign_target(cli: &Cli) -> String {
+    match (&cli.module_path, &cli.network) {
+        (Some(path), _) => path.display().to_string(),
+        (None, Some(network)) => network.rest_url.clone(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Send a campaign statistic to the monitor, shown next to the execution
+/// and coverage counters
+fn send_stat<EM>(
+    mgr: &mut EM,
+    state: &mut AptosFuzzerState,
+    name: &'static str,
+    value: String,
+) -> Result<(), libafl::Error>
+where
+    EM: EventFirer<AptosFuzzerInput, AptosFuzzerState>,
+{
+    mgr.fire(
+        state,
+        Event::UpdateUserStats {
+            name: Cow::Borrowed(name),
+            value: UserStats::new(UserStatsValue::String(Cow::Owned(value)), AggregatorOps::None),
+            phantom: PhantomData,
+        },
+    )
+}
+
+/// Report the latest solution and the fork's request latency
+fn send_campaign_stats<EM>(
+    mgr: &mut EM,
+    state: &mut AptosFuzzerState,
+    solutions: &mut usize,
+) -> Result<(), libafl::Error>
+where
+    EM: EventFirer<AptosFuzzerInput, AptosFuzzerState>,
+{
+    if state.solutions().count() > *solutions {
+        *solutions = state.solutions().count();
+        let last = state
+            .solutions()
+            .last()
+            .and_then(|id| state.solutions().get(id).ok())
+            .and_then(|testcase| testcase.borrow().filename().clone())
+            .unwrap_or_default();
+        send_stat(mgr, state, "last violation", last)?;
+    }
+    if let Some(latency) = state.aptos_state().fork_latency() {
+        send_stat(mgr, state, "fork rpc", format!("{}ms", latency.as_millis()))?;
+    }
+    Ok(())
+}
+
+/// `Ok` as well for a fuzzing loop the interrupt ended early
+fn allow_interrupt<T>(result: Result<T, libafl::Error>) -> Result<(), libafl::Error> {
+    match result {
+        Ok(_) => Ok(()),
+        Err(libafl::Error::ShuttingDown) if AptosFuzzerState::interrupted() => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Summarize a client's campaign once the interrupt stopped it
+fn log_interrupted(state: &AptosFuzzerState) {
+    info!(
+        executions = *state.executions(),
+        corpus = state.corpus().count(),
+        solutions = state.solutions().count(),
+        "interrupted, campaign saved"
     );
+}
+
+fn main() {
+    let mut cli = Cli::parse();
+    // The function command's dashboard shares the terminal with its logs
+    let log_file = (cli.tui && matches!(cli.command, Some(Command::Function { .. }))).then(|| Path::new(TUI_LOG_FILE));
+    init_logging(cli.log_format, log_file);
+    if let (Some(network), Some(chain_id)) = (&mut cli.network, cli.chain_id) {
+        network.chain_id = Some(chain_id);
+    }
+    // A bare VM campaign is a package campaign over its modules
+    let bare_vm = if let Some(Command::Movevm { module }) = &cli.command {
+        cli.module_path = Some(module.clone());
+        cli.package_only = true;
+        true
+    } else {
+        false
+    };
+    if let Some(Command::Findings(command)) = &cli.command {
+        let path = cli
+            .findings_db
+            .as_ref()
+            .unwrap_or_else(|| panic!("--findings-db is required to browse findings"));
+        match command {
+            FindingsCommand::List => findings::list(path),
+            FindingsCommand::Show { id } => findings::show(path, *id),
+        }
+        return;
+    }
+    if let Some(Command::Serve { listen }) = &cli.command {
+        server::run(listen);
+        return;
+    }
+    let senders: Vec<AccountAddress> = cli
+        .senders
+        .iter()
+        .map(|s| AccountAddress::from_str(s).unwrap_or_else(|e| panic!("invalid --sender {s}: {e}")))
+        .collect();
+
+    // Pin the fork version once so every client sees the same state
+    let fork = cli.network.as_ref().map(|network| {
+        let config = ForkConfig {
+            url: network.rest_url.clone(),
+            version: cli.fork_version,
+        };
+        RestStateFetcher::new(&config)
+            .unwrap_or_else(|e| panic!("failed to fork {}: {e:#}", config.url))
+            .pinned_config()
+    });
+    // Forked runs can target modules already deployed on chain
+    if cli.module_path.is_none() && fork.is_none() {
+        panic!("--module-path is required (no fallback).");
+    }
+    let builder = campaign_builder(&cli, senders.clone(), fork.clone());
+    let offline_setup = || builder.clone().build_setup().unwrap_or_else(|e| panic!("{e:#}"));
+
+    match &cli.command {
+        Some(Command::Replay { solution, export_state }) => {
+            replay::run(solution, offline_state(&offline_setup()), export_state.as_deref());
+            return;
+        }
+        Some(Command::Corpus(CorpusCommand::Minimize { output })) => {
+            corpus::minimize(&cli.corpus_dir, output, offline_state(&offline_setup()));
+            return;
+        }
+        Some(Command::Corpus(CorpusCommand::Merge { sources })) => {
+            corpus::merge(sources, &cli.corpus_dir, offline_state(&offline_setup()));
+            return;
+        }
+        Some(Command::Report) => {
+            let findings_db = cli.findings_db.as_ref().map(|path| {
+                let db = FindingsDb::open(path).unwrap_or_else(|e| panic!("{e:#}"));
+                let campaign = db
+                    .start_campaign(
+                        &campaign_target(&cli),
+                        fork.as_ref()
+                            .and_then(|fork| fork.version)
+                            .map(|v| v.to_string())
+                            .as_deref(),
+                        &format!("{cli:?}"),
+                        None,
+                    )
+                    .unwrap_or_else(|e| panic!("{e:#}"));
+                (db, campaign)
+            });
+            // Solutions are checked as the fuzzing executor checked them
+            let setup = offline_setup();
+            let state = offline_state(&setup);
+            let executor = setup.replay_executor(&state);
+            report::run(
+                &cli.corpus_dir,
+                &cli.solutions_dir,
+                state,
+                setup.abort_policy(),
+                executor,
+                findings_db.as_ref().map(|(db, campaign)| (db, campaign)),
+            );
+            return;
+        }
+        Some(Command::Function {
+            target,
+            args,
+            type_args,
+            iterations,
+            timeout_secs,
+            keep_going,
+            html_report,
+        }) => {
+            let mut adapter = AptosAdapter::new();
+            if let Some(network) = &cli.network {
+                adapter = adapter
+                    .with_network(network, fork.as_ref().and_then(|fork| fork.version))
+                    .unwrap_or_else(|e| panic!("{e:#}"));
+            }
+            if let Some(path) = &cli.module_path {
+                adapter = adapter.with_module_path(path).unwrap_or_else(|e| panic!("{e:#}"));
+            }
+            if cli.compare_bare_vm {
+                adapter = adapter.with_bare_vm_comparison();
+            }
+            let mut config = function::config(target)
+                .with_args(args.clone())
+                .with_type_arguments(type_args.clone())
+                .with_iterations(*iterations)
+                .with_timeout_seconds(*timeout_secs)
+                .with_keep_going(*keep_going);
+            if let Some(sender) = senders.first() {
+                config = config.with_sender(sender.to_hex_literal());
+            }
+            if let Some(path) = &cli.findings_db {
+                config = config.with_findings_db(path.clone());
+            }
+            function::run(adapter, config, html_report.as_deref(), cli.tui);
+            return;
+        }
+        Some(Command::Fuzz) |
+        Some(Command::Movevm { .. }) |
+        None |
+        Some(Command::Findings(_)) |
+        Some(Command::Serve { .. }) => {}
+    }
+
+    info!("starting Aptos Move fuzzer");
 
-    // Prefer adding initial seeds via fuzzer.add_input to fire events and reflect in monitor
-    let initial_inputs = state.take_initial_inputs();
-    for input in initial_inputs {
-        let _ = fuzzer
-            .add_input(&mut state, &mut executor, &mut mgr, input)
-            .expect("failed to add initial input");
+    // Package campaigns seed each function from the bytecode when its turn comes
+    let mut builder = builder.with_bare_vm(bare_vm).with_hang_watchdog(true);
+    if !cli.package_only {
+        let abi = cli
+            .abi_path
+            .clone()
+            .unwrap_or_else(|| panic!("--abi-path is required (no fallback)."));
+        builder = builder.with_abi_path(abi);
     }
+    if let Some(path) = &cli.script_path {
+        builder = builder.with_script_path(path);
+    }
+    let setup = builder.build_setup().unwrap_or_else(|e| panic!("{e:#}"));
+    let cores = Cores::from_cmdline(&cli.cores).unwrap_or_else(|e| panic!("invalid --cores {}: {e}", cli.cores));
+
+    // Build the shared VM environment and framework modules once so forked
+    // clients inherit them
+    let _ = AptosCustomState::default_env();
 
-    fuzzer
-        .fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)
-        .expect("Fuzzing loop failed");
+    let mut run_client = |state: Option<AptosFuzzerState>,
+                          mut mgr: LlmpRestartingEventManager<_, _, _, _, _>,
+                          client: ClientDescription|
+     -> Result<(), libafl::Error> {
+        let _client = info_span!("client", id = client.id()).entered();
+        // Finish the current input on Ctrl-C and save the campaign instead of
+        // dying mid-iteration
+        if let Err(err) = ctrlc::set_handler(AptosFuzzerState::interrupt) {
+            warn!(%err, "failed to install the interrupt handler");
+        }
+        // A respawned client gets its previous state back from the manager
+        let (mut state, fresh) = match state {
+            Some(mut state) => {
+                state.restore_aptos_state();
+                (state, false)
+            }
+            None => (setup.new_state()?, true),
+        };
+
+        let mut executor = setup.executor(&state);
+        let mut feedback = setup.feedback(&executor);
+        if fresh {
+            feedback.init_state(&mut state)?;
+            state.load_history(&cli.corpus_dir);
+        }
+        let mut fuzzer = StdFuzzer::new(QueueScheduler::new(), feedback, setup.objective());
+        let mut stages = tuple_list!(StdMutationalStage::new(setup.mutator(&mut state)?));
+
+        if fresh && !cli.package_only {
+            info!(corpus = state.corpus().count(), "starting fuzzing loop");
+
+            // Prefer adding initial seeds via fuzzer.add_input to fire events and reflect in monitor
+            let mut initial_inputs = state.take_initial_inputs();
+            if let Some(dir) = &cli.source_path {
+                let seeds = source_seeds(dir, &initial_inputs);
+                info!(seeds = seeds.len(), "seeded from Move sources");
+                initial_inputs.extend(seeds);
+            }
+            // One client seeds from chain; the others get the inputs it finds
+            if let (0, Some(limit), Some(fork), Some(indexer_url)) =
+                (client.id(), cli.seed_from_chain, &fork, &cli.indexer_url)
+            {
+                match onchain_seeds(fork, indexer_url, &initial_inputs, limit) {
+                    Ok(seeds) => {
+                        info!(seeds = seeds.len(), "seeded from on-chain transactions");
+                        initial_inputs.extend(seeds);
+                    }
+                    Err(err) => warn!(err = %format!("{err:#}"), "failed to seed from on-chain transactions"),
+                }
+            }
+            if cli.sequence_templates {
+                let seeds = template_seeds(&initial_inputs);
+                info!(seeds = seeds.len(), "seeded from sequence templates");
+                initial_inputs.extend(seeds);
+            }
+            let saved_inputs = AptosFuzzerState::load_inputs_from_dir(&cli.corpus_dir);
+            if !saved_inputs.is_empty() {
+                info!(
+                    inputs = saved_inputs.len(),
+                    corpus_dir = %cli.corpus_dir.display(),
+                    "resuming from saved corpus"
+                );
+                initial_inputs.extend(saved_inputs);
+            }
+            for input in initial_inputs {
+                fuzzer.add_input(&mut state, &mut executor, &mut mgr, input)?;
+            }
+        }
+
+        // Save the feedback history regularly so a later campaign resuming
+        // from the corpus directory keeps it
+        let history_path = cli.corpus_dir.join(format!(".history-{}", client.id()));
+        let mut solutions = state.solutions().count();
+        if cli.package_only {
+            let summary_path = cli.corpus_dir.join(format!("{PACKAGE_SUMMARY_PREFIX}-{}", client.id()));
+            let per_function = Duration::from_secs(cli.function_budget_secs);
+            // Clients split the functions, highest scores first; inputs found
+            // for earlier ones stay in the corpus for the sequence mutator to
+            // combine
+            let targets = state.package_entry_functions(bare_vm);
+            let fuzzable: Vec<EntryTarget> = targets
+                .iter()
+                .filter(|target| target.unsupported.is_none())
+                .cloned()
+                .collect();
+            for target in targets.into_iter().skip(client.id()).step_by(cores.ids.len()) {
+                if state
+                    .metadata_or_insert_with(PackageSummaryMetadata::default)
+                    .is_done(&target)
+                {
+                    continue;
+                }
+
+                let summary = if target.unsupported.is_some() {
+                    FunctionSummary::skipped(target)
+                } else {
+                    let budget = budget_share(&target, &fuzzable, per_function);
+                    send_stat(&mut mgr, &mut state, "function", target.to_string())?;
+                    let started = Instant::now();
+                    let executions = *state.executions();
+                    let corpus_entries = state.corpus().count();
+                    let solutions = state.solutions().count();
+                    let mut seeds = state.entry_function_seeds(&target);
+                    if let Some(dir) = &cli.source_path {
+                        seeds.extend(source_seeds(dir, &seeds));
+                    }
+                    for input in seeds {
+                        fuzzer.add_input(&mut state, &mut executor, &mut mgr, input)?;
+                    }
+                    while started.elapsed() < budget && !AptosFuzzerState::interrupted() {
+                        allow_interrupt(fuzzer.fuzz_loop_for(
+                            &mut stages,
+                            &mut executor,
+                            &mut state,
+                            &mut mgr,
+                            BUDGET_CHECK_INTERVAL,
+                        ))?;
+                        setup.upgrade_when_due(&mut state)?;
+                        send_campaign_stats(&mut mgr, &mut state, &mut solutions)?;
+                    }
+                    FunctionSummary {
+                        target,
+                        executions: *state.executions() - executions,
+                        corpus_entries: state.corpus().count() - corpus_entries,
+                        solutions: state.solutions().count() - solutions,
+                        seconds: started.elapsed().as_secs(),
+                    }
+                };
+                if AptosFuzzerState::interrupted() {
+                    // Left undone, so a resumed campaign fuzzes it again
+                    break;
+                }
+                info!(%summary, "function done");
+                let metadata = state.metadata_or_insert_with(PackageSummaryMetadata::default);
+                metadata.functions.push(summary);
+                metadata.save(&summary_path)?;
+                state.save_history(&history_path)?;
+            }
+            if AptosFuzzerState::interrupted() {
+                state.save_history(&history_path)?;
+                log_interrupted(&state);
+            }
+            mgr.send_exiting()?;
+            return Ok(());
+        }
+        while !AptosFuzzerState::interrupted() {
+            allow_interrupt(fuzzer.fuzz_loop_for(
+                &mut stages,
+                &mut executor,
+                &mut state,
+                &mut mgr,
+                HISTORY_SAVE_INTERVAL,
+            ))?;
+            setup.upgrade_when_due(&mut state)?;
+            state.save_history(&history_path)?;
+            send_campaign_stats(&mut mgr, &mut state, &mut solutions)?;
+        }
+        log_interrupted(&state);
+        mgr.send_exiting()?;
+        Ok(())
+    };
+
+    let shmem_provider = StdShMemProvider::new().expect("failed to initialize shared memory");
+    let launched = if cli.tui {
+        let monitor = TuiMonitor::builder()
+            .title("libafl-aptos")
+            .version(env!("CARGO_PKG_VERSION"))
+            .enhanced_graphics(true)
+            .build();
+        Launcher::builder()
+            .shmem_provider(shmem_provider)
+            .configuration(EventConfig::from_name("libafl-aptos"))
+            .monitor(monitor)
+            .run_client(&mut run_client)
+            .cores(&cores)
+            .broker_port(cli.broker_port)
+            .stdout_file(Some(TUI_LOG_FILE))
+            .stderr_file(Some(TUI_LOG_FILE))
+            .build()
+            .launch()
+    } else {
+        let monitor = MultiMonitor::new(|s| info!(target: "monitor", "{s}"));
+        Launcher::builder()
+            .shmem_provider(shmem_provider)
+            .configuration(EventConfig::from_name("libafl-aptos"))
+            .monitor(monitor)
+            .run_client(&mut run_client)
+            .cores(&cores)
+            .broker_port(cli.broker_port)
+            .build()
+            .launch()
+    };
+    match launched {
+        Ok(()) | Err(libafl::Error::ShuttingDown) => {}
+        Err(err) => panic!("Fuzzing failed: {err:?}"),
+    }
 }
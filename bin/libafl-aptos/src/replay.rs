@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use aptos_fuzzer::{AptosCall, AptosFuzzerInput, AptosFuzzerState, AptosMoveExecutor};
+use aptos_move_core_types::vm_status::VMStatus;
+use aptos_types::transaction::{ExecutionStatus, TransactionPayload, TransactionStatus};
+use libafl::inputs::Input;
+
+/// Execute a saved input once against a fresh state and print what happened.
+/// With `export_state` the state left by the sequence is exported there, so
+/// setup transactions only have to run once.
+pub fn run(solution: &Path, mut state: AptosFuzzerState, export_state: Option<&Path>) {
+    let input = AptosFuzzerInput::from_file(solution)
+        .unwrap_or_else(|e| panic!("failed to load input {}: {e}", solution.display()));
+    let mut executor: AptosMoveExecutor<(), ()> = AptosMoveExecutor::new();
+    if state.has_custom_environment() {
+        executor = executor.with_environment_of(state.aptos_state());
+    }
+
+    println!("=== Reproduction report: {} ===", solution.display());
+    for entry in input.tables() {
+        println!(
+            "Table override: {} key 0x{} = 0x{}",
+            entry.handle,
+            hex::encode(&entry.key),
+            hex::encode(&entry.value)
+        );
+        state
+            .aptos_state_mut()
+            .set_table_entry(entry.handle, &entry.key, entry.value.clone());
+    }
+    println!("Calls: {}", input.calls().len());
+    for (index, call) in input.calls().iter().enumerate() {
+        println!("--- Call {index} ---");
+        report_call(&mut executor, &mut state, call);
+    }
+
+    if let Some(path) = export_state {
+        match state.aptos_state().export_snapshot(path) {
+            Ok(()) => println!("State exported to {}", path.display()),
+            Err(err) => eprintln!("Failed to export state to {}: {err:#}", path.display()),
+        }
+    }
+}
+
+/// Print one call of the sequence, execute it and apply its writes so later
+/// calls see them, as during fuzzing
+fn report_call(executor: &mut AptosMoveExecutor<(), ()>, state: &mut AptosFuzzerState, call: &AptosCall) {
+    match call.payload() {
+        TransactionPayload::EntryFunction(entry) => {
+            println!("Function: {}::{}", entry.module(), entry.function());
+            if !entry.ty_args().is_empty() {
+                let ty_args: Vec<String> = entry.ty_args().iter().map(|ty| ty.to_string()).collect();
+                println!("Type arguments: <{}>", ty_args.join(", "));
+            }
+        }
+        TransactionPayload::Multisig(multisig) => match call.entry_function() {
+            Some(entry) => println!(
+                "Function: {}::{} (via multisig {})",
+                entry.module(),
+                entry.function(),
+                multisig.multisig_address.to_hex_literal()
+            ),
+            None => println!("Multisig payload: {}", multisig.multisig_address.to_hex_literal()),
+        },
+        TransactionPayload::Script(_) => println!("Script payload"),
+        _ => println!("Unsupported payload"),
+    }
+    match call.sender() {
+        Some(sender) => println!("Sender: {}", sender.to_hex_literal()),
+        None => println!("Sender: <default>"),
+    }
+    for signer in call.secondary_signers() {
+        println!("Secondary signer: {}", signer.to_hex_literal());
+    }
+    if let Some(fee_payer) = call.fee_payer() {
+        println!("Fee payer: {}", fee_payer.to_hex_literal());
+    }
+    for (i, arg) in call.decoded_args().iter().enumerate() {
+        println!("Arg {i}: {arg}");
+    }
+
+    let (result, outcome, pcs, shifts) = executor.execute_call(call, state.aptos_state());
+
+    println!("Outcome: {:?}", outcome);
+    println!("Instructions executed: {}", pcs.len());
+    println!(
+        "Shifts: {} ({} lost high bits)",
+        shifts.len(),
+        shifts.iter().filter(|shift| shift.lost_high_bits).count()
+    );
+    for shift in shifts.iter().filter(|shift| shift.lost_high_bits) {
+        println!("  lossy shift at {shift}");
+    }
+
+    match result {
+        Ok(result) => {
+            match &result.status {
+                TransactionStatus::Keep(ExecutionStatus::MoveAbort { location, code, .. }) => {
+                    println!("Status: aborted with code {code} at {location:?}")
+                }
+                status => println!("Status: {status:?}"),
+            }
+            println!("Events: {}", result.events.len());
+            for event in &result.events {
+                println!("  {}", event.type_tag());
+            }
+            println!("Write set:");
+            for (state_key, write_op) in result.write_set.write_op_iter() {
+                println!("  {state_key:?}: {write_op:?}");
+            }
+            state.aptos_state_mut().apply_write_set(&result.write_set);
+        }
+        Err(VMStatus::MoveAbort(location, code)) => println!("Status: aborted with code {code} at {location:?}"),
+        Err(status) => println!("Status: {status:?}"),
+    }
+}
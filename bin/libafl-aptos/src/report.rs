@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aptos_fuzzer::campaign::{describe_calls, triggers};
+use aptos_fuzzer::{
+    AbortPolicy, AptosFuzzerInput, AptosFuzzerState, AptosMoveExecutor, Campaign, FindingsDb, PackageSummaryMetadata,
+};
+use libafl::inputs::Input;
+
+/// Summarize a campaign: the corpus size and what every solution triggers
+/// when executed again on `executor`, with aborts classified by
+/// `abort_policy` if given. With `findings_db` each thing triggered is
+/// recorded there as a finding of the campaign.
+pub fn run(
+    corpus_dir: &Path,
+    solutions_dir: &Path,
+    mut state: AptosFuzzerState,
+    abort_policy: Option<&AbortPolicy>,
+    mut executor: AptosMoveExecutor<(), ()>,
+    findings_db: Option<(&FindingsDb, &Campaign)>,
+) {
+    let corpus = AptosFuzzerState::load_inputs_from_dir(corpus_dir);
+    println!("=== Campaign report ===");
+    println!("Corpus: {} inputs in {}", corpus.len(), corpus_dir.display());
+
+    let mut functions = PackageSummaryMetadata::load_dir(corpus_dir);
+    if !functions.is_empty() {
+        functions.sort_by(|a, b| b.target.score.cmp(&a.target.score));
+        println!("Package functions by score: {}", functions.len());
+        for summary in functions {
+            println!("  {summary}");
+        }
+    }
+
+    let mut new_findings = 0;
+    let solutions = solution_files(solutions_dir);
+    println!("Solutions: {} in {}", solutions.len(), solutions_dir.display());
+
+    if state.has_custom_environment() {
+        executor = executor.with_environment_of(state.aptos_state());
+    }
+    for path in solutions {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let input = match AptosFuzzerInput::from_file(&path) {
+            Ok(input) => input,
+            Err(err) => {
+                println!("{name}: unreadable ({err})");
+                continue;
+            }
+        };
+        let mut findings = match triggers(&mut executor, &mut state, &input, abort_policy) {
+            Ok(findings) => findings,
+            Err(err) => {
+                println!("{name}: execution failed ({err})");
+                continue;
+            }
+        };
+        let calls = describe_calls(&input);
+        if let Some((db, campaign)) = findings_db {
+            for finding in &findings {
+                let artifact = path.display().to_string();
+                match db.record(campaign, finding, &calls, finding, Some(&artifact)) {
+                    Ok(true) => new_findings += 1,
+                    Ok(false) => {}
+                    Err(err) => eprintln!("Failed to record finding of {name}: {err:#}"),
+                }
+            }
+        }
+        if findings.is_empty() {
+            findings.push("no longer reproduces".to_string());
+        }
+        println!("{name}: {calls} -> {}", findings.join("; "));
+    }
+
+    if let Some((_, campaign)) = findings_db {
+        println!("Recorded as campaign {}: {new_findings} new finding(s)", campaign.id);
+    }
+}
+
+/// Input files of a solutions directory, skipping libafl metadata and lock
+/// files
+fn solution_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file() &&
+                path.file_name()
+                    .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    files.sort();
+    files
+}
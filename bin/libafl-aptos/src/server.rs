@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aptos_fuzzer::executor::ForkConfig;
+use aptos_fuzzer::{
+    AbortPolicy, CampaignFinding, CampaignHandle, CampaignSummary, Detector, EventInvariants, FeatureFlags,
+    FuzzCampaign, GasMode, ViewChecks, WriteRules,
+};
+use aptos_move_core_types::account_address::AccountAddress;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Executions before the upgrade when `upgrade_after` isn't given, as for
+/// `--upgrade-after`
+const DEFAULT_UPGRADE_AFTER: u64 = 100_000;
+
+/// A campaign to start, as the `start` method's params
+#[derive(Debug, Deserialize)]
+struct StartParams {
+    abi_path: Option<PathBuf>,
+    module_path: Option<PathBuf>,
+    script_path: Option<PathBuf>,
+    #[serde(default)]
+    senders: Vec<String>,
+    fork_url: Option<String>,
+    fork_version: Option<u64>,
+    corpus_dir: Option<PathBuf>,
+    solutions_dir: Option<PathBuf>,
+    framework_bundle: Option<PathBuf>,
+    state_snapshot: Option<PathBuf>,
+    table_fixture: Option<PathBuf>,
+    /// As `--gas-schedule`
+    gas_schedule: Option<String>,
+    /// As `--features`
+    features: Option<String>,
+    upgrade_module_path: Option<PathBuf>,
+    upgrade_after: Option<u64>,
+    #[serde(default)]
+    persist_writes: bool,
+    abort_policy: Option<PathBuf>,
+    event_invariants: Option<PathBuf>,
+    write_rules: Option<PathBuf>,
+    view_checks: Option<PathBuf>,
+    #[serde(default)]
+    compare_bare_vm: bool,
+    detectors: Option<Vec<Detector>>,
+    timeout_ms: Option<u64>,
+    gas_budget: Option<u64>,
+    instruction_ceiling: Option<u64>,
+    max_executions: Option<u64>,
+    max_duration_secs: Option<u64>,
+}
+
+impl StartParams {
+    fn into_campaign(self) -> anyhow::Result<FuzzCampaign> {
+        let mut builder = FuzzCampaign::builder();
+        if let Some(path) = self.abi_path {
+            builder = builder.with_abi_path(path);
+        }
+        if let Some(path) = self.module_path {
+            builder = builder.with_module_path(path);
+        }
+        if let Some(path) = self.script_path {
+            builder = builder.with_script_path(path);
+        }
+        if !self.senders.is_empty() {
+            let senders = self
+                .senders
+                .iter()
+                .map(|s| AccountAddress::from_str(s).map_err(|e| anyhow::anyhow!("invalid sender {s}: {e}")))
+                .collect::<anyhow::Result<_>>()?;
+            builder = builder.with_senders(senders);
+        }
+        if let Some(url) = self.fork_url {
+            builder = builder.with_fork(ForkConfig {
+                url,
+                version: self.fork_version,
+            });
+        }
+        if let Some(dir) = self.corpus_dir {
+            builder = builder.with_corpus_dir(dir);
+        }
+        if let Some(dir) = self.solutions_dir {
+            builder = builder.with_solutions_dir(dir);
+        }
+        if let Some(path) = self.framework_bundle {
+            builder = builder.with_framework_bundle(path);
+        }
+        if let Some(path) = self.state_snapshot {
+            builder = builder.with_state_snapshot(path);
+        }
+        if let Some(path) = self.table_fixture {
+            builder = builder.with_table_fixture(path);
+        }
+        if let Some(schedule) = self.gas_schedule {
+            builder = builder.with_gas_mode(GasMode::from_str(&schedule).map_err(anyhow::Error::msg)?);
+        }
+        if let Some(features) = self.features {
+            let FeatureFlags(features) = FeatureFlags::from_str(&features).map_err(anyhow::Error::msg)?;
+            builder = builder.with_features(features);
+        }
+        if let Some(path) = self.upgrade_module_path {
+            builder = builder.with_upgrade(path, self.upgrade_after.unwrap_or(DEFAULT_UPGRADE_AFTER));
+        }
+        builder = builder
+            .with_persist_writes(self.persist_writes)
+            .with_bare_vm_comparison(self.compare_bare_vm);
+        if let Some(path) = self.abort_policy {
+            builder = builder.with_abort_policy(AbortPolicy::from_file(&path)?);
+        }
+        if let Some(path) = self.event_invariants {
+            builder = builder.with_event_invariants(EventInvariants::from_file(&path)?);
+        }
+        if let Some(path) = self.write_rules {
+            builder = builder.with_write_rules(WriteRules::from_file(&path)?);
+        }
+        if let Some(path) = self.view_checks {
+            builder = builder.with_view_checks(ViewChecks::from_file(&path)?);
+        }
+        if let Some(detectors) = self.detectors {
+            builder = builder.with_detectors(&detectors);
+        }
+        if let Some(timeout_ms) = self.timeout_ms {
+            builder = builder.with_timeout(Duration::from_millis(timeout_ms));
+        }
+        if let Some(gas_budget) = self.gas_budget {
+            builder = builder.with_gas_budget(gas_budget);
+        }
+        if let Some(ceiling) = self.instruction_ceiling {
+            builder = builder.with_instruction_ceiling(ceiling);
+        }
+        if let Some(executions) = self.max_executions {
+            builder = builder.with_max_executions(executions);
+        }
+        if let Some(secs) = self.max_duration_secs {
+            builder = builder.with_max_duration(Duration::from_secs(secs));
+        }
+        builder.build()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CampaignParams {
+    campaign: u64,
+    /// Skip the findings already pulled
+    #[serde(default)]
+    since: usize,
+}
+
+/// A campaign started by the server, with the findings it sent so far
+struct ManagedCampaign {
+    started: Instant,
+    handle: Option<CampaignHandle>,
+    findings: Vec<CampaignFinding>,
+    outcome: Option<Result<CampaignSummary, String>>,
+}
+
+impl ManagedCampaign {
+    /// Collect the findings sent since the last call, and the outcome once
+    /// the campaign has finished
+    fn poll(&mut self) {
+        let Some(handle) = &self.handle else {
+            return;
+        };
+        self.findings.extend(handle.findings().try_iter());
+        if handle.is_finished() {
+            let handle = self.handle.take().expect("checked above");
+            self.findings.extend(handle.findings().try_iter());
+            self.outcome = Some(handle.join().map_err(|e| format!("{e:#}")));
+        }
+    }
+
+    fn status(&self, id: u64) -> Value {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let (executions, corpus_entries, solutions) = match (&self.handle, &self.outcome) {
+            (Some(handle), _) => (
+                handle.progress().executions(),
+                handle.progress().corpus_entries(),
+                handle.progress().solutions(),
+            ),
+            (None, Some(Ok(summary))) => (summary.executions, summary.corpus_entries, summary.solutions),
+            (None, _) => (0, 0, 0),
+        };
+        json!({
+            "campaign": id,
+            "running": self.handle.is_some(),
+            "executions": executions,
+            "execs_per_sec": if elapsed > 0.0 { executions as f64 / elapsed } else { 0.0 },
+            "corpus_entries": corpus_entries,
+            "solutions": solutions,
+            "findings": self.findings.len(),
+            "error": match &self.outcome {
+                Some(Err(error)) => Some(error),
+                _ => None,
+            },
+        })
+    }
+}
+
+#[derive(Default)]
+struct Campaigns {
+    next_id: u64,
+    campaigns: BTreeMap<u64, ManagedCampaign>,
+}
+
+/// Serve newline-delimited JSON-RPC 2.0 on `addr` for a controller to
+/// start, stop and watch campaigns and pull their findings. There is no
+/// authentication: bind it to an address only the controller reaches. A
+/// bare port is bound on localhost.
+///
+/// Methods: `start` (a campaign's settings, returns its id; its corpus and
+/// solutions go to `corpus/<id>` and `solutions/<id>` unless given), `stop`,
+/// `status` and `findings` (`{"campaign": id, "since": n}`, returns the
+/// findings after the first `n` with their inputs as repro artifacts) and
+/// `list`.
+pub fn run(addr: &str) {
+    let addr = match addr.parse::<u16>() {
+        Ok(port) => format!("127.0.0.1:{port}"),
+        Err(_) => addr.to_string(),
+    };
+    let listener = TcpListener::bind(&addr).unwrap_or_else(|e| panic!("failed to listen on {addr}: {e}"));
+    match listener.local_addr() {
+        Ok(local) if !local.ip().is_loopback() => {
+            warn!(%local, "control server reachable from other hosts, anyone reaching it can start campaigns")
+        }
+        _ => info!(addr, "control server listening"),
+    }
+    let campaigns = Arc::new(Mutex::new(Campaigns::default()));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                warn!(%err, "failed to accept connection");
+                continue;
+            }
+        };
+        let campaigns = campaigns.clone();
+        thread::spawn(move || {
+            if let Err(err) = serve_connection(stream, &campaigns) {
+                warn!(%err, "control connection failed");
+            }
+        });
+    }
+}
+
+fn serve_connection(stream: TcpStream, campaigns: &Mutex<Campaigns>) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(request) => {
+                let id = request.get("id").cloned().unwrap_or(Value::Null);
+                let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+                let params = request.get("params").cloned().unwrap_or(Value::Null);
+                match handle(campaigns, method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, message)) => {
+                        json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+                    }
+                }
+            }
+            Err(err) => json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": PARSE_ERROR, "message": err.to_string() },
+            }),
+        };
+        writeln!(writer, "{response}")?;
+    }
+    Ok(())
+}
+
+fn handle(campaigns: &Mutex<Campaigns>, method: &str, params: Value) -> Result<Value, (i64, String)> {
+    if method == "start" {
+        return start(campaigns, params);
+    }
+    let mut campaigns = lock(campaigns);
+    match method {
+        "stop" => {
+            let params = parse::<CampaignParams>(params)?;
+            let campaign = find(&mut campaigns, params.campaign)?;
+            if let Some(handle) = &campaign.handle {
+                handle.stop();
+            }
+            Ok(campaign.status(params.campaign))
+        }
+        "status" => {
+            let params = parse::<CampaignParams>(params)?;
+            Ok(find(&mut campaigns, params.campaign)?.status(params.campaign))
+        }
+        "findings" => {
+            let params = parse::<CampaignParams>(params)?;
+            let campaign = find(&mut campaigns, params.campaign)?;
+            let findings: Vec<Value> = campaign
+                .findings
+                .iter()
+                .skip(params.since)
+                .map(|finding| {
+                    json!({
+                        "calls": finding.calls,
+                        "triggers": finding.triggers,
+                        "executions": finding.executions,
+                        "elapsed_secs": finding.elapsed.as_secs_f64(),
+                        "input": finding.input,
+                    })
+                })
+                .collect();
+            Ok(json!(findings))
+        }
+        "list" => {
+            let statuses: Vec<Value> = campaigns
+                .campaigns
+                .iter_mut()
+                .map(|(id, campaign)| {
+                    campaign.poll();
+                    campaign.status(*id)
+                })
+                .collect();
+            Ok(json!(statuses))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method {method}"))),
+    }
+}
+
+/// Start a campaign, with its corpus and solutions in `corpus/<id>` and
+/// `solutions/<id>` unless given. The campaigns aren't locked while it is
+/// built, which may fetch the fork's feature flags.
+fn start(campaigns: &Mutex<Campaigns>, params: Value) -> Result<Value, (i64, String)> {
+    let mut params = parse::<StartParams>(params)?;
+    let id = {
+        let mut campaigns = lock(campaigns);
+        campaigns.next_id += 1;
+        campaigns.next_id
+    };
+    params
+        .corpus_dir
+        .get_or_insert_with(|| PathBuf::from("corpus").join(id.to_string()));
+    params
+        .solutions_dir
+        .get_or_insert_with(|| PathBuf::from("solutions").join(id.to_string()));
+    let campaign = params.into_campaign().map_err(|e| (INVALID_PARAMS, format!("{e:#}")))?;
+    info!(campaign = id, "starting campaign");
+    lock(campaigns).campaigns.insert(
+        id,
+        ManagedCampaign {
+            started: Instant::now(),
+            handle: Some(campaign.spawn()),
+            findings: Vec::new(),
+            outcome: None,
+        },
+    );
+    Ok(json!({ "campaign": id }))
+}
+
+fn lock(campaigns: &Mutex<Campaigns>) -> MutexGuard<'_, Campaigns> {
+    campaigns.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+fn find(campaigns: &mut Campaigns, id: u64) -> Result<&mut ManagedCampaign, (i64, String)> {
+    let campaign = campaigns
+        .campaigns
+        .get_mut(&id)
+        .ok_or_else(|| (INVALID_PARAMS, format!("no campaign {id}")))?;
+    campaign.poll();
+    Ok(campaign)
+}
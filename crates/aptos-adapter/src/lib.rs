@@ -0,0 +1,517 @@
+pub mod mutation;
+pub mod network;
+pub mod types;
+
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use aptos_crypto::HashValue;
+use aptos_fuzzer::executor::aptos_custom_state::AptosCustomState;
+use aptos_fuzzer::executor::custom_state_view::CustomStateView;
+use aptos_fuzzer::executor::{bare_vm, ForkConfig, RestStateFetcher, TransactionResult};
+use aptos_fuzzer::AptosMoveExecutor;
+use aptos_move_binary_format::access::ModuleAccess;
+use aptos_move_binary_format::file_format::SignatureToken;
+use aptos_move_binary_format::CompiledModule;
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::identifier::Identifier;
+use aptos_move_core_types::language_storage::{ModuleId, TypeTag};
+use aptos_move_core_types::vm_status::VMStatus;
+use aptos_types::access_path::Path as AccessPathKind;
+use aptos_types::chain_id::ChainId;
+use aptos_types::state_store::state_key::inner::StateKeyInner;
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::transaction::{EntryFunction, ExecutionStatus, TransactionPayload, TransactionStatus};
+use aptos_vm::AptosVM;
+use async_trait::async_trait;
+use fuzzer_core::writes::WritePolicy;
+use fuzzer_core::{ChainAdapter, EndpointStats, FunctionInfo, FuzzerConfig, ObjectChange, Parameter, ViolationInfo};
+use tracing::{debug, info};
+
+use crate::mutation::AptosMutator;
+pub use crate::network::Network;
+pub use crate::types::{AptosExecutionResult, AptosValue};
+
+/// Gas the view function call returning the values compared with the bare
+/// VM's may use
+const VIEW_MAX_GAS_AMOUNT: u64 = 2_000_000;
+
+/// Aptos implementation of the fuzzer-core [`ChainAdapter`]
+///
+/// Executes entry functions against an in-memory [`AptosCustomState`] using
+/// the same VM as the libafl fuzzer, so Aptos targets share the cache,
+/// reporting and invariant machinery of `CoreFuzzer`.
+pub struct AptosAdapter {
+    executor: Mutex<AptosMoveExecutor<(), ()>>,
+    state: Mutex<AptosCustomState>,
+    /// Fullnode the state is forked from, if any
+    fork: Option<Arc<RestStateFetcher>>,
+    /// Also call each input in a bare MoveVM session and compare outcomes
+    compare_bare_vm: bool,
+    /// Resource types the transaction may write; any if unset
+    write_policy: Option<WritePolicy>,
+}
+
+impl AptosAdapter {
+    pub fn new() -> Self {
+        Self {
+            executor: Mutex::new(AptosMoveExecutor::new()),
+            state: Mutex::new(AptosCustomState::new_default()),
+            fork: None,
+            compare_bare_vm: false,
+            write_policy: None,
+        }
+    }
+
+    /// Fork state from `network` at ledger `version`, or its latest one.
+    /// Modules deployed afterwards shadow the network's.
+    pub fn with_network(mut self, network: &Network, version: Option<u64>) -> Result<Self> {
+        let fetcher = Arc::new(RestStateFetcher::new(&ForkConfig {
+            url: network.rest_url.clone(),
+            version,
+        })?);
+        info!("Forking {} at version {}", network, fetcher.version());
+        let mut state = AptosCustomState::new_forked(fetcher.clone());
+        if let Some(chain_id) = network.chain_id {
+            state.set_chain_id(ChainId::new(chain_id))?;
+        }
+        // Run on the network's features and gas schedule
+        let executor = AptosMoveExecutor::new().with_environment_of(&state);
+        *self
+            .executor
+            .lock()
+            .map_err(|_| anyhow!("Aptos executor lock poisoned"))? = executor;
+        *self.state.lock().map_err(|_| anyhow!("Aptos state lock poisoned"))? = state;
+        self.fork = Some(fetcher);
+        Ok(self)
+    }
+
+    /// Deploy a compiled module into the adapter state
+    pub fn with_module_bytes(self, bytes: Vec<u8>) -> Result<Self> {
+        let module = CompiledModule::deserialize(&bytes).map_err(|e| anyhow!("Invalid module bytecode: {:?}", e))?;
+        info!("Deploying module {}", module.self_id());
+        self.state
+            .lock()
+            .map_err(|_| anyhow!("Aptos state lock poisoned"))?
+            .deploy_module_bytes(module.self_id(), bytes);
+        Ok(self)
+    }
+
+    /// Deploy a compiled module (`.mv`) from disk
+    pub fn with_module_path(self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("Failed to read module {}", path.display()))?;
+        self.with_module_bytes(bytes)
+    }
+
+    /// Call every input's function in a bare MoveVM session too, see
+    /// [`bare_vm::execute_function`], and report outcomes differing from the
+    /// transaction's as divergences. Both run the same bytecode on the same
+    /// state, so these point at the argument encoding or at natives and
+    /// setup of the transaction path rather than at the target.
+    ///
+    /// Transactions discard return values, so those of `#[view]` functions
+    /// are taken from the VM's view function API instead and compared with
+    /// the bare VM's byte for byte, reported as return divergences.
+    pub fn with_bare_vm_comparison(mut self) -> Self {
+        self.compare_bare_vm = true;
+        self
+    }
+
+    /// Report writes to resource types `policy` doesn't allow
+    pub fn with_write_policy(mut self, policy: WritePolicy) -> Self {
+        self.write_policy = Some(policy);
+        self
+    }
+
+    fn module_id(function: &FunctionInfo) -> Result<ModuleId> {
+        let address = AccountAddress::from_str(&function.package_id)
+            .map_err(|e| anyhow!("Invalid package address '{}': {}", function.package_id, e))?;
+        Ok(ModuleId::new(address, Identifier::new(function.module_name.as_str())?))
+    }
+
+    fn load_module(&self, module_id: &ModuleId) -> Result<CompiledModule> {
+        let state = self.state.lock().map_err(|_| anyhow!("Aptos state lock poisoned"))?;
+        let state_value = state
+            .get_state_value(&StateKey::module(module_id.address(), module_id.name()))
+            .ok_or_else(|| anyhow!("Module {} is not deployed", module_id))?;
+        CompiledModule::deserialize(state_value.bytes())
+            .map_err(|e| anyhow!("Failed to deserialize module {}: {:?}", module_id, e))
+    }
+
+    /// Parameter types of an entry function, excluding leading signers
+    fn parameter_types(module: &CompiledModule, function_name: &str) -> Result<Vec<TypeTag>> {
+        let function_def = module
+            .function_defs()
+            .iter()
+            .find(|def| {
+                let handle = module.function_handle_at(def.function);
+                module.identifier_at(handle.name).as_str() == function_name
+            })
+            .ok_or_else(|| anyhow!("Function {} not found in {}", function_name, module.self_id()))?;
+        let handle = module.function_handle_at(function_def.function);
+
+        module
+            .signature_at(handle.parameters)
+            .0
+            .iter()
+            .filter(|token| !Self::is_signer(token))
+            .map(Self::token_to_type_tag)
+            .collect()
+    }
+
+    fn is_signer(token: &SignatureToken) -> bool {
+        match token {
+            SignatureToken::Signer => true,
+            SignatureToken::Reference(inner) => matches!(inner.as_ref(), SignatureToken::Signer),
+            _ => false,
+        }
+    }
+
+    fn token_to_type_tag(token: &SignatureToken) -> Result<TypeTag> {
+        Ok(match token {
+            SignatureToken::Bool => TypeTag::Bool,
+            SignatureToken::U8 => TypeTag::U8,
+            SignatureToken::U16 => TypeTag::U16,
+            SignatureToken::U32 => TypeTag::U32,
+            SignatureToken::U64 => TypeTag::U64,
+            SignatureToken::U128 => TypeTag::U128,
+            SignatureToken::U256 => TypeTag::U256,
+            SignatureToken::Address => TypeTag::Address,
+            SignatureToken::Vector(inner) => TypeTag::Vector(Box::new(Self::token_to_type_tag(inner)?)),
+            other => bail!("Unsupported parameter type: {:?}", other),
+        })
+    }
+
+    /// Call `entry` in the bare VM and describe how its outcome differs from
+    /// the transaction's, and its return values from the view function's
+    fn compare_with_bare_vm(
+        state: &AptosCustomState,
+        entry: &EntryFunction,
+        sender: AccountAddress,
+        location: &str,
+        result: &Result<TransactionResult, VMStatus>,
+    ) -> Vec<ViolationInfo> {
+        let bare = bare_vm::execute_function(state, entry, sender).map_err(|error| error.into_vm_status());
+        let mut divergences = Vec::new();
+        if let Some(divergence) = bare_vm::divergence(entry, result, &bare) {
+            debug!(%divergence, "outcome differs from the bare VM");
+            divergences.push(ViolationInfo {
+                location: location.to_string(),
+                operation: format!("BareVmDivergence({} vs {})", divergence.transaction, divergence.bare),
+                left_operand: 0,
+                right_operand: 0,
+                call_chain: Vec::new(),
+            });
+        }
+        if let Some(returned) = bare.as_ref().ok().filter(|returned| !returned.is_empty()) {
+            divergences.extend(Self::compare_return_values(state, entry, location, returned));
+        }
+        divergences
+    }
+
+    /// Call `entry` as a view function and report the first return value
+    /// whose encoding differs from the bare VM's `returned`. Functions the
+    /// view function API refuses, such as ones not marked `#[view]`, aren't
+    /// compared.
+    fn compare_return_values(
+        state: &AptosCustomState,
+        entry: &EntryFunction,
+        location: &str,
+        returned: &[Vec<u8>],
+    ) -> Option<ViolationInfo> {
+        let output = AptosVM::execute_view_function(
+            &CustomStateView::new(state),
+            entry.module().clone(),
+            entry.function().to_owned(),
+            entry.ty_args().to_vec(),
+            entry.args().to_vec(),
+            VIEW_MAX_GAS_AMOUNT,
+        );
+        let Ok(viewed) = output.values else {
+            return None;
+        };
+        if viewed.len() != returned.len() {
+            return Some(ViolationInfo {
+                location: location.to_string(),
+                operation: "ReturnDivergence(count)".to_string(),
+                left_operand: viewed.len() as u64,
+                right_operand: returned.len() as u64,
+                call_chain: Vec::new(),
+            });
+        }
+        let index = viewed.iter().zip(returned).position(|(viewed, bare)| viewed != bare)?;
+        debug!(index, viewed = ?viewed[index], bare = ?returned[index], "return value differs from the bare VM");
+        Some(ViolationInfo {
+            location: location.to_string(),
+            operation: format!(
+                "ReturnDivergence({index}: 0x{} vs 0x{})",
+                hex::encode(&viewed[index]),
+                hex::encode(&returned[index])
+            ),
+            left_operand: 0,
+            right_operand: 0,
+            call_chain: Vec::new(),
+        })
+    }
+
+    /// Types of the resources and resource groups a successful transaction
+    /// wrote; table items carry no type and are left out
+    fn written_types(result: &Result<TransactionResult, VMStatus>) -> Vec<String> {
+        let Ok(result) = result else {
+            return Vec::new();
+        };
+        result
+            .write_set
+            .write_op_iter()
+            .filter_map(|(state_key, _)| match state_key.inner() {
+                StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+                    AccessPathKind::Resource(tag) | AccessPathKind::ResourceGroup(tag) => Some(tag.to_string()),
+                    AccessPathKind::Code(_) => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn abort_code(result: &Result<TransactionResult, VMStatus>) -> Option<u64> {
+        match result {
+            Ok(result) => match &result.status {
+                TransactionStatus::Keep(ExecutionStatus::MoveAbort { code, .. }) => Some(*code),
+                _ => None,
+            },
+            Err(VMStatus::MoveAbort(_, code)) => Some(*code),
+            Err(_) => None,
+        }
+    }
+}
+
+#[async_trait]
+impl ChainAdapter for AptosAdapter {
+    type Value = AptosValue;
+    type Address = AccountAddress;
+    type ObjectId = AccountAddress;
+    type Object = Vec<u8>;
+    type ExecutionResult = AptosExecutionResult;
+    type Mutator = AptosMutator;
+
+    async fn resolve_function(&self, config: &FuzzerConfig) -> Result<FunctionInfo> {
+        info!(
+            "Resolving function: {}::{}::{}",
+            config.package_id, config.module_name, config.function_name
+        );
+
+        Ok(FunctionInfo {
+            package_id: config.package_id.clone(),
+            module_name: config.module_name.clone(),
+            function_name: config.function_name.clone(),
+            type_arguments: config.type_arguments.clone(),
+            compare_package_id: None,
+            invariants: Vec::new(),
+        })
+    }
+
+    async fn initialize_parameters(
+        &self,
+        function: &FunctionInfo,
+        args: &[String],
+    ) -> Result<Vec<Parameter<Self::Value>>> {
+        info!(
+            "Initializing parameters for function: {}::{}",
+            function.module_name, function.function_name
+        );
+
+        let module = self.load_module(&Self::module_id(function)?)?;
+        let param_types = Self::parameter_types(&module, &function.function_name)?;
+
+        let mut parameters = Vec::new();
+        for (index, type_tag) in param_types.iter().enumerate() {
+            let value = match args.get(index) {
+                Some(arg) => AptosValue::parse(arg, type_tag)?,
+                None => AptosValue::default_for(type_tag)?,
+            };
+
+            parameters.push(Parameter {
+                index,
+                name: format!("param_{}", index),
+                type_name: type_tag.to_string(),
+                value,
+            });
+        }
+
+        info!("Initialized {} parameters", parameters.len());
+        Ok(parameters)
+    }
+
+    async fn execute(
+        &self,
+        sender: &Self::Address,
+        function: &FunctionInfo,
+        params: &[Parameter<Self::Value>],
+    ) -> Result<Self::ExecutionResult> {
+        let module_id = Self::module_id(function)?;
+        let location = format!("{}::{}", module_id, function.function_name);
+        let ty_args = function
+            .type_arguments
+            .iter()
+            .map(|ty| TypeTag::from_str(ty).map_err(|e| anyhow!("Invalid type argument '{}': {}", ty, e)))
+            .collect::<Result<Vec<_>>>()?;
+        let args = params
+            .iter()
+            .map(|param| param.value.to_bcs_bytes())
+            .collect::<Result<Vec<_>>>()?;
+        let entry = EntryFunction::new(
+            module_id,
+            Identifier::new(function.function_name.as_str())?,
+            ty_args,
+            args,
+        );
+        let payload = TransactionPayload::EntryFunction(entry.clone());
+
+        let state = self.state.lock().map_err(|_| anyhow!("Aptos state lock poisoned"))?;
+        let mut executor = self
+            .executor
+            .lock()
+            .map_err(|_| anyhow!("Aptos executor lock poisoned"))?;
+
+        let start = Instant::now();
+        let (result, _outcome, pcs, shifts) = executor.execute_transaction(payload, &state, Some(*sender));
+        let execution_time = start.elapsed();
+        let divergences = self
+            .compare_bare_vm
+            .then(|| Self::compare_with_bare_vm(&state, &entry, *sender, &location, &result))
+            .unwrap_or_default();
+        let write_violations = self
+            .write_policy
+            .as_ref()
+            .map(|policy| {
+                let written = Self::written_types(&result);
+                policy.violations(&location, written.iter().map(String::as_str))
+            })
+            .unwrap_or_default();
+
+        let abort_code = Self::abort_code(&result);
+        let error = result.as_ref().err().map(|status| format!("{:?}", status));
+        debug!("Executed {} instructions, abort code {:?}", pcs.len(), abort_code);
+
+        Ok(AptosExecutionResult {
+            location,
+            success: result.is_ok() && abort_code.is_none(),
+            abort_code,
+            error,
+            lossy_shifts: shifts.into_iter().filter(|shift| shift.lost_high_bits).collect(),
+            divergences,
+            write_violations,
+            instructions_executed: pcs.len() as u64,
+            execution_time,
+        })
+    }
+
+    fn has_violations(&self, result: &Self::ExecutionResult) -> bool {
+        !result.lossy_shifts.is_empty() || !result.divergences.is_empty() || !result.write_violations.is_empty()
+    }
+
+    fn extract_violations(&self, result: &Self::ExecutionResult) -> Vec<ViolationInfo> {
+        result
+            .lossy_shifts
+            .iter()
+            .map(|shift| ViolationInfo {
+                location: format!("{}::{}:{}", shift.module, shift.function, shift.pc),
+                operation: "ShiftOverflow".to_string(),
+                left_operand: shift.value.parse::<u64>().unwrap_or_default(),
+                right_operand: shift.shift_amount,
+                call_chain: vec![result.location.clone()],
+            })
+            .chain(result.divergences.iter().cloned())
+            .chain(result.write_violations.iter().cloned())
+            .collect()
+    }
+
+    fn execution_time(&self, result: &Self::ExecutionResult) -> Duration {
+        result.execution_time
+    }
+
+    /// Not gas: entry functions run without a gas meter, so this is the
+    /// number of instructions executed
+    fn gas_used(&self, result: &Self::ExecutionResult) -> u64 {
+        result.instructions_executed
+    }
+
+    fn extract_object_changes(
+        &self,
+        _result: &Self::ExecutionResult,
+    ) -> Vec<ObjectChange<Self::ObjectId, Self::Object>> {
+        // Aptos has no owned objects passed by reference as arguments
+        Vec::new()
+    }
+
+    fn get_sender_from_config(&self, config: &FuzzerConfig) -> Self::Address {
+        config
+            .sender
+            .as_deref()
+            .and_then(|sender| AccountAddress::from_str(sender).ok())
+            .unwrap_or(AccountAddress::ZERO)
+    }
+
+    fn compute_object_digest(&self, object: &Self::Object) -> Vec<u8> {
+        HashValue::sha3_256_of(object).to_vec()
+    }
+
+    fn update_value_with_cached_object(&self, _value: &mut Self::Value, _object: &Self::Object) -> Result<()> {
+        Ok(())
+    }
+
+    fn bytes_to_object_id(&self, bytes: &[u8]) -> Result<Self::ObjectId> {
+        AccountAddress::from_bytes(bytes).map_err(|e| anyhow!("Failed to convert bytes to address: {}", e))
+    }
+
+    fn object_id_to_bytes(&self, id: &Self::ObjectId) -> Vec<u8> {
+        id.to_vec()
+    }
+
+    fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.fork
+            .iter()
+            .map(|fork| EndpointStats {
+                url: fork.url().to_string(),
+                requests: fork.requests(),
+                failures: fork.failures(),
+                mean_latency_ms: fork.average_latency().unwrap_or_default().as_millis() as u64,
+                healthy: true,
+            })
+            .collect()
+    }
+
+    fn create_mutator(&self, seed: u64) -> Self::Mutator {
+        AptosMutator::new().with_seed(seed)
+    }
+}
+
+impl Default for AptosAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_type_tags() {
+        let token = SignatureToken::Vector(Box::new(SignatureToken::U8));
+        assert_eq!(
+            AptosAdapter::token_to_type_tag(&token).unwrap(),
+            TypeTag::Vector(Box::new(TypeTag::U8))
+        );
+        assert!(AptosAdapter::token_to_type_tag(&SignatureToken::Signer).is_err());
+        assert!(AptosAdapter::is_signer(&SignatureToken::Reference(Box::new(
+            SignatureToken::Signer
+        ))));
+        assert!(!AptosAdapter::is_signer(&SignatureToken::Address));
+    }
+}
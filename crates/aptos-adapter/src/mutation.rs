@@ -0,0 +1,111 @@
+use anyhow::Result;
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::u256::U256;
+use fuzzer_core::ChainMutationStrategy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::types::AptosValue;
+
+/// Mutator for Aptos entry function arguments
+///
+/// Integers are replaced with boundary values (0, 1, MAX-1, MAX),
+/// power-of-two patterns (2^n, 2^n±1) or uniformly random values; booleans
+/// are flipped, addresses randomized and vectors either grow, shrink or have
+/// one element mutated.
+pub struct AptosMutator {
+    rng: StdRng,
+}
+
+impl AptosMutator {
+    pub fn new() -> Self {
+        Self {
+            rng: StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Pick a new integer of `bits` width, returned as u128 (u256 handled
+    /// separately)
+    fn pick_integer(&mut self, bits: u32) -> u128 {
+        let max = if bits == 128 { u128::MAX } else { (1u128 << bits) - 1 };
+        match self.rng.random_range(0..3) {
+            0 => [0, 1, max - 1, max][self.rng.random_range(0..4)],
+            1 => {
+                let power = 1u128 << self.rng.random_range(0..bits);
+                match self.rng.random_range(0..3) {
+                    0 => power,
+                    1 => power - 1,
+                    _ => power.saturating_add(1) & max,
+                }
+            }
+            _ => self.rng.random::<u128>() & max,
+        }
+    }
+
+    fn pick_u256(&mut self) -> U256 {
+        match self.rng.random_range(0..3) {
+            0 => [
+                U256::zero(),
+                U256::one(),
+                U256::max_value() - U256::one(),
+                U256::max_value(),
+            ][self.rng.random_range(0..4)],
+            1 => U256::one() << self.rng.random_range(0..=255u8),
+            _ => {
+                let bytes: [u8; 32] = self.rng.random();
+                U256::from_le_bytes(&bytes)
+            }
+        }
+    }
+}
+
+impl ChainMutationStrategy<AptosValue> for AptosMutator {
+    fn mutate(&mut self, value: &mut AptosValue) -> Result<()> {
+        match value {
+            AptosValue::U8(v) => *v = self.pick_integer(8) as u8,
+            AptosValue::U16(v) => *v = self.pick_integer(16) as u16,
+            AptosValue::U32(v) => *v = self.pick_integer(32) as u32,
+            AptosValue::U64(v) => *v = self.pick_integer(64) as u64,
+            AptosValue::U128(v) => *v = self.pick_integer(128),
+            AptosValue::U256(v) => *v = self.pick_u256(),
+            AptosValue::Bool(v) => *v = !*v,
+            AptosValue::Address(addr) => {
+                *addr = if self.rng.random_bool(0.5) {
+                    AccountAddress::ZERO
+                } else {
+                    AccountAddress::new(self.rng.random())
+                };
+            }
+            AptosValue::Vector(vec) => match (self.rng.random_range(0..3), vec.first().cloned()) {
+                // Grow by duplicating an element, then mutate the copy
+                (0, Some(template)) => {
+                    let mut element = template;
+                    self.mutate(&mut element)?;
+                    vec.push(element);
+                }
+                (1, Some(_)) => {
+                    vec.pop();
+                }
+                (_, Some(_)) => {
+                    let index = self.rng.random_range(0..vec.len());
+                    self.mutate(&mut vec[index])?;
+                }
+                // Element type of an empty vector is unknown
+                (_, None) => {}
+            },
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for AptosMutator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+
+/// Network serving the Aptos fullnode REST API that an [`AptosAdapter`]
+/// forks state from: Aptos itself, Movement, or any other Move chain
+/// compatible with the API
+///
+/// [`AptosAdapter`]: crate::AptosAdapter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    pub name: String,
+    pub rest_url: String,
+    /// Chain ID the VM runs with instead of the one in the forked state, so
+    /// known networks run with theirs even if their state doesn't carry it
+    pub chain_id: Option<u8>,
+}
+
+impl Network {
+    pub fn aptos_mainnet() -> Self {
+        Self::custom("aptos-mainnet", "https://api.mainnet.aptoslabs.com/v1").with_chain_id(1)
+    }
+
+    pub fn aptos_testnet() -> Self {
+        Self::custom("aptos-testnet", "https://api.testnet.aptoslabs.com/v1").with_chain_id(2)
+    }
+
+    pub fn movement_mainnet() -> Self {
+        Self::custom("movement-mainnet", "https://mainnet.movementnetwork.xyz/v1").with_chain_id(126)
+    }
+
+    pub fn movement_testnet() -> Self {
+        Self::custom("movement-testnet", "https://testnet.bardock.movementnetwork.xyz/v1").with_chain_id(250)
+    }
+
+    pub fn custom(name: impl Into<String>, rest_url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            rest_url: rest_url.into(),
+            chain_id: None,
+        }
+    }
+
+    pub fn with_chain_id(mut self, chain_id: u8) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.rest_url)
+    }
+}
+
+/// Parses a known network's name, `aptos` and `movement` being their
+/// mainnets, or the REST URL of any other
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.trim() {
+            "aptos" | "aptos-mainnet" => Self::aptos_mainnet(),
+            "aptos-testnet" => Self::aptos_testnet(),
+            "movement" | "movement-mainnet" => Self::movement_mainnet(),
+            "movement-testnet" => Self::movement_testnet(),
+            url if url.starts_with("http://") || url.starts_with("https://") => Self::custom(url, url),
+            other => bail!(
+                "Unknown network '{other}': expected aptos, aptos-testnet, movement, movement-testnet or a REST URL"
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_network() {
+        assert_eq!(
+            "movement-mainnet".parse::<Network>().unwrap(),
+            Network::movement_mainnet()
+        );
+        let custom: Network = "http://localhost:30731/v1".parse().unwrap();
+        assert_eq!(custom.rest_url, "http://localhost:30731/v1");
+        assert_eq!(custom.chain_id, None);
+        assert_eq!("movement".parse::<Network>().unwrap().chain_id, Some(126));
+        assert!("movement-devnet".parse::<Network>().is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use aptos_fuzzer::executor::ShiftEvent;
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::language_storage::TypeTag;
+use aptos_move_core_types::u256::U256;
+use fuzzer_core::{ChainValue, ViolationInfo};
+use serde::{Deserialize, Serialize};
+
+/// Entry function argument value for Aptos
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AptosValue {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    Bool(bool),
+    Address(AccountAddress),
+    Vector(Vec<AptosValue>),
+}
+
+impl AptosValue {
+    /// Default (zero) value of a supported argument type
+    pub fn default_for(type_tag: &TypeTag) -> Result<Self> {
+        Ok(match type_tag {
+            TypeTag::U8 => AptosValue::U8(0),
+            TypeTag::U16 => AptosValue::U16(0),
+            TypeTag::U32 => AptosValue::U32(0),
+            TypeTag::U64 => AptosValue::U64(0),
+            TypeTag::U128 => AptosValue::U128(0),
+            TypeTag::U256 => AptosValue::U256(U256::zero()),
+            TypeTag::Bool => AptosValue::Bool(false),
+            TypeTag::Address => AptosValue::Address(AccountAddress::ZERO),
+            TypeTag::Vector(_) => AptosValue::Vector(Vec::new()),
+            other => bail!("Unsupported argument type: {}", other),
+        })
+    }
+
+    /// Parse a CLI argument as a value of the given type. Vectors use
+    /// `[a,b,c]`; `vector<u8>` additionally accepts a `0x` hex string.
+    pub fn parse(arg: &str, type_tag: &TypeTag) -> Result<Self> {
+        let arg = arg.trim();
+        Ok(match type_tag {
+            TypeTag::U8 => AptosValue::U8(arg.parse().with_context(|| format!("Invalid u8 '{}'", arg))?),
+            TypeTag::U16 => AptosValue::U16(arg.parse().with_context(|| format!("Invalid u16 '{}'", arg))?),
+            TypeTag::U32 => AptosValue::U32(arg.parse().with_context(|| format!("Invalid u32 '{}'", arg))?),
+            TypeTag::U64 => AptosValue::U64(arg.parse().with_context(|| format!("Invalid u64 '{}'", arg))?),
+            TypeTag::U128 => AptosValue::U128(arg.parse().with_context(|| format!("Invalid u128 '{}'", arg))?),
+            TypeTag::U256 => AptosValue::U256(U256::from_str(arg).with_context(|| format!("Invalid u256 '{}'", arg))?),
+            TypeTag::Bool => AptosValue::Bool(arg.parse().with_context(|| format!("Invalid bool '{}'", arg))?),
+            TypeTag::Address => AptosValue::Address(
+                AccountAddress::from_str(arg).with_context(|| format!("Invalid address '{}'", arg))?,
+            ),
+            TypeTag::Vector(inner) => {
+                if let (TypeTag::U8, Some(hex)) = (inner.as_ref(), arg.strip_prefix("0x")) {
+                    let bytes = (0..hex.len())
+                        .step_by(2)
+                        .map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or(""), 16))
+                        .collect::<Result<Vec<u8>, _>>()
+                        .with_context(|| format!("Invalid hex bytes '{}'", arg))?;
+                    return Ok(AptosValue::Vector(bytes.into_iter().map(AptosValue::U8).collect()));
+                }
+
+                let Some(items) = arg.strip_prefix('[').and_then(|s| s.strip_suffix(']')) else {
+                    bail!("Vector argument must be in [a,b,c] format: '{}'", arg);
+                };
+                let values = items
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| AptosValue::parse(item, inner))
+                    .collect::<Result<Vec<_>>>()?;
+                AptosValue::Vector(values)
+            }
+            other => bail!("Unsupported argument type: {}", other),
+        })
+    }
+
+    /// BCS encoding as expected for entry function arguments
+    pub fn to_bcs_bytes(&self) -> Result<Vec<u8>> {
+        let bytes = match self {
+            AptosValue::U8(v) => bcs::to_bytes(v)?,
+            AptosValue::U16(v) => bcs::to_bytes(v)?,
+            AptosValue::U32(v) => bcs::to_bytes(v)?,
+            AptosValue::U64(v) => bcs::to_bytes(v)?,
+            AptosValue::U128(v) => bcs::to_bytes(v)?,
+            AptosValue::U256(v) => bcs::to_bytes(v)?,
+            AptosValue::Bool(v) => bcs::to_bytes(v)?,
+            AptosValue::Address(v) => bcs::to_bytes(v)?,
+            AptosValue::Vector(values) => {
+                let mut bytes = Vec::new();
+                write_uleb128(&mut bytes, values.len() as u64);
+                for value in values {
+                    bytes.extend(value.to_bcs_bytes()?);
+                }
+                bytes
+            }
+        };
+        Ok(bytes)
+    }
+}
+
+fn write_uleb128(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+impl ChainValue for AptosValue {
+    fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            AptosValue::U8(_) |
+                AptosValue::U16(_) |
+                AptosValue::U32(_) |
+                AptosValue::U64(_) |
+                AptosValue::U128(_) |
+                AptosValue::U256(_)
+        )
+    }
+
+    fn is_integer_vector(&self) -> bool {
+        match self {
+            AptosValue::Vector(vec) => vec.iter().all(AptosValue::is_integer),
+            _ => false,
+        }
+    }
+
+    fn contains_integers(&self) -> bool {
+        match self {
+            AptosValue::Vector(vec) => vec.iter().any(|v| v.contains_integers()),
+            _ => self.is_integer(),
+        }
+    }
+
+    fn is_mutable_object(&self) -> bool {
+        false
+    }
+
+    fn get_object_id(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            AptosValue::U8(_) => "u8",
+            AptosValue::U16(_) => "u16",
+            AptosValue::U32(_) => "u32",
+            AptosValue::U64(_) => "u64",
+            AptosValue::U128(_) => "u128",
+            AptosValue::U256(_) => "u256",
+            AptosValue::Bool(_) => "bool",
+            AptosValue::Address(_) => "address",
+            AptosValue::Vector(_) => "vector",
+        }
+    }
+
+    fn integer_value(&self) -> Option<u128> {
+        match self {
+            AptosValue::U8(v) => Some(*v as u128),
+            AptosValue::U16(v) => Some(*v as u128),
+            AptosValue::U32(v) => Some(*v as u128),
+            AptosValue::U64(v) => Some(*v as u128),
+            AptosValue::U128(v) => Some(*v),
+            AptosValue::U256(v) => u128::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    fn with_integer_value(&self, value: u128) -> Option<Self> {
+        match self {
+            AptosValue::U8(_) => u8::try_from(value).ok().map(AptosValue::U8),
+            AptosValue::U16(_) => u16::try_from(value).ok().map(AptosValue::U16),
+            AptosValue::U32(_) => u32::try_from(value).ok().map(AptosValue::U32),
+            AptosValue::U64(_) => u64::try_from(value).ok().map(AptosValue::U64),
+            AptosValue::U128(_) => Some(AptosValue::U128(value)),
+            AptosValue::U256(_) => Some(AptosValue::U256(U256::from(value))),
+            _ => None,
+        }
+    }
+
+    fn domain(&self) -> Option<Vec<Self>> {
+        match self {
+            AptosValue::Bool(_) => Some(vec![AptosValue::Bool(false), AptosValue::Bool(true)]),
+            AptosValue::U8(_) => Some((0..=u8::MAX).map(AptosValue::U8).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// Execution result of an Aptos entry function call
+#[derive(Debug, Clone)]
+pub struct AptosExecutionResult {
+    /// `address::module::function` of the executed entry function
+    pub location: String,
+    /// Whether the transaction executed without error
+    pub success: bool,
+    /// Abort code if the transaction aborted
+    pub abort_code: Option<u64>,
+    /// VM status description if the transaction failed
+    pub error: Option<String>,
+    /// Shifts that lost high-order bits
+    pub lossy_shifts: Vec<ShiftEvent>,
+    /// Status and return value disagreements with the bare VM, when
+    /// compared against it
+    pub divergences: Vec<ViolationInfo>,
+    /// Writes to resource types the write policy doesn't allow
+    pub write_violations: Vec<ViolationInfo>,
+    /// Number of executed instructions
+    pub instructions_executed: u64,
+    /// Execution duration
+    pub execution_time: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_bcs_matches_native_encoding() {
+        let value = AptosValue::Vector(vec![AptosValue::U64(1), AptosValue::U64(u64::MAX)]);
+        assert_eq!(
+            value.to_bcs_bytes().unwrap(),
+            bcs::to_bytes(&vec![1u64, u64::MAX]).unwrap()
+        );
+
+        let long = AptosValue::Vector(vec![AptosValue::U8(7); 200]);
+        assert_eq!(long.to_bcs_bytes().unwrap(), bcs::to_bytes(&vec![7u8; 200]).unwrap());
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let u8_vector = TypeTag::Vector(Box::new(TypeTag::U8));
+        assert_eq!(
+            AptosValue::parse("0x0aff", &u8_vector).unwrap(),
+            AptosValue::Vector(vec![AptosValue::U8(10), AptosValue::U8(255)])
+        );
+        assert_eq!(
+            AptosValue::parse("[1, 2]", &u8_vector).unwrap(),
+            AptosValue::Vector(vec![AptosValue::U8(1), AptosValue::U8(2)])
+        );
+        assert_eq!(AptosValue::parse("42", &TypeTag::U64).unwrap(), AptosValue::U64(42));
+        assert!(AptosValue::parse("256", &TypeTag::U8).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! `CoreFuzzer` on the Aptos adapter, against the demo module built by
+//! `aptos move compile` in `contracts/aptos-demo` (see
+//! `scripts/setup_aptos.sh`)
+
+use std::path::PathBuf;
+
+use aptos_adapter::AptosAdapter;
+use fuzzer_core::fuzzer::CoreFuzzer;
+use fuzzer_core::{FuzzerConfig, FuzzingStatus};
+
+fn demo_module() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../contracts/aptos-demo/build/aptos-demo/bytecode_modules/shl_demo.mv")
+}
+
+#[tokio::test]
+#[ignore = "needs contracts/aptos-demo compiled"]
+async fn test_finds_lossy_shift() {
+    let adapter = AptosAdapter::new().with_module_path(demo_module()).unwrap();
+    let config = FuzzerConfig::new(
+        String::new(),
+        "0x42".to_string(),
+        "shl_demo".to_string(),
+        "integer_shl".to_string(),
+    )
+    .with_args(vec!["5".to_string(), "2".to_string()])
+    .with_iterations(10_000)
+    .with_timeout_seconds(60)
+    .with_seed(1);
+
+    let mut fuzzer = CoreFuzzer::new(adapter, config).await.unwrap();
+    let result = fuzzer.run().await.unwrap();
+
+    assert!(matches!(result.status, FuzzingStatus::ViolationFound));
+    let violation = &result.violations[0];
+    assert_eq!(violation.operation, "ShiftOverflow");
+    assert!(violation.location.contains("shl_demo::integer_shl"));
+}
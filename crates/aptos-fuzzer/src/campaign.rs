@@ -0,0 +1,886 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail};
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_types::on_chain_config::Features;
+use aptos_types::transaction::TransactionPayload;
+use libafl::corpus::Corpus;
+use libafl::events::{EventFirer, NopEventManager};
+use libafl::executors::{Executor, ExitKind};
+use libafl::feedbacks::{
+    ConstFeedback, EagerOrFeedback, FastAndFeedback, Feedback, MaxMapFeedback, StateInitializer, TimeoutFeedback,
+};
+use libafl::fuzzer::Fuzzer;
+use libafl::mutators::{Mutator, TuneableScheduledMutator};
+use libafl::schedulers::QueueScheduler;
+use libafl::stages::StdMutationalStage;
+use libafl::state::{HasCorpus, HasExecutions, HasSolutions};
+use libafl::{Evaluator, StdFuzzer};
+use libafl_bolts::tuples::tuple_list;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::executor::aptos_move_executor::AptosObservers;
+use crate::executor::ForkConfig;
+use crate::{
+    AbortCodeFeedback, AbortCodeObjective, AbortPolicy, AptosFuzzerInput, AptosFuzzerMutator, AptosFuzzerState,
+    AptosMoveExecutor, ArithmeticObjective, BareVmObjective, BoundaryValueMutator, EventInvariantFeedback,
+    EventInvariantObjective, EventInvariants, GasFeedback, GasMode, InstructionCeilingObjective, PowerOfTwoMutator,
+    SenderMutator, SequenceMutator, SerializedBytesMutator, ShiftOverflowObjective, TableMutator,
+    TransactionKindMutator, TypeArgMutator, ViewChecks, ViewObjective, WriteRules, WriteSetFeedback, WriteSetObjective,
+};
+
+/// Executions between checks of the budgets and for new solutions
+const CHECK_INTERVAL: u64 = 1_000;
+
+/// Bug class a campaign saves solutions for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Detector {
+    /// Shifts losing high bits
+    ShiftOverflow,
+    /// Overflowing, truncating or precision-losing arithmetic
+    Arithmetic,
+    /// Executions slower than the timeout or over the gas budget
+    Timeout,
+    /// Aborts, or only those the abort policy calls bugs
+    Abort,
+    /// Executions over the instruction ceiling, potential DoS
+    InstructionCeiling,
+    /// Events breaking the configured event invariants
+    EventInvariant,
+    /// Writes breaking the configured write rules
+    WriteRule,
+    /// View results breaking the configured view checks
+    ViewCheck,
+    /// Calls whose outcome differs in a bare MoveVM session, with the
+    /// comparison on
+    BareVmDivergence,
+}
+
+impl Detector {
+    pub const ALL: [Detector; 9] = [
+        Detector::ShiftOverflow,
+        Detector::Arithmetic,
+        Detector::Timeout,
+        Detector::Abort,
+        Detector::InstructionCeiling,
+        Detector::EventInvariant,
+        Detector::WriteRule,
+        Detector::ViewCheck,
+        Detector::BareVmDivergence,
+    ];
+}
+
+impl FromStr for Detector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shift_overflow" => Ok(Self::ShiftOverflow),
+            "arithmetic" => Ok(Self::Arithmetic),
+            "timeout" => Ok(Self::Timeout),
+            "abort" => Ok(Self::Abort),
+            "instruction_ceiling" => Ok(Self::InstructionCeiling),
+            "event_invariant" => Ok(Self::EventInvariant),
+            "write_rule" => Ok(Self::WriteRule),
+            "view_check" => Ok(Self::ViewCheck),
+            "bare_vm_divergence" => Ok(Self::BareVmDivergence),
+            _ => Err(format!("unknown detector {s}")),
+        }
+    }
+}
+
+/// A solution found by a running campaign
+#[derive(Debug, Clone)]
+pub struct CampaignFinding {
+    /// The functions the input calls, in order
+    pub calls: String,
+    /// What executing it again triggers, as in `libafl-aptos report`
+    pub triggers: Vec<String>,
+    pub input: AptosFuzzerInput,
+    /// Campaign executions when it was found
+    pub executions: u64,
+    pub elapsed: Duration,
+}
+
+/// Totals of a finished campaign
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CampaignSummary {
+    pub executions: u64,
+    pub corpus_entries: usize,
+    pub solutions: usize,
+    pub elapsed: Duration,
+}
+
+/// How a fuzzing client is set up: the state it starts from, the executor
+/// and its oracles, and what it keeps as corpus entries and solutions.
+/// Built by [`CampaignBuilder`]; `libafl-aptos` builds its clients from it
+/// as [`FuzzCampaign`] does.
+#[derive(Debug, Clone)]
+pub struct FuzzerSetup {
+    abi_path: Option<PathBuf>,
+    module_path: Option<PathBuf>,
+    script_path: Option<PathBuf>,
+    senders: Vec<AccountAddress>,
+    fork: Option<ForkConfig>,
+    corpus_dir: PathBuf,
+    solutions_dir: PathBuf,
+    framework_bundle: Option<PathBuf>,
+    state_snapshot: Option<PathBuf>,
+    table_fixture: Option<PathBuf>,
+    gas_mode: Option<GasMode>,
+    features: Option<Features>,
+    chain_id: Option<u8>,
+    /// Package to upgrade to, and after how many executions
+    upgrade: Option<(PathBuf, u64)>,
+    persist_writes: bool,
+    bare_vm: bool,
+    compare_bare_vm: bool,
+    watch_hangs: bool,
+    timeout: Duration,
+    gas_budget: Option<u64>,
+    instruction_ceiling: Option<u64>,
+    abort_policy: Option<AbortPolicy>,
+    event_invariants: EventInvariants,
+    write_rules: WriteRules,
+    view_checks: ViewChecks,
+    detectors: Vec<Detector>,
+}
+
+impl FuzzerSetup {
+    pub fn corpus_dir(&self) -> &Path {
+        &self.corpus_dir
+    }
+
+    pub fn solutions_dir(&self) -> &Path {
+        &self.solutions_dir
+    }
+
+    pub fn abort_policy(&self) -> Option<&AbortPolicy> {
+        self.abort_policy.as_ref()
+    }
+
+    /// A new fuzzer state seeded from the ABIs and scripts, prepared with
+    /// [`Self::prepare_state`]
+    pub fn new_state(&self) -> Result<AptosFuzzerState, libafl::Error> {
+        let mut state = AptosFuzzerState::new(
+            self.abi_path.clone(),
+            self.module_path.clone(),
+            self.script_path.clone(),
+            self.senders.clone(),
+            self.fork.clone(),
+            &self.corpus_dir,
+            &self.solutions_dir,
+        );
+        self.prepare_state(&mut state)?;
+        Ok(state)
+    }
+
+    /// Apply the framework bundle, state snapshot, table fixture, gas
+    /// schedule, feature flags and chain ID to a new state
+    pub fn prepare_state(&self, state: &mut AptosFuzzerState) -> Result<(), libafl::Error> {
+        if let Some(path) = &self.framework_bundle {
+            let count = state.load_framework_bundle(path.clone())?;
+            info!(count, path = %path.display(), "deployed framework bundle");
+        }
+        if let Some(path) = &self.state_snapshot {
+            state.load_state_snapshot(path.clone())?;
+        }
+        if let Some(path) = &self.table_fixture {
+            let count = state.seed_tables(path)?;
+            info!(count, path = %path.display(), "seeded table entries");
+        }
+        if let Some(mode) = &self.gas_mode {
+            state.set_gas_mode(mode.clone())?;
+        }
+        if let Some(features) = &self.features {
+            state.set_features(features.clone())?;
+        }
+        if let Some(chain_id) = self.chain_id {
+            state.set_chain_id(chain_id)?;
+        }
+        Ok(())
+    }
+
+    /// Upgrade the package to the configured one right away, for commands
+    /// replaying inputs found after the upgrade
+    pub fn upgrade_now(&self, state: &mut AptosFuzzerState) -> Result<(), libafl::Error> {
+        if let Some((path, _)) = &self.upgrade {
+            state.upgrade_modules(path.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Upgrade the package once `state` ran the configured number of
+    /// executions
+    pub fn upgrade_when_due(&self, state: &mut AptosFuzzerState) -> Result<(), libafl::Error> {
+        let Some((path, after)) = &self.upgrade else {
+            return Ok(());
+        };
+        if state.upgrade_path().is_some() || *state.executions() < *after {
+            return Ok(());
+        }
+        let upgraded = state.upgrade_modules(path.clone())?;
+        info!(upgraded, path = %path.display(), executions = *state.executions(), "upgraded package");
+        Ok(())
+    }
+
+    /// The fuzzing executor, on `state`'s environment if it isn't the
+    /// default one
+    pub fn executor<EM, Z>(&self, state: &AptosFuzzerState) -> AptosMoveExecutor<EM, Z> {
+        let mut executor = AptosMoveExecutor::new()
+            .with_persist_writes(self.persist_writes)
+            .with_timeout(self.timeout)
+            .with_bare_vm(self.bare_vm)
+            .with_bare_vm_comparison(self.compare_bare_vm)
+            .with_event_invariants(self.event_invariants.clone())
+            .with_write_rules(self.write_rules.clone())
+            .with_view_checks(self.view_checks.clone());
+        if self.watch_hangs {
+            executor = executor.watch_hangs(self.solutions_dir.clone());
+        }
+        if let Some(gas_budget) = self.gas_budget {
+            executor = executor.with_gas_budget(gas_budget);
+        }
+        if let Some(ceiling) = self.instruction_ceiling {
+            executor = executor.with_instruction_ceiling(ceiling);
+        }
+        if state.has_custom_environment() {
+            executor = executor.with_environment_of(state.aptos_state());
+        }
+        executor
+    }
+
+    /// Executor running solutions again to tell what they trigger, with the
+    /// checks of the fuzzing one but without its budgets
+    pub fn replay_executor(&self, state: &AptosFuzzerState) -> AptosMoveExecutor<(), ()> {
+        let mut executor = AptosMoveExecutor::new()
+            .with_bare_vm_comparison(self.compare_bare_vm)
+            .with_event_invariants(self.event_invariants.clone())
+            .with_write_rules(self.write_rules.clone())
+            .with_view_checks(self.view_checks.clone());
+        if let Some(ceiling) = self.instruction_ceiling {
+            executor = executor.with_instruction_ceiling(ceiling);
+        }
+        if state.has_custom_environment() {
+            executor = executor.with_environment_of(state.aptos_state());
+        }
+        executor
+    }
+
+    /// Inputs kept in the corpus: new edges, abort sites, gas usage, event
+    /// and write progress
+    pub fn feedback<EM, Z>(
+        &self,
+        executor: &AptosMoveExecutor<EM, Z>,
+    ) -> impl Feedback<EM, AptosFuzzerInput, AptosObservers, AptosFuzzerState>
+    where
+        EM: EventFirer<AptosFuzzerInput, AptosFuzzerState>,
+    {
+        EagerOrFeedback::new(
+            MaxMapFeedback::new(executor.pc_observer()),
+            EagerOrFeedback::new(
+                AbortCodeFeedback::new(),
+                EagerOrFeedback::new(
+                    GasFeedback::new(),
+                    EagerOrFeedback::new(
+                        EventInvariantFeedback::new(self.event_invariants.clone()),
+                        WriteSetFeedback::new(),
+                    ),
+                ),
+            ),
+        )
+    }
+
+    /// Inputs kept as solutions, by the enabled detectors and the
+    /// configured invariants, rules and checks
+    pub fn objective<EM>(&self) -> impl Feedback<EM, AptosFuzzerInput, AptosObservers, AptosFuzzerState> {
+        let abort_objective = match &self.abort_policy {
+            Some(policy) => AbortCodeObjective::new().with_policy(policy.clone()),
+            None => AbortCodeObjective::new(),
+        };
+        let enabled = |detector: Detector| ConstFeedback::new(self.detectors.contains(&detector));
+        EagerOrFeedback::new(
+            FastAndFeedback::new(enabled(Detector::ShiftOverflow), ShiftOverflowObjective::new()),
+            EagerOrFeedback::new(
+                FastAndFeedback::new(enabled(Detector::Arithmetic), ArithmeticObjective::new()),
+                EagerOrFeedback::new(
+                    FastAndFeedback::new(enabled(Detector::Timeout), TimeoutFeedback::new()),
+                    EagerOrFeedback::new(
+                        FastAndFeedback::new(enabled(Detector::Abort), abort_objective),
+                        EagerOrFeedback::new(
+                            FastAndFeedback::new(
+                                enabled(Detector::EventInvariant),
+                                EventInvariantObjective::new(self.event_invariants.clone()),
+                            ),
+                            EagerOrFeedback::new(
+                                FastAndFeedback::new(enabled(Detector::WriteRule), WriteSetObjective::new()),
+                                EagerOrFeedback::new(
+                                    FastAndFeedback::new(
+                                        enabled(Detector::InstructionCeiling),
+                                        InstructionCeilingObjective::new(),
+                                    ),
+                                    EagerOrFeedback::new(
+                                        FastAndFeedback::new(enabled(Detector::ViewCheck), ViewObjective::new()),
+                                        FastAndFeedback::new(
+                                            enabled(Detector::BareVmDivergence),
+                                            BareVmObjective::new(),
+                                        ),
+                                    ),
+                                ),
+                            ),
+                        ),
+                    ),
+                ),
+            ),
+        )
+    }
+
+    /// One weighted mutation per execution
+    pub fn mutator(
+        &self,
+        state: &mut AptosFuzzerState,
+    ) -> Result<impl Mutator<AptosFuzzerInput, AptosFuzzerState>, libafl::Error> {
+        let mutator = TuneableScheduledMutator::new(
+            state,
+            tuple_list!(
+                AptosFuzzerMutator::default(),
+                BoundaryValueMutator::new(),
+                PowerOfTwoMutator::new(),
+                TypeArgMutator::new(),
+                SenderMutator::new(),
+                SequenceMutator::new(),
+                TableMutator::new(),
+                SerializedBytesMutator::new(),
+                TransactionKindMutator::new()
+            ),
+        );
+        mutator.set_mutation_probabilities(state, vec![0.25, 0.15, 0.1, 0.1, 0.1, 0.1, 0.05, 0.1, 0.05])?;
+        mutator.set_iters(state, 1);
+        Ok(mutator)
+    }
+}
+
+/// Fuzzing campaign run in-process by a single client, for tools embedding
+/// the fuzzer instead of launching `libafl-aptos`. Solutions are saved to
+/// the solutions directory as by the CLI and streamed over a channel.
+#[derive(Debug, Clone)]
+pub struct FuzzCampaign {
+    setup: FuzzerSetup,
+    max_executions: Option<u64>,
+    max_duration: Option<Duration>,
+    progress: Arc<CampaignProgress>,
+}
+
+/// Counters of a running campaign, updated between batches of executions,
+/// and a flag asking it to stop
+#[derive(Debug, Default)]
+pub struct CampaignProgress {
+    stop: Arc<AtomicBool>,
+    executions: AtomicU64,
+    corpus_entries: AtomicUsize,
+    solutions: AtomicUsize,
+}
+
+impl CampaignProgress {
+    /// Ask the campaign to stop after its current execution
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn stop_requested(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    pub fn executions(&self) -> u64 {
+        self.executions.load(Ordering::Relaxed)
+    }
+
+    pub fn corpus_entries(&self) -> usize {
+        self.corpus_entries.load(Ordering::Relaxed)
+    }
+
+    pub fn solutions(&self) -> usize {
+        self.solutions.load(Ordering::Relaxed)
+    }
+
+    fn update(&self, state: &AptosFuzzerState) {
+        self.executions.store(*state.executions(), Ordering::Relaxed);
+        self.corpus_entries.store(state.corpus().count(), Ordering::Relaxed);
+        self.solutions.store(state.solutions().count(), Ordering::Relaxed);
+    }
+}
+
+impl FuzzCampaign {
+    pub fn builder() -> CampaignBuilder {
+        CampaignBuilder::default()
+    }
+
+    /// The campaign's counters and stop flag, shared with the thread
+    /// running it
+    pub fn progress(&self) -> Arc<CampaignProgress> {
+        self.progress.clone()
+    }
+
+    /// Run the campaign on a new thread; findings arrive on the handle's
+    /// receiver as they are found
+    pub fn spawn(self) -> CampaignHandle {
+        let (sender, findings) = mpsc::channel();
+        let progress = self.progress();
+        let thread = thread::spawn(move || self.run(&sender));
+        CampaignHandle {
+            findings,
+            progress,
+            thread,
+        }
+    }
+
+    /// Run the campaign on this thread until a budget runs out or it is
+    /// asked to stop, sending each finding to `findings`. Stops early once
+    /// the receiver is dropped.
+    pub fn run(self, findings: &Sender<CampaignFinding>) -> anyhow::Result<CampaignSummary> {
+        let started = Instant::now();
+        let setup = &self.setup;
+        let mut state = setup.new_state()?;
+        state.set_stop_flag(self.progress.stop.clone());
+        let mut executor = setup.executor(&state);
+        let mut feedback = setup.feedback(&executor);
+        feedback.init_state(&mut state)?;
+        state.load_history(setup.corpus_dir());
+        let mut fuzzer = StdFuzzer::new(QueueScheduler::new(), feedback, setup.objective());
+        let mut mgr = NopEventManager::new();
+        let mut stages = tuple_list!(StdMutationalStage::new(setup.mutator(&mut state)?));
+
+        let mut initial_inputs = state.take_initial_inputs();
+        // Without ABIs or scripts, every entry function of the package is
+        // seeded from its bytecode
+        if setup.abi_path.is_none() && setup.script_path.is_none() {
+            for target in state.package_entry_functions(setup.bare_vm) {
+                initial_inputs.extend(state.entry_function_seeds(&target));
+            }
+        }
+        initial_inputs.extend(AptosFuzzerState::load_inputs_from_dir(setup.corpus_dir()));
+        for input in initial_inputs {
+            fuzzer.add_input(&mut state, &mut executor, &mut mgr, input)?;
+        }
+
+        // Solutions are executed again on their own executor to tell what
+        // they trigger
+        let mut replayer = setup.replay_executor(&state);
+        let mut reported = 0;
+        loop {
+            let ids: Vec<_> = state.solutions().ids().skip(reported).collect();
+            reported += ids.len();
+            for id in ids {
+                let input = state.solutions().cloned_input_for_id(id)?;
+                // Replays aren't fuzzing executions and don't count toward
+                // the budget
+                let executions = *state.executions();
+                let triggers = triggers(&mut replayer, &mut state, &input, setup.abort_policy())?;
+                *state.executions_mut() = executions;
+                let finding = CampaignFinding {
+                    calls: describe_calls(&input),
+                    triggers,
+                    input,
+                    executions: *state.executions(),
+                    elapsed: started.elapsed(),
+                };
+                if findings.send(finding).is_err() {
+                    return Ok(self.summary(&state, started));
+                }
+            }
+
+            self.progress.update(&state);
+            if self.progress.stop_requested() {
+                return Ok(self.summary(&state, started));
+            }
+            let out_of_executions = self.max_executions.is_some_and(|max| *state.executions() >= max);
+            let out_of_time = self.max_duration.is_some_and(|max| started.elapsed() >= max);
+            if out_of_executions || out_of_time {
+                return Ok(self.summary(&state, started));
+            }
+            let iterations = match self.max_executions {
+                Some(max) => CHECK_INTERVAL.min(max - *state.executions()),
+                None => CHECK_INTERVAL,
+            };
+            match fuzzer.fuzz_loop_for(&mut stages, &mut executor, &mut state, &mut mgr, iterations) {
+                // Stopped mid-batch; the next round reports the last
+                // solutions and returns
+                Err(libafl::Error::ShuttingDown) if self.progress.stop_requested() => {}
+                result => {
+                    result?;
+                }
+            }
+            setup.upgrade_when_due(&mut state)?;
+        }
+    }
+
+    fn summary(&self, state: &AptosFuzzerState, started: Instant) -> CampaignSummary {
+        CampaignSummary {
+            executions: *state.executions(),
+            corpus_entries: state.corpus().count(),
+            solutions: state.solutions().count(),
+            elapsed: started.elapsed(),
+        }
+    }
+}
+
+/// Builder of a [`FuzzCampaign`] or a [`FuzzerSetup`], with the defaults of
+/// `libafl-aptos`
+#[derive(Debug, Clone)]
+pub struct CampaignBuilder {
+    campaign: FuzzCampaign,
+}
+
+impl Default for CampaignBuilder {
+    fn default() -> Self {
+        Self {
+            campaign: FuzzCampaign {
+                setup: FuzzerSetup {
+                    abi_path: None,
+                    module_path: None,
+                    script_path: None,
+                    senders: vec![
+                        AccountAddress::from_hex_literal("0xa11ce").expect("valid address"),
+                        AccountAddress::from_hex_literal("0xb0b").expect("valid address"),
+                    ],
+                    fork: None,
+                    corpus_dir: PathBuf::from("corpus"),
+                    solutions_dir: PathBuf::from("solutions"),
+                    framework_bundle: None,
+                    state_snapshot: None,
+                    table_fixture: None,
+                    gas_mode: None,
+                    features: None,
+                    chain_id: None,
+                    upgrade: None,
+                    persist_writes: false,
+                    bare_vm: false,
+                    compare_bare_vm: false,
+                    watch_hangs: false,
+                    timeout: Duration::from_secs(1),
+                    gas_budget: None,
+                    instruction_ceiling: None,
+                    abort_policy: None,
+                    event_invariants: EventInvariants::default(),
+                    write_rules: WriteRules::default(),
+                    view_checks: ViewChecks::default(),
+                    detectors: Detector::ALL.to_vec(),
+                },
+                max_executions: None,
+                max_duration: None,
+                progress: Arc::default(),
+            },
+        }
+    }
+}
+
+impl CampaignBuilder {
+    /// ABI file or directory of the entry functions to fuzz
+    pub fn with_abi_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.abi_path = Some(path.into());
+        self
+    }
+
+    /// Compiled module, package build directory or .mrb bundle to publish.
+    /// A campaign without ABIs or scripts fuzzes every entry function of it.
+    pub fn with_module_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.module_path = Some(path.into());
+        self
+    }
+
+    pub fn with_script_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.script_path = Some(path.into());
+        self
+    }
+
+    pub fn with_senders(mut self, senders: Vec<AccountAddress>) -> Self {
+        self.campaign.setup.senders = senders;
+        self
+    }
+
+    pub fn with_fork(mut self, fork: ForkConfig) -> Self {
+        self.campaign.setup.fork = Some(fork);
+        self
+    }
+
+    pub fn with_corpus_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.corpus_dir = dir.into();
+        self
+    }
+
+    pub fn with_solutions_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.solutions_dir = dir.into();
+        self
+    }
+
+    /// Framework release bundle (`.mrb`) deployed over the built-in one
+    pub fn with_framework_bundle(mut self, path: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.framework_bundle = Some(path.into());
+        self
+    }
+
+    pub fn with_state_snapshot(mut self, path: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.state_snapshot = Some(path.into());
+        self
+    }
+
+    /// Table entries to seed, see [`AptosFuzzerState::seed_tables`]
+    pub fn with_table_fixture(mut self, path: impl Into<PathBuf>) -> Self {
+        self.campaign.setup.table_fixture = Some(path.into());
+        self
+    }
+
+    pub fn with_gas_mode(mut self, mode: GasMode) -> Self {
+        self.campaign.setup.gas_mode = Some(mode);
+        self
+    }
+
+    pub fn with_features(mut self, features: Features) -> Self {
+        self.campaign.setup.features = Some(features);
+        self
+    }
+
+    /// Chain ID to run with instead of the default or forked one, see
+    /// [`AptosFuzzerState::set_chain_id`]
+    pub fn with_chain_id(mut self, chain_id: u8) -> Self {
+        self.campaign.setup.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Upgrade the package to the modules at `path` once a client ran
+    /// `after` executions
+    pub fn with_upgrade(mut self, path: impl Into<PathBuf>, after: u64) -> Self {
+        self.campaign.setup.upgrade = Some((path.into(), after));
+        self
+    }
+
+    /// Keep each execution's writes, see
+    /// [`AptosMoveExecutor::with_persist_writes`]
+    pub fn with_persist_writes(mut self, persist_writes: bool) -> Self {
+        self.campaign.setup.persist_writes = persist_writes;
+        self
+    }
+
+    /// Call functions in a bare MoveVM session, see
+    /// [`AptosMoveExecutor::with_bare_vm`]. Such sessions trace no shifts,
+    /// so this leaves out [`Detector::ShiftOverflow`].
+    pub fn with_bare_vm(mut self, bare_vm: bool) -> Self {
+        self.campaign.setup.bare_vm = bare_vm;
+        if bare_vm {
+            self.campaign
+                .setup
+                .detectors
+                .retain(|detector| *detector != Detector::ShiftOverflow);
+        }
+        self
+    }
+
+    /// Call entry functions in a bare MoveVM session too and save inputs
+    /// whose outcomes differ, see
+    /// [`AptosMoveExecutor::with_bare_vm_comparison`]
+    pub fn with_bare_vm_comparison(mut self, compare: bool) -> Self {
+        self.campaign.setup.compare_bare_vm = compare;
+        self
+    }
+
+    /// Kill the process when an execution hangs, see
+    /// [`AptosMoveExecutor::watch_hangs`]; only for clients running in a
+    /// process of their own
+    pub fn with_hang_watchdog(mut self, watch_hangs: bool) -> Self {
+        self.campaign.setup.watch_hangs = watch_hangs;
+        self
+    }
+
+    pub fn with_abort_policy(mut self, policy: AbortPolicy) -> Self {
+        self.campaign.setup.abort_policy = Some(policy);
+        self
+    }
+
+    pub fn with_event_invariants(mut self, invariants: EventInvariants) -> Self {
+        self.campaign.setup.event_invariants = invariants;
+        self
+    }
+
+    pub fn with_write_rules(mut self, rules: WriteRules) -> Self {
+        self.campaign.setup.write_rules = rules;
+        self
+    }
+
+    pub fn with_view_checks(mut self, checks: ViewChecks) -> Self {
+        self.campaign.setup.view_checks = checks;
+        self
+    }
+
+    /// Only save solutions of these classes; all of them by default
+    pub fn with_detectors(mut self, detectors: &[Detector]) -> Self {
+        self.campaign.setup.detectors = detectors.to_vec();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.campaign.setup.timeout = timeout;
+        self
+    }
+
+    pub fn with_gas_budget(mut self, instructions: u64) -> Self {
+        self.campaign.setup.gas_budget = Some(instructions);
+        self
+    }
+
+    /// Save executions running more instructions than `instructions` as
+    /// potential DoS
+    pub fn with_instruction_ceiling(mut self, instructions: u64) -> Self {
+        self.campaign.setup.instruction_ceiling = Some(instructions);
+        self
+    }
+
+    pub fn with_max_executions(mut self, executions: u64) -> Self {
+        self.campaign.max_executions = Some(executions);
+        self
+    }
+
+    pub fn with_max_duration(mut self, duration: Duration) -> Self {
+        self.campaign.max_duration = Some(duration);
+        self
+    }
+
+    /// The client setup alone, for running clients some other way than
+    /// [`FuzzCampaign::run`]
+    pub fn build_setup(self) -> anyhow::Result<FuzzerSetup> {
+        let setup = self.campaign.setup;
+        if setup.module_path.is_none() && setup.fork.is_none() {
+            bail!("A module path or a fork is required");
+        }
+        if setup.senders.is_empty() {
+            bail!("At least one sender is required");
+        }
+        if setup.bare_vm && setup.detectors.contains(&Detector::ShiftOverflow) {
+            bail!("Bare MoveVM sessions trace no shifts for the shift overflow detector");
+        }
+        if setup.bare_vm && setup.compare_bare_vm {
+            bail!("Bare MoveVM campaigns run no transactions to compare with the bare VM");
+        }
+        Ok(setup)
+    }
+
+    pub fn build(self) -> anyhow::Result<FuzzCampaign> {
+        let (max_executions, max_duration) = (self.campaign.max_executions, self.campaign.max_duration);
+        let setup = self.build_setup()?;
+        if setup.abi_path.is_none() && setup.script_path.is_none() && setup.module_path.is_none() {
+            bail!("An ABI, script or module path is required");
+        }
+        if max_executions.is_none() && max_duration.is_none() {
+            bail!("A campaign needs an execution or time budget");
+        }
+        // Campaigns built from clones of a builder are stopped separately
+        Ok(FuzzCampaign {
+            setup,
+            max_executions,
+            max_duration,
+            progress: Arc::default(),
+        })
+    }
+}
+
+/// A campaign running on its own thread
+pub struct CampaignHandle {
+    findings: Receiver<CampaignFinding>,
+    progress: Arc<CampaignProgress>,
+    thread: JoinHandle<anyhow::Result<CampaignSummary>>,
+}
+
+impl CampaignHandle {
+    /// Findings as they are found; iterating ends with the campaign
+    pub fn findings(&self) -> &Receiver<CampaignFinding> {
+        &self.findings
+    }
+
+    pub fn progress(&self) -> &CampaignProgress {
+        &self.progress
+    }
+
+    /// Ask the campaign to stop; [`CampaignHandle::join`] then returns soon
+    pub fn stop(&self) {
+        self.progress.stop();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Wait for the campaign to finish
+    pub fn join(self) -> anyhow::Result<CampaignSummary> {
+        self.thread.join().map_err(|_| anyhow!("Campaign thread panicked"))?
+    }
+}
+
+/// What executing `input` triggers: a non-`Ok` exit, the abort (classified
+/// by `abort_policy` if given), lossy shifts, arithmetic events and what
+/// the executor checks: the instruction ceiling, event invariants, write
+/// rules, view checks and bare VM divergences
+pub fn triggers(
+    executor: &mut AptosMoveExecutor<(), ()>,
+    state: &mut AptosFuzzerState,
+    input: &AptosFuzzerInput,
+    abort_policy: Option<&AbortPolicy>,
+) -> Result<Vec<String>, libafl::Error> {
+    let exit_kind = executor.run_target(&mut (), state, &mut (), input)?;
+    let mut triggers = Vec::new();
+    if exit_kind != ExitKind::Ok {
+        triggers.push(format!("{exit_kind:?}"));
+    }
+    if let Some(site) = executor.abort_observer().last_site() {
+        match abort_policy {
+            Some(policy) => triggers.push(format!(
+                "abort in {site} ({})",
+                policy.classify(&site.module, site.code)
+            )),
+            None => triggers.push(format!("abort in {site}")),
+        }
+    }
+    for shift in executor.shift_observer().overflows() {
+        triggers.push(format!("lossy shift: {shift}"));
+    }
+    for event in executor.arithmetic_observer().events() {
+        triggers.push(event.to_string());
+    }
+    let gas = executor.gas_observer();
+    if let Some(ceiling) = gas.ceiling().filter(|_| gas.exceeds_ceiling()) {
+        triggers.push(format!("{} instructions over ceiling {ceiling}", gas.gas_used()));
+    }
+    for violation in executor.event_violations() {
+        triggers.push(format!("event invariant broken: {violation}"));
+    }
+    for violation in executor.write_set_observer().violations() {
+        triggers.push(format!("write rule broken: {violation}"));
+    }
+    for violation in executor.view_observer().violations() {
+        triggers.push(format!("view check broken: {violation}"));
+    }
+    for divergence in executor.bare_vm_observer().divergences() {
+        triggers.push(format!("bare VM divergence: {divergence}"));
+    }
+    Ok(triggers)
+}
+
+/// The functions `input` calls, comma-separated
+pub fn describe_calls(input: &AptosFuzzerInput) -> String {
+    input
+        .calls()
+        .iter()
+        .map(|call| match (call.payload(), call.entry_function()) {
+            (TransactionPayload::Script(_), _) => "script".to_string(),
+            (_, Some(entry)) => format!("{}::{}", entry.module(), entry.function()),
+            (_, None) => "unsupported".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use aptos_move_core_types::language_storage::{StructTag, TypeTag};
+use aptos_types::contract_event::ContractEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::executor::aptos_custom_state::AptosCustomState;
+use crate::layout::LayoutCache;
+
+/// What an invariant asserts about the events of a type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "assert", rename_all = "snake_case")]
+pub enum EventPredicate {
+    /// Every input whose calls all succeed emits the event
+    Present,
+    /// No input emits the event
+    Absent,
+    /// A numeric field of every emitted event lies within `min..=max`;
+    /// nested fields are joined with dots, e.g. `amount.value`
+    Bounds {
+        field: String,
+        min: Option<u128>,
+        max: Option<u128>,
+    },
+}
+
+/// A user predicate on the events of one type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventInvariant {
+    pub name: String,
+    /// Event struct, e.g. `0xcafe::pool::SwapEvent`; without type arguments
+    /// it matches every instantiation
+    pub event: String,
+    #[serde(flatten)]
+    pub predicate: EventPredicate,
+}
+
+impl EventInvariant {
+    fn matches_tag(&self, tag: &StructTag) -> bool {
+        match StructTag::from_str(&self.event) {
+            Ok(expected) if expected.type_args.is_empty() => {
+                expected.address == tag.address && expected.module == tag.module && expected.name == tag.name
+            }
+            Ok(expected) => expected == *tag,
+            Err(_) => false,
+        }
+    }
+
+    pub(crate) fn matches(&self, event: &DecodedEvent) -> bool {
+        match &event.tag {
+            Some(tag) => self.matches_tag(tag),
+            // Deserialized events carry their type as a string only
+            None => StructTag::from_str(&event.type_tag).is_ok_and(|tag| self.matches_tag(&tag)),
+        }
+    }
+}
+
+/// Event invariants loaded from a TOML or JSON file
+///
+/// ```toml
+/// [[invariants]]
+/// name = "no_unbacked_mint"
+/// event = "0xcafe::pool::MintEvent"
+/// assert = "absent"
+///
+/// [[invariants]]
+/// name = "fee_capped"
+/// event = "0xcafe::pool::SwapEvent"
+/// assert = "bounds"
+/// field = "fee_bps"
+/// max = 1000
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventInvariants {
+    pub invariants: Vec<EventInvariant>,
+}
+
+impl EventInvariants {
+    /// Load invariants from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read event invariants {}", path.display()))?;
+
+        let invariants: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&content)
+                .with_context(|| format!("Failed to parse event invariants {}", path.display()))?,
+            Some("json") => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse event invariants {}", path.display()))?,
+            _ => bail!("Unsupported event invariants format: {}", path.display()),
+        };
+        for invariant in &invariants.invariants {
+            StructTag::from_str(&invariant.event)
+                .with_context(|| format!("Invalid event type in invariant {}", invariant.name))?;
+        }
+        Ok(invariants)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invariants.is_empty()
+    }
+
+    /// Whether the fields of events of type `tag` are checked
+    pub fn watches_fields(&self, tag: &StructTag) -> bool {
+        self.invariants
+            .iter()
+            .any(|invariant| matches!(invariant.predicate, EventPredicate::Bounds { .. }) && invariant.matches_tag(tag))
+    }
+
+    /// `event` with its fields decoded if invariants check them
+    pub fn decode(&self, layouts: &mut LayoutCache, state: &AptosCustomState, event: &ContractEvent) -> DecodedEvent {
+        let type_tag = event.type_tag();
+        let tag = match type_tag {
+            TypeTag::Struct(tag) => Some(tag.as_ref().clone()),
+            _ => None,
+        };
+        let fields = if tag.as_ref().is_some_and(|tag| self.watches_fields(tag)) {
+            layouts
+                .numeric_fields(state, type_tag, event.event_data())
+                .unwrap_or_default()
+        } else {
+            BTreeMap::new()
+        };
+        DecodedEvent {
+            type_tag: type_tag.to_canonical_string(),
+            tag,
+            fields,
+        }
+    }
+
+    /// Invariants the `events` of an execution break; `completed` tells
+    /// whether all of its calls succeeded
+    pub fn violations(&self, events: &[DecodedEvent], completed: bool) -> Vec<EventViolation> {
+        let mut violations = Vec::new();
+
+        for invariant in &self.invariants {
+            let mut emitted = events.iter().filter(|event| invariant.matches(event));
+            let reason = match &invariant.predicate {
+                EventPredicate::Present => (completed && emitted.next().is_none()).then(|| "not emitted".to_string()),
+                EventPredicate::Absent => emitted.next().map(|_| "emitted".to_string()),
+                EventPredicate::Bounds { field, min, max } => emitted.find_map(|event| {
+                    let Some(&value) = event.fields.get(field) else {
+                        return Some(format!("field {field} missing or not numeric"));
+                    };
+                    if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                        Some(format!("{field} = {value} out of bounds"))
+                    } else {
+                        None
+                    }
+                }),
+            };
+            if let Some(reason) = reason {
+                violations.push(EventViolation {
+                    invariant: invariant.name.clone(),
+                    event: invariant.event.clone(),
+                    reason,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// An event of an execution, with its numeric and boolean fields when they
+/// are checked by an invariant
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecodedEvent {
+    pub type_tag: String,
+    #[serde(skip)]
+    tag: Option<StructTag>,
+    /// Values by dotted field path, see [`LayoutCache::numeric_fields`]
+    pub fields: BTreeMap<String, u128>,
+}
+
+/// An event invariant an execution broke
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventViolation {
+    pub invariant: String,
+    pub event: String,
+    pub reason: String,
+}
+
+impl fmt::Display for EventViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.invariant, self.event, self.reason)
+    }
+}
@@ -1,11 +1,19 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::sync::Arc;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use aptos_aggregator::bounded_math::SignedU128;
 use aptos_aggregator::resolver::{TAggregatorV1View, TDelayedFieldView};
 use aptos_aggregator::types::{DelayedFieldValue, DelayedFieldsSpeculativeError};
 use aptos_cached_packages::head_release_bundle;
-use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters};
+use aptos_framework::ReleaseBundle;
+use aptos_gas_schedule::{
+    AptosGasParameters, FromOnChainGasSchedule, InitialGasSchedule, MiscGasParameters, NativeGasParameters,
+    ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
+};
+use aptos_move_binary_format::compatibility::Compatibility;
 use aptos_move_binary_format::errors::{PartialVMError, PartialVMResult, VMResult};
 use aptos_move_binary_format::file_format::CompiledScript;
 use aptos_move_binary_format::CompiledModule;
@@ -20,15 +28,21 @@ use aptos_move_vm_types::code::{Code, ScriptCache};
 use aptos_move_vm_types::delayed_values::delayed_field_id::DelayedFieldID;
 use aptos_move_vm_types::resolver::ResourceResolver;
 use aptos_native_interface::SafeNativeBuilder;
+use aptos_types::account_config::{AccountResource, AptosCoinType, CoinStoreResource};
 use aptos_types::chain_id::ChainId;
 use aptos_types::error::{PanicError, PanicOr};
-use aptos_types::on_chain_config::{ConfigStorage, Features, TimedFeaturesBuilder};
+use aptos_types::event::{EventHandle, EventKey};
+use aptos_types::move_resource::MoveResource;
+use aptos_types::on_chain_config::{
+    ConfigStorage, Features, GasSchedule, GasScheduleV2, OnChainConfig, TimedFeaturesBuilder,
+};
 use aptos_types::state_store::errors::StateViewError;
 use aptos_types::state_store::state_key::inner::StateKeyInner;
 use aptos_types::state_store::state_key::StateKey;
 use aptos_types::state_store::state_storage_usage::StateStorageUsage;
 use aptos_types::state_store::state_value::{StateValue, StateValueMetadata};
 use aptos_types::state_store::StateViewId;
+use aptos_types::vm::module_metadata::get_resource_group_member_from_metadata;
 use aptos_types::write_set::{TransactionWrite, WriteSet};
 use aptos_vm::move_vm_ext::{AptosMoveResolver, AsExecutorView, AsResourceGroupView, ResourceGroupResolver};
 use aptos_vm_environment::natives::aptos_natives_with_builder;
@@ -40,6 +54,13 @@ use aptos_vm_types::resolver::{
 };
 use bytes::Bytes;
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::rest_state_fetcher::RestStateFetcher;
+
+/// Balance in octas minted to bootstrapped sender accounts
+pub const DEFAULT_ACCOUNT_BALANCE: u64 = 1_000_000_000_000;
 
 #[derive(Clone)]
 pub struct AptosCustomState {
@@ -49,6 +70,32 @@ pub struct AptosCustomState {
     scripts_deser: DashMap<[u8; 32], Arc<CompiledScript>>,
     scripts_verified: DashMap<[u8; 32], Arc<Script>>,
     runtime_environment: RuntimeEnvironment,
+    // Open snapshots, innermost last; each records the prior value of every
+    // entry first written while it was open
+    snapshots: Vec<Snapshot>,
+    // Fullnode state read through for keys missing locally
+    fork: Option<Arc<RestStateFetcher>>,
+    // Keys deleted locally, hiding their forked value
+    removed: HashSet<StateKey>,
+    // Decoded resource groups by group key, dropped when the key is written
+    groups: DashMap<StateKey, Arc<BTreeMap<StructTag, Bytes>>>,
+}
+
+/// Undo log of one open snapshot. `None` means the entry did not exist.
+#[derive(Clone, Default)]
+struct Snapshot {
+    kv_state: HashMap<StateKey, Option<StateValue>>,
+    tables: HashMap<(TableHandle, Vec<u8>), Option<Bytes>>,
+    modules: HashMap<ModuleId, Option<Bytes>>,
+    removed: HashMap<StateKey, bool>,
+}
+
+/// State written to disk by [`AptosCustomState::export_snapshot`]
+#[derive(Serialize, Deserialize)]
+struct ExportedState {
+    entries: Vec<(StateKey, StateValue)>,
+    // Keys deleted locally while forked
+    removed: Vec<StateKey>,
 }
 
 macro_rules! unknown_status {
@@ -63,8 +110,8 @@ impl TAggregatorV1View for AptosCustomState {
     type Identifier = StateKey;
 
     fn get_aggregator_v1_state_value(&self, id: &StateKey) -> PartialVMResult<Option<StateValue>> {
-        match self.kv_state.get(id) {
-            Some(v) => Ok(Some(v.clone())),
+        match self.lookup(id) {
+            Some(v) => Ok(Some(v)),
             None => Err(unknown_status!()),
         }
     }
@@ -121,7 +168,7 @@ impl TDelayedFieldView for AptosCustomState {
 
 impl ConfigStorage for AptosCustomState {
     fn fetch_config_bytes(&self, state_key: &StateKey) -> Option<Bytes> {
-        self.kv_state.get(state_key).map(|v| v.bytes().clone())
+        self.lookup(state_key).map(|v| v.bytes().clone())
     }
 }
 
@@ -130,12 +177,21 @@ impl ResourceResolver for AptosCustomState {
         &self,
         address: &AccountAddress,
         struct_tag: &StructTag,
-        _metadata: &[Metadata],
+        metadata: &[Metadata],
         _layout: Option<&MoveTypeLayout>,
     ) -> PartialVMResult<(Option<Bytes>, usize)> {
+        // Members of a resource group live in the group's blob, not under
+        // their own key
+        if let Some(group_tag) = get_resource_group_member_from_metadata(struct_tag, metadata) {
+            let group_key = StateKey::resource_group(address, &group_tag);
+            let bytes = self.get_resource_from_group(&group_key, struct_tag, None)?;
+            let size = bytes.as_ref().map_or(0, |bytes| bytes.len());
+            return Ok((bytes, size));
+        }
+
         let state_key = StateKey::resource(address, struct_tag).map_err(|_| unknown_status!())?;
 
-        match self.kv_state.get(&state_key) {
+        match self.lookup(&state_key) {
             Some(state_value) => {
                 let bytes = state_value.bytes();
                 let size = bytes.len();
@@ -146,10 +202,10 @@ impl ResourceResolver for AptosCustomState {
     }
 }
 
-// Simple resolver; delegates to group view; no internal caching.
+// Simple resolver; delegates to group view, which caches decoded groups.
 impl ResourceGroupResolver for AptosCustomState {
     fn release_resource_group_cache(&self) -> Option<HashMap<StateKey, BTreeMap<StructTag, Bytes>>> {
-        // Return empty: no internal cache
+        // Return empty: the decoded groups are kept by the state
         Some(HashMap::new())
     }
 
@@ -174,7 +230,7 @@ impl StateStorageView for AptosCustomState {
     }
 
     fn read_state_value(&self, state_key: &StateKey) -> Result<(), StateViewError> {
-        match self.kv_state.get(state_key) {
+        match self.lookup(state_key) {
             Some(_) => Ok(()),
             None => Err(StateViewError::NotFound(format!("Key not found: {:?}", state_key))),
         }
@@ -192,11 +248,7 @@ impl TableResolver for AptosCustomState {
         key: &[u8],
         _maybe_layout: Option<&MoveTypeLayout>,
     ) -> Result<Option<Bytes>, PartialVMError> {
-        let table_key = (*handle, key.to_vec());
-        match self.tables.get(&table_key) {
-            Some(bytes) => Ok(Some(bytes.clone())),
-            None => Ok(None),
-        }
+        Ok(self.lookup_table(handle, key))
     }
 }
 
@@ -215,28 +267,25 @@ impl TResourceView for AptosCustomState {
         state_key: &StateKey,
         _maybe_layout: Option<&MoveTypeLayout>,
     ) -> PartialVMResult<Option<StateValue>> {
-        match self.kv_state.get(state_key) {
-            Some(state_value) => Ok(Some(state_value.clone())),
-            None => Ok(None),
-        }
+        Ok(self.lookup(state_key))
     }
 
     fn get_resource_state_value_metadata(&self, state_key: &StateKey) -> PartialVMResult<Option<StateValueMetadata>> {
-        match self.kv_state.get(state_key) {
+        match self.lookup(state_key) {
             Some(state_value) => Ok(Some(state_value.metadata().clone())),
             None => Ok(None),
         }
     }
 
     fn get_resource_state_value_size(&self, state_key: &StateKey) -> PartialVMResult<u64> {
-        match self.kv_state.get(state_key) {
+        match self.lookup(state_key) {
             Some(state_value) => Ok(state_value.bytes().len() as u64),
             None => Ok(0),
         }
     }
 
     fn resource_exists(&self, state_key: &StateKey) -> PartialVMResult<bool> {
-        Ok(self.kv_state.contains_key(state_key))
+        Ok(self.lookup(state_key).is_some())
     }
 }
 
@@ -253,7 +302,7 @@ impl TResourceGroupView for AptosCustomState {
     type Layout = MoveTypeLayout;
 
     fn resource_group_size(&self, group_key: &StateKey) -> PartialVMResult<ResourceGroupSize> {
-        match self.kv_state.get(group_key) {
+        match self.lookup(group_key) {
             Some(state_value) => Ok(ResourceGroupSize::Concrete(state_value.bytes().len() as u64)),
             None => Ok(ResourceGroupSize::Concrete(0)),
         }
@@ -265,37 +314,26 @@ impl TResourceGroupView for AptosCustomState {
         resource_tag: &StructTag,
         _maybe_layout: Option<&MoveTypeLayout>,
     ) -> PartialVMResult<Option<Bytes>> {
-        let maybe_bytes = self.kv_state.get(group_key).map(|sv| sv.bytes().clone());
-        if let Some(blob) = maybe_bytes {
-            let map: BTreeMap<StructTag, Bytes> = bcs::from_bytes(&blob).map_err(|_| unknown_status!())?;
-            Ok(map.get(resource_tag).cloned())
-        } else {
-            Ok(None)
-        }
+        Ok(self
+            .resource_group(group_key)?
+            .and_then(|group| group.get(resource_tag).cloned()))
     }
 
     fn resource_size_in_group(&self, group_key: &StateKey, resource_tag: &StructTag) -> PartialVMResult<usize> {
-        let maybe_bytes = self.kv_state.get(group_key).map(|sv| sv.bytes().clone());
-        if let Some(blob) = maybe_bytes {
-            let map: BTreeMap<StructTag, Bytes> = bcs::from_bytes(&blob).map_err(|_| unknown_status!())?;
-            Ok(map.get(resource_tag).map_or(0, |v| v.len()))
-        } else {
-            Ok(0)
-        }
+        Ok(self
+            .resource_group(group_key)?
+            .and_then(|group| group.get(resource_tag).map(|v| v.len()))
+            .unwrap_or(0))
     }
 
     fn resource_exists_in_group(&self, group_key: &StateKey, resource_tag: &StructTag) -> PartialVMResult<bool> {
-        let maybe_bytes = self.kv_state.get(group_key).map(|sv| sv.bytes().clone());
-        if let Some(blob) = maybe_bytes {
-            let map: BTreeMap<StructTag, Bytes> = bcs::from_bytes(&blob).map_err(|_| unknown_status!())?;
-            Ok(map.contains_key(resource_tag))
-        } else {
-            Ok(false)
-        }
+        Ok(self
+            .resource_group(group_key)?
+            .is_some_and(|group| group.contains_key(resource_tag)))
     }
 
     fn release_group_cache(&self) -> Option<HashMap<StateKey, BTreeMap<StructTag, Bytes>>> {
-        // Return empty: no caching
+        // Return empty: decoded groups stay cached until their key is written
         Some(HashMap::new())
     }
 }
@@ -308,7 +346,7 @@ impl AptosModuleStorage for AptosCustomState {
     ) -> PartialVMResult<Option<StateValueMetadata>> {
         let state_key = StateKey::module(address, module_name);
 
-        match self.kv_state.get(&state_key) {
+        match self.lookup(&state_key) {
             Some(state_value) => Ok(Some(state_value.metadata().clone())),
             None => Ok(None),
         }
@@ -322,7 +360,7 @@ impl ModuleStorage for AptosCustomState {
     #[doc = " Note: this API is not metered!"]
     fn unmetered_check_module_exists(&self, address: &AccountAddress, module_name: &IdentStr) -> VMResult<bool> {
         let module_id = ModuleId::new(*address, module_name.to_owned());
-        Ok(self.modules.contains_key(&module_id))
+        Ok(self.lookup_module(&module_id).is_some())
     }
 
     #[doc = " Returns module bytes if module exists, or [None] otherwise. An error is returned if there"]
@@ -331,7 +369,7 @@ impl ModuleStorage for AptosCustomState {
     #[doc = " Note: this API is not metered!"]
     fn unmetered_get_module_bytes(&self, address: &AccountAddress, module_name: &IdentStr) -> VMResult<Option<Bytes>> {
         let module_id = ModuleId::new(*address, module_name.to_owned());
-        Ok(self.modules.get(&module_id).cloned())
+        Ok(self.lookup_module(&module_id))
     }
 
     #[doc = " Returns the size of a module in bytes, or [None] otherwise. An error is returned if the"]
@@ -341,7 +379,7 @@ impl ModuleStorage for AptosCustomState {
     #[doc = " can actually be implemented before loading a module."]
     fn unmetered_get_module_size(&self, address: &AccountAddress, module_name: &IdentStr) -> VMResult<Option<usize>> {
         let module_id = ModuleId::new(*address, module_name.to_owned());
-        Ok(self.modules.get(&module_id).map(|bytes| bytes.len()))
+        Ok(self.lookup_module(&module_id).map(|bytes| bytes.len()))
     }
 
     #[doc = " Returns the metadata in the module, or [None] otherwise. An error is returned if there is"]
@@ -354,8 +392,8 @@ impl ModuleStorage for AptosCustomState {
         module_name: &IdentStr,
     ) -> VMResult<Option<Vec<Metadata>>> {
         let module_id = ModuleId::new(*address, module_name.to_owned());
-        match self.modules.get(&module_id) {
-            Some(bytes) => match CompiledModule::deserialize(bytes) {
+        match self.lookup_module(&module_id) {
+            Some(bytes) => match CompiledModule::deserialize(&bytes) {
                 Ok(module) => Ok(Some(module.metadata)),
                 Err(_) => Ok(None),
             },
@@ -374,8 +412,8 @@ impl ModuleStorage for AptosCustomState {
         module_name: &IdentStr,
     ) -> VMResult<Option<Arc<CompiledModule>>> {
         let module_id = ModuleId::new(*address, module_name.to_owned());
-        match self.modules.get(&module_id) {
-            Some(bytes) => match CompiledModule::deserialize(bytes) {
+        match self.lookup_module(&module_id) {
+            Some(bytes) => match CompiledModule::deserialize(&bytes) {
                 Ok(module) => Ok(Some(Arc::new(module))),
                 Err(_) => Ok(None),
             },
@@ -492,34 +530,18 @@ impl std::fmt::Debug for AptosCustomState {
             .field("modules_len", &self.modules.len())
             .field("scripts_deser_len", &self.scripts_deser.len())
             .field("scripts_verified_len", &self.scripts_verified.len())
+            .field("snapshots_len", &self.snapshots.len())
+            .field("forked", &self.fork.is_some())
+            .field("groups_len", &self.groups.len())
             .finish()
     }
 }
 
 impl AptosCustomState {
     pub fn new_default() -> Self {
-        // This mirrors aptos-core's AptosEnvironment defaults when on-chain configs are
-        // missing.
         let chain_id = ChainId::test();
         let features = Features::default();
-        let timed_features = TimedFeaturesBuilder::new(chain_id, 0).build();
-        let gas_feature_version = 0u64;
-        let mut builder = SafeNativeBuilder::new(
-            gas_feature_version,
-            NativeGasParameters::zeros(),
-            MiscGasParameters::zeros(),
-            timed_features.clone(),
-            features.clone(),
-            None,
-        );
-        let natives = aptos_natives_with_builder(&mut builder, false);
-        let vm_config = aptos_prod_vm_config(
-            gas_feature_version,
-            &features,
-            &timed_features,
-            aptos_default_ty_builder(),
-        );
-        let runtime_environment = RuntimeEnvironment::new_with_config(natives, vm_config);
+        let runtime_environment = Self::build_runtime_environment(chain_id, &features, None);
 
         // Seed essential on-chain config state with sane defaults works.
         let mut kv_state: HashMap<StateKey, StateValue> = HashMap::new();
@@ -542,23 +564,177 @@ impl AptosCustomState {
             scripts_deser: DashMap::new(),
             scripts_verified: DashMap::new(),
             runtime_environment,
+            snapshots: Vec::new(),
+            fork: None,
+            removed: HashSet::new(),
+            groups: DashMap::new(),
         };
 
         // Load and deploy Aptos framework bundle (includes move-stdlib, aptos-stdlib,
         // aptos-framework, etc.)
-        let bundle = head_release_bundle();
-        for (module_bytes, module) in bundle.code_and_compiled_modules() {
-            let module_id = module.self_id();
-            this.deploy_module_bytes(module_id.clone(), module_bytes.to_vec());
+        for (module_id, code) in Self::release_modules() {
+            this.insert_module(module_id.clone(), code.clone());
         }
 
         this
     }
 
+    /// Modules of the framework release the fuzzer is built with. Read from
+    /// the bundle once per process, so clients forked after the first state
+    /// was built set up theirs without deserializing it again.
+    fn release_modules() -> &'static [(ModuleId, Bytes)] {
+        static RELEASE_MODULES: OnceLock<Vec<(ModuleId, Bytes)>> = OnceLock::new();
+        RELEASE_MODULES.get_or_init(|| {
+            head_release_bundle()
+                .code_and_compiled_modules()
+                .into_iter()
+                .map(|(code, module)| (module.self_id(), Bytes::copy_from_slice(code)))
+                .collect()
+        })
+    }
+
+    /// State reading through to `fork` for every key not written locally.
+    /// The framework and on-chain configs come from the fork as well.
+    pub fn new_forked(fork: Arc<RestStateFetcher>) -> Self {
+        let runtime_environment = Self::build_runtime_environment(ChainId::test(), &Features::default(), None);
+        let mut this = Self {
+            kv_state: HashMap::new(),
+            tables: HashMap::new(),
+            modules: HashMap::new(),
+            scripts_deser: DashMap::new(),
+            scripts_verified: DashMap::new(),
+            runtime_environment,
+            snapshots: Vec::new(),
+            fork: Some(fork),
+            removed: HashSet::new(),
+            groups: DashMap::new(),
+        };
+        this.refresh_runtime_environment();
+        this
+    }
+
+    /// Mean latency of the fork's requests, if the state is forked and
+    /// fetched anything yet
+    pub fn fork_latency(&self) -> Option<Duration> {
+        self.fork.as_ref().and_then(|fork| fork.average_latency())
+    }
+
+    /// Natives are charged with `gas` (feature version and parameters), or
+    /// run for free without it
+    fn build_runtime_environment(
+        chain_id: ChainId,
+        features: &Features,
+        gas: Option<(u64, &AptosGasParameters)>,
+    ) -> RuntimeEnvironment {
+        // This mirrors aptos-core's AptosEnvironment defaults when on-chain configs are
+        // missing.
+        let timed_features = TimedFeaturesBuilder::new(chain_id, 0).build();
+        let (gas_feature_version, native_gas_params, misc_gas_params) = match gas {
+            Some((version, params)) => (version, params.natives.clone(), params.vm.misc.clone()),
+            None => (0u64, NativeGasParameters::zeros(), MiscGasParameters::zeros()),
+        };
+        let mut builder = SafeNativeBuilder::new(
+            gas_feature_version,
+            native_gas_params,
+            misc_gas_params,
+            timed_features.clone(),
+            features.clone(),
+            None,
+        );
+        let natives = aptos_natives_with_builder(&mut builder, false);
+        let vm_config = aptos_prod_vm_config(
+            gas_feature_version,
+            features,
+            &timed_features,
+            aptos_default_ty_builder(),
+        );
+        RuntimeEnvironment::new_with_config(natives, vm_config)
+    }
+
+    /// Rebuild the runtime environment from the chain id, features and gas
+    /// schedule currently in the state
+    fn refresh_runtime_environment(&mut self) {
+        let chain_id = ChainId::fetch_config(self).unwrap_or_else(ChainId::test);
+        let features = Features::fetch_config(self).unwrap_or_default();
+        let gas = GasScheduleV2::fetch_config(self).and_then(|schedule| {
+            let version = schedule.feature_version;
+            AptosGasParameters::from_on_chain_gas_schedule(&schedule.into_btree_map(), version)
+                .ok()
+                .map(|params| (version, params))
+        });
+        self.runtime_environment = Self::build_runtime_environment(
+            chain_id,
+            &features,
+            gas.as_ref().map(|(version, params)| (*version, params)),
+        );
+    }
+
+    /// Run executions with `chain_id`, overriding a forked one
+    pub fn set_chain_id(&mut self, chain_id: ChainId) -> anyhow::Result<()> {
+        let state_key = StateKey::on_chain_config::<ChainId>()?;
+        let bytes = bcs::to_bytes(&chain_id)?;
+        self.write(&state_key, Some(StateValue::new_legacy(bytes.into())));
+        self.refresh_runtime_environment();
+        Ok(())
+    }
+
+    /// Run executions with the `features` flags, overriding the default or
+    /// forked ones
+    pub fn set_features(&mut self, features: &Features) -> anyhow::Result<()> {
+        let state_key = StateKey::on_chain_config::<Features>()?;
+        let bytes = bcs::to_bytes(features)?;
+        self.write(&state_key, Some(StateValue::new_legacy(bytes.into())));
+        self.refresh_runtime_environment();
+        Ok(())
+    }
+
+    /// Install `schedule` as the on-chain gas schedule, which natives read
+    /// their gas parameters from and whole signed transactions are charged
+    /// with
+    pub fn set_gas_schedule(&mut self, schedule: &GasScheduleV2) -> anyhow::Result<()> {
+        AptosGasParameters::from_on_chain_gas_schedule(&schedule.clone().into_btree_map(), schedule.feature_version)
+            .map_err(|e| anyhow::anyhow!("invalid gas schedule: {e}"))?;
+        let state_key = StateKey::on_chain_config::<GasScheduleV2>()?;
+        let bytes = bcs::to_bytes(schedule)?;
+        self.write(&state_key, Some(StateValue::new_legacy(bytes.into())));
+        self.refresh_runtime_environment();
+        Ok(())
+    }
+
+    /// Remove the gas schedule, including a forked one, so natives get
+    /// zeroed gas parameters
+    pub fn clear_gas_schedule(&mut self) {
+        for state_key in [
+            StateKey::on_chain_config::<GasScheduleV2>(),
+            StateKey::on_chain_config::<GasSchedule>(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            self.write(&state_key, None);
+        }
+        self.refresh_runtime_environment();
+    }
+
+    /// Gas schedule of the framework release the fuzzer is built with
+    pub fn release_gas_schedule() -> GasScheduleV2 {
+        GasScheduleV2 {
+            feature_version: LATEST_GAS_FEATURE_VERSION,
+            entries: AptosGasParameters::initial().to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION),
+        }
+    }
+
+    /// Environment derived from the default state. Built once per process so
+    /// executors are cheap to create per fuzzing client.
     pub fn default_env() -> aptos_vm_environment::environment::AptosEnvironment {
-        let tmp = Self::new_default();
-        let view = crate::executor::custom_state_view::CustomStateView::new(&tmp);
-        aptos_vm_environment::environment::AptosEnvironment::new(&view)
+        static DEFAULT_ENV: OnceLock<aptos_vm_environment::environment::AptosEnvironment> = OnceLock::new();
+        DEFAULT_ENV
+            .get_or_init(|| {
+                let tmp = Self::new_default();
+                let view = crate::executor::custom_state_view::CustomStateView::new(&tmp);
+                aptos_vm_environment::environment::AptosEnvironment::new(&view)
+            })
+            .clone()
     }
 
     pub fn id(&self) -> StateViewId {
@@ -566,65 +742,345 @@ impl AptosCustomState {
     }
 
     pub fn get_state_value(&self, state_key: &StateKey) -> Option<StateValue> {
-        self.kv_state.get(state_key).cloned()
+        self.lookup(state_key)
+    }
+
+    /// Local value of `state_key`, falling back to the fork unless the key was
+    /// deleted locally
+    fn lookup(&self, state_key: &StateKey) -> Option<StateValue> {
+        if let Some(state_value) = self.kv_state.get(state_key) {
+            return Some(state_value.clone());
+        }
+        match &self.fork {
+            Some(fork) if !self.removed.contains(state_key) => fork.get(state_key),
+            _ => None,
+        }
+    }
+
+    /// Decoded resource group stored under `group_key`
+    fn resource_group(&self, group_key: &StateKey) -> PartialVMResult<Option<Arc<BTreeMap<StructTag, Bytes>>>> {
+        if let Some(group) = self.groups.get(group_key) {
+            return Ok(Some(group.clone()));
+        }
+        let Some(blob) = self.lookup(group_key) else {
+            return Ok(None);
+        };
+        let group: Arc<BTreeMap<StructTag, Bytes>> =
+            Arc::new(bcs::from_bytes(blob.bytes()).map_err(|_| unknown_status!())?);
+        self.groups.insert(group_key.clone(), group.clone());
+        Ok(Some(group))
+    }
+
+    fn lookup_table(&self, handle: &TableHandle, key: &[u8]) -> Option<Bytes> {
+        if let Some(bytes) = self.tables.get(&(*handle, key.to_vec())) {
+            return Some(bytes.clone());
+        }
+        if self.fork.is_none() {
+            return None;
+        }
+        let state_key = StateKey::table_item(&aptos_types::state_store::table::TableHandle(handle.0), key);
+        self.lookup(&state_key).map(|v| v.bytes().clone())
+    }
+
+    fn lookup_module(&self, module_id: &ModuleId) -> Option<Bytes> {
+        if let Some(bytes) = self.modules.get(module_id) {
+            return Some(bytes.clone());
+        }
+        if self.fork.is_none() {
+            return None;
+        }
+        let state_key = StateKey::module(module_id.address(), module_id.name());
+        self.lookup(&state_key).map(|v| v.bytes().clone())
     }
 
     // Apply WriteSet to in-memory state; mirror modules from code access paths.
+    // Inside a snapshot the overwritten entries are recorded so the writes can
+    // be rolled back.
     pub fn apply_write_set(&mut self, write_set: &WriteSet) {
         for (state_key, write_op) in write_set.write_op_iter() {
-            match state_key.inner() {
-                StateKeyInner::TableItem { handle, key } => {
-                    let table_handle = TableHandle(handle.0);
-                    match write_op.bytes() {
-                        Some(bytes) => {
-                            self.tables.insert((table_handle, key.clone()), bytes.clone());
-                        }
-                        None => {
-                            self.tables.remove(&(table_handle, key.clone()));
-                        }
+            self.write(state_key, write_op.as_state_value());
+        }
+    }
+
+    fn write(&mut self, state_key: &StateKey, value: Option<StateValue>) {
+        match state_key.inner() {
+            StateKeyInner::TableItem { handle, key } => {
+                let table_key = (TableHandle(handle.0), key.clone());
+                self.record_table(&table_key);
+                match value {
+                    Some(value) => {
+                        self.tables.insert(table_key, value.bytes().clone());
                     }
-                }
-                StateKeyInner::AccessPath(access_path) => {
-                    // Always update kv_state
-                    match write_op.as_state_value() {
-                        Some(state_value) => {
-                            self.kv_state.insert(state_key.clone(), state_value);
-                        }
-                        None => {
-                            self.kv_state.remove(state_key);
-                        }
+                    None => {
+                        self.tables.remove(&table_key);
+                        self.hide_forked(state_key);
                     }
-
-                    // If module code, also maintain modules cache
-                    if access_path.is_code() {
-                        if let Some(module_id) = access_path.try_get_module_id() {
-                            match write_op.bytes() {
-                                Some(bytes) => {
-                                    self.modules.insert(module_id, bytes.clone());
-                                }
-                                None => {
-                                    self.modules.remove(&module_id);
-                                }
+                }
+            }
+            StateKeyInner::AccessPath(access_path) => {
+                // If module code, also maintain modules cache
+                if access_path.is_code() {
+                    if let Some(module_id) = access_path.try_get_module_id() {
+                        self.record_module(&module_id);
+                        match &value {
+                            Some(value) => {
+                                self.modules.insert(module_id, value.bytes().clone());
+                            }
+                            None => {
+                                self.modules.remove(&module_id);
                             }
                         }
                     }
                 }
-                StateKeyInner::Raw(_) => match write_op.as_state_value() {
-                    Some(state_value) => {
-                        self.kv_state.insert(state_key.clone(), state_value);
-                    }
-                    None => {
-                        self.kv_state.remove(state_key);
-                    }
-                },
+
+                // Always update kv_state
+                self.write_kv(state_key, value);
+            }
+            StateKeyInner::Raw(_) => self.write_kv(state_key, value),
+        }
+    }
+
+    fn write_kv(&mut self, state_key: &StateKey, value: Option<StateValue>) {
+        self.record_kv(state_key);
+        self.groups.remove(state_key);
+        match value {
+            Some(value) => {
+                self.kv_state.insert(state_key.clone(), value);
+            }
+            None => {
+                self.kv_state.remove(state_key);
+                self.hide_forked(state_key);
+            }
+        }
+    }
+
+    /// Write the local entries, and those read from the fork so far, to `path`
+    /// so a prepared state can be reused with [`Self::load_snapshot`]
+    pub fn export_snapshot(&self, path: &Path) -> anyhow::Result<()> {
+        let mut entries: Vec<(StateKey, StateValue)> = self
+            .kv_state
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        for ((handle, key), bytes) in &self.tables {
+            let state_key = StateKey::table_item(&aptos_types::state_store::table::TableHandle(handle.0), key);
+            entries.push((state_key, StateValue::new_legacy(bytes.clone())));
+        }
+        if let Some(fork) = &self.fork {
+            let local: HashSet<StateKey> = entries.iter().map(|(key, _)| key.clone()).collect();
+            for (key, value) in fork.fetched() {
+                if !local.contains(&key) && !self.removed.contains(&key) {
+                    entries.push((key, value));
+                }
+            }
+        }
+
+        let exported = ExportedState {
+            entries,
+            removed: self.removed.iter().cloned().collect(),
+        };
+        fs::write(path, bcs::to_bytes(&exported)?)?;
+        Ok(())
+    }
+
+    /// Write the entries saved by [`Self::export_snapshot`] into this state
+    pub fn load_snapshot(&mut self, path: &Path) -> anyhow::Result<()> {
+        let exported: ExportedState = bcs::from_bytes(&fs::read(path)?)?;
+        for (key, value) in exported.entries {
+            self.write(&key, Some(value));
+        }
+        for key in exported.removed {
+            self.write(&key, None);
+        }
+        Ok(())
+    }
+
+    /// Open a snapshot; writes applied until the matching [`Self::rollback`]
+    /// or [`Self::commit`] can be undone. Snapshots nest.
+    pub fn snapshot(&mut self) {
+        self.snapshots.push(Snapshot::default());
+    }
+
+    /// Undo every write applied since the innermost open snapshot and close it
+    pub fn rollback(&mut self) {
+        let Some(snapshot) = self.snapshots.pop() else {
+            return;
+        };
+        for (key, value) in snapshot.kv_state {
+            self.groups.remove(&key);
+            match value {
+                Some(value) => self.kv_state.insert(key, value),
+                None => self.kv_state.remove(&key),
+            };
+        }
+        for (key, value) in snapshot.tables {
+            match value {
+                Some(value) => self.tables.insert(key, value),
+                None => self.tables.remove(&key),
+            };
+        }
+        for (key, value) in snapshot.modules {
+            match value {
+                Some(value) => self.modules.insert(key, value),
+                None => self.modules.remove(&key),
+            };
+        }
+        for (key, removed) in snapshot.removed {
+            self.groups.remove(&key);
+            if removed {
+                self.removed.insert(key);
+            } else {
+                self.removed.remove(&key);
+            }
+        }
+    }
+
+    /// Keep the writes applied since the innermost open snapshot and close it.
+    /// An enclosing snapshot can still roll them back.
+    pub fn commit(&mut self) {
+        let Some(snapshot) = self.snapshots.pop() else {
+            return;
+        };
+        if let Some(parent) = self.snapshots.last_mut() {
+            // The parent keeps its own prior value for entries it already saw
+            for (key, value) in snapshot.kv_state {
+                parent.kv_state.entry(key).or_insert(value);
+            }
+            for (key, value) in snapshot.tables {
+                parent.tables.entry(key).or_insert(value);
+            }
+            for (key, value) in snapshot.modules {
+                parent.modules.entry(key).or_insert(value);
+            }
+            for (key, removed) in snapshot.removed {
+                parent.removed.entry(key).or_insert(removed);
+            }
+        }
+    }
+
+    fn record_kv(&mut self, state_key: &StateKey) {
+        if let Some(snapshot) = self.snapshots.last_mut() {
+            if !snapshot.kv_state.contains_key(state_key) {
+                snapshot
+                    .kv_state
+                    .insert(state_key.clone(), self.kv_state.get(state_key).cloned());
+            }
+        }
+    }
+
+    fn record_table(&mut self, table_key: &(TableHandle, Vec<u8>)) {
+        if let Some(snapshot) = self.snapshots.last_mut() {
+            if !snapshot.tables.contains_key(table_key) {
+                snapshot
+                    .tables
+                    .insert(table_key.clone(), self.tables.get(table_key).cloned());
             }
         }
     }
 
+    fn record_module(&mut self, module_id: &ModuleId) {
+        if let Some(snapshot) = self.snapshots.last_mut() {
+            if !snapshot.modules.contains_key(module_id) {
+                snapshot
+                    .modules
+                    .insert(module_id.clone(), self.modules.get(module_id).cloned());
+            }
+        }
+    }
+
+    // Deleting a key must also hide its forked value, or the next read would
+    // fetch it again
+    fn hide_forked(&mut self, state_key: &StateKey) {
+        if self.fork.is_none() {
+            return;
+        }
+        if let Some(snapshot) = self.snapshots.last_mut() {
+            if !snapshot.removed.contains_key(state_key) {
+                snapshot
+                    .removed
+                    .insert(state_key.clone(), self.removed.contains(state_key));
+            }
+        }
+        self.removed.insert(state_key.clone());
+    }
+
+    /// Write a `0x1::table` entry; like transaction writes it can be rolled
+    /// back while a snapshot is open
+    pub fn set_table_entry(&mut self, handle: AccountAddress, key: &[u8], value: Vec<u8>) {
+        let state_key = StateKey::table_item(&aptos_types::state_store::table::TableHandle(handle), key);
+        self.write(&state_key, Some(StateValue::new_legacy(value.into())));
+    }
+
+    /// Local table entries as (handle, key, value)
+    pub fn table_entries(&self) -> impl Iterator<Item = (AccountAddress, &[u8], &Bytes)> {
+        self.tables
+            .iter()
+            .map(|((handle, key), value)| (handle.0, key.as_slice(), value))
+    }
+
+    /// Create an account with a `0x1::account::Account` resource and an
+    /// AptosCoin `CoinStore` holding `balance` octas, so entry functions
+    /// taking `&signer` can run as this address
+    pub fn create_funded_account(&mut self, address: AccountAddress, balance: u64) {
+        let account = AccountResource::new(
+            0,
+            address.to_vec(),
+            EventHandle::new(EventKey::new(0, address), 0),
+            EventHandle::new(EventKey::new(1, address), 0),
+        );
+        let coin_store = CoinStoreResource::<AptosCoinType>::new(
+            balance,
+            false,
+            EventHandle::new(EventKey::new(2, address), 0),
+            EventHandle::new(EventKey::new(3, address), 0),
+        );
+        self.insert_resource(&address, &account);
+        self.insert_resource(&address, &coin_store);
+    }
+
+    fn insert_resource<T: MoveResource + serde::Serialize>(&mut self, address: &AccountAddress, resource: &T) {
+        let (Ok(state_key), Ok(bytes)) = (StateKey::resource_typed::<T>(address), bcs::to_bytes(resource)) else {
+            warn!(resource = %T::struct_identifier(), %address, "failed to create resource");
+            return;
+        };
+        self.kv_state.insert(state_key, StateValue::new_legacy(bytes.into()));
+    }
+
     pub fn deploy_module_bytes(&mut self, module_id: ModuleId, code: Vec<u8>) {
-        let bytes = Bytes::from(code);
+        self.insert_module(module_id, Bytes::from(code));
+    }
+
+    fn insert_module(&mut self, module_id: ModuleId, bytes: Bytes) {
         let state_key = StateKey::module(module_id.address(), module_id.name());
-        self.modules.insert(module_id.clone(), bytes.clone());
+        self.modules.insert(module_id, bytes.clone());
         self.kv_state.insert(state_key, StateValue::new_legacy(bytes));
     }
+
+    /// Publish every module of a framework release, replacing the ones of
+    /// the release the fuzzer is built with. Returns the number of modules.
+    pub fn deploy_bundle(&mut self, bundle: &ReleaseBundle) -> usize {
+        let modules = bundle.code_and_compiled_modules();
+        for (code, module) in &modules {
+            self.deploy_module_bytes(module.self_id(), code.to_vec());
+        }
+        modules.len()
+    }
+
+    /// Replace a published module with a new version of it, as a package
+    /// upgrade would, keeping the resources the old version stored. The new
+    /// version has to pass the same compatibility check as on chain.
+    pub fn upgrade_module(&mut self, code: Vec<u8>) -> anyhow::Result<ModuleId> {
+        let new_module = CompiledModule::deserialize(&code).map_err(|e| anyhow::anyhow!("invalid module: {e}"))?;
+        let module_id = new_module.self_id();
+        let Some(old_code) = self.lookup_module(&module_id) else {
+            anyhow::bail!("module {module_id} isn't published");
+        };
+        let old_module = CompiledModule::deserialize(&old_code)
+            .map_err(|e| anyhow::anyhow!("invalid published module {module_id}: {e}"))?;
+        Compatibility::full_check()
+            .check(&old_module, &new_module)
+            .map_err(|e| anyhow::anyhow!("incompatible upgrade of {module_id}: {e}"))?;
+        self.deploy_module_bytes(module_id.clone(), code);
+        Ok(module_id)
+    }
 }
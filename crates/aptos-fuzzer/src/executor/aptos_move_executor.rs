@@ -1,29 +1,81 @@
+use std::collections::{BTreeMap, HashSet};
 use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
-use aptos_move_core_types::vm_status::{StatusCode, VMStatus};
-use aptos_types::transaction::{ExecutionStatus, TransactionPayload, TransactionStatus};
+use aptos_move_binary_format::access::ModuleAccess;
+use aptos_move_binary_format::file_format::Bytecode;
+use aptos_move_binary_format::CompiledModule;
+use aptos_move_core_types::vm_status::{AbortLocation, StatusCode, StatusType, VMStatus};
+use aptos_types::account_config::AccountResource;
+use aptos_types::chain_id::ChainId;
+use aptos_types::on_chain_config::{Features, GasSchedule, GasScheduleV2, OnChainConfig, Version};
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::transaction::authenticator::AccountAuthenticator;
+use aptos_types::transaction::{
+    EntryFunction, ExecutionStatus, MultisigTransactionPayload, RawTransaction, SignedTransaction, TransactionPayload,
+    TransactionStatus,
+};
+use aptos_types::write_set::{WriteOp, WriteSet, WriteSetMut};
 use aptos_vm::aptos_vm::ExecOutcomeKind;
-use aptos_vm::AptosVM;
+use aptos_vm::{AptosSimulationVM, AptosVM};
 use libafl::executors::{Executor, ExitKind, HasObservers};
 use libafl::observers::map::{HitcountsMapObserver, OwnedMapObserver};
 use libafl::state::HasExecutions;
 use libafl_bolts::tuples::RefIndexable;
 use libafl_bolts::AsSliceMut;
+use tracing::{debug, debug_span, trace};
 
+use crate::events::{EventInvariants, EventViolation};
 use crate::executor::aptos_custom_state::AptosCustomState;
+use crate::executor::bare_vm;
 use crate::executor::custom_state_view::CustomStateView;
-use crate::executor::types::TransactionResult;
-use crate::observers::{AbortCodeObserver, ShiftOverflowObserver};
+use crate::executor::types::{ArithmeticEvent, BatchResult, ShiftEvent, TransactionResult};
+use crate::executor::watchdog::HangWatchdog;
+use crate::input::AptosCall;
+use crate::layout::LayoutCache;
+use crate::observers::{
+    AbortCodeObserver, AbortSite, ArithmeticObserver, BareVmObserver, EventObserver, GasObserver,
+    ShiftOverflowObserver, ViewObserver, WriteSetObserver,
+};
+use crate::views::ViewChecks;
+use crate::write_rules::WriteRules;
 use crate::{AptosFuzzerInput, AptosFuzzerState};
 
-// Type aliases to simplify complex observer tuple types
-type AptosObservers = (
+/// Observers of [`AptosMoveExecutor`], in the order of its accessors
+pub type AptosObservers = (
     HitcountsMapObserver<OwnedMapObserver<u8>>,
-    (AbortCodeObserver, (ShiftOverflowObserver, ())),
+    (
+        AbortCodeObserver,
+        (
+            ShiftOverflowObserver,
+            (
+                GasObserver,
+                (
+                    ArithmeticObserver,
+                    (EventObserver, (WriteSetObserver, (ViewObserver, (BareVmObserver, ())))),
+                ),
+            ),
+        ),
+    ),
 );
 
 const MAP_SIZE: usize = 1 << 16;
 
+/// Name of the edge coverage observer, which also names the coverage history
+/// kept by map feedbacks built on it
+pub const EDGES_OBSERVER_NAME: &str = "edges";
+
+/// How far past the timeout an execution may run before the watchdog kills
+/// the process
+const HANG_TIMEOUT_FACTOR: u32 = 10;
+
+/// Gas budget and price of calls run as signed transactions; the payer is
+/// charged up to their product
+const SIGNED_MAX_GAS_AMOUNT: u64 = 2_000_000;
+const SIGNED_GAS_UNIT_PRICE: u64 = 100;
+
 pub struct AptosMoveExecutor<EM, Z> {
     aptos_vm: AptosVM,
     _phantom: PhantomData<(EM, Z)>,
@@ -32,23 +84,156 @@ pub struct AptosMoveExecutor<EM, Z> {
     error_count: u64,
     observers: AptosObservers,
     prev_loc: u32,
+    // Keep each execution's write set instead of rolling it back
+    persist_writes: bool,
+    // Execution budget; exceeding it reports ExitKind::Timeout
+    timeout: Option<Duration>,
+    gas_budget: Option<u64>,
+    watchdog: Option<HangWatchdog>,
+    // Call functions in a bare MoveVM session instead of as transactions
+    bare_vm: bool,
+    // Also call entry functions in a bare MoveVM session and compare outcomes
+    compare_bare_vm: bool,
+    // Events are only recorded when invariants check them
+    event_invariants: EventInvariants,
+    write_rules: WriteRules,
+    view_checks: ViewChecks,
+    layouts: LayoutCache,
 }
 
 impl<EM, Z> AptosMoveExecutor<EM, Z> {
     pub fn new() -> Self {
         let env = super::aptos_custom_state::AptosCustomState::default_env();
-        let edges = OwnedMapObserver::new("edges", vec![0u8; MAP_SIZE]);
+        let edges = OwnedMapObserver::new(EDGES_OBSERVER_NAME, vec![0u8; MAP_SIZE]);
         let edges = HitcountsMapObserver::new(edges);
         let abort_obs = AbortCodeObserver::new();
         let shift_obs = ShiftOverflowObserver::new();
+        let gas_obs = GasObserver::new();
+        let arith_obs = ArithmeticObserver::new();
+        let event_obs = EventObserver::new();
+        let write_obs = WriteSetObserver::new();
+        let view_obs = ViewObserver::new();
+        let bare_vm_obs = BareVmObserver::new();
         Self {
             aptos_vm: AptosVM::new_fuzzer(&env),
             _phantom: PhantomData,
             success_count: 0,
             error_count: 0,
-            observers: (edges, (abort_obs, (shift_obs, ()))),
+            observers: (
+                edges,
+                (
+                    abort_obs,
+                    (
+                        shift_obs,
+                        (
+                            gas_obs,
+                            (arith_obs, (event_obs, (write_obs, (view_obs, (bare_vm_obs, ()))))),
+                        ),
+                    ),
+                ),
+            ),
             prev_loc: 0,
+            persist_writes: false,
+            timeout: None,
+            gas_budget: None,
+            watchdog: None,
+            bare_vm: false,
+            compare_bare_vm: false,
+            event_invariants: EventInvariants::default(),
+            write_rules: WriteRules::default(),
+            view_checks: ViewChecks::default(),
+            layouts: LayoutCache::new(),
+        }
+    }
+
+    /// Run on the environment (features, gas schedule) configured on-chain
+    /// in `state` rather than the defaults, e.g. for a forked network
+    pub fn with_environment_of(mut self, state: &AptosCustomState) -> Self {
+        self.rebuild_vm(state);
+        self
+    }
+
+    fn rebuild_vm(&mut self, state: &AptosCustomState) {
+        let env = aptos_vm_environment::environment::AptosEnvironment::new(&CustomStateView::new(state));
+        self.aptos_vm = AptosVM::new_fuzzer(&env);
+    }
+
+    /// Keep the writes of every successful execution in the state so later
+    /// inputs observe them (stateful fuzzing). By default each execution runs
+    /// against the same state and its writes are rolled back afterwards.
+    pub fn with_persist_writes(mut self, persist_writes: bool) -> Self {
+        self.persist_writes = persist_writes;
+        self
+    }
+
+    /// Report executions taking longer than `timeout` as
+    /// [`ExitKind::Timeout`]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Report executions running more than `gas_budget` instructions as
+    /// [`ExitKind::Timeout`]; the rest of a sequence is skipped once the
+    /// budget is spent
+    pub fn with_gas_budget(mut self, gas_budget: u64) -> Self {
+        self.gas_budget = Some(gas_budget);
+        self
+    }
+
+    /// Flag executions running more than `ceiling` instructions as potential
+    /// DoS; unlike the gas budget they run to completion
+    pub fn with_instruction_ceiling(mut self, ceiling: u64) -> Self {
+        self.observers.1 .1 .1 .0.set_ceiling(Some(ceiling));
+        self
+    }
+
+    /// Kill the process when an execution runs far past the timeout (a VM
+    /// stuck in a loop never returns), saving the input to `dump_dir` first.
+    /// Requires [`Self::with_timeout`].
+    pub fn watch_hangs(mut self, dump_dir: PathBuf) -> Self {
+        if let Some(timeout) = self.timeout {
+            self.watchdog = Some(HangWatchdog::spawn(timeout * HANG_TIMEOUT_FACTOR, dump_dir));
         }
+        self
+    }
+
+    /// Call entry payloads' functions directly in a bare MoveVM session, see
+    /// [`bare_vm::execute_function`], for the throughput of library code.
+    /// Such calls may target any function. They keep no writes and no trace:
+    /// coverage comes from their return values and no shifts are reported.
+    pub fn with_bare_vm(mut self, bare_vm: bool) -> Self {
+        self.bare_vm = bare_vm;
+        self
+    }
+
+    /// Call entry functions run as plain transactions in a bare MoveVM
+    /// session too, on the same state, and record the calls whose outcomes
+    /// differ, see [`bare_vm::divergence`]. Calls run as signed transactions
+    /// have a prologue the bare VM lacks, so they aren't compared.
+    pub fn with_bare_vm_comparison(mut self, compare: bool) -> Self {
+        self.compare_bare_vm = compare;
+        self
+    }
+
+    /// Record the events of each execution, decoding the fields `invariants`
+    /// check
+    pub fn with_event_invariants(mut self, invariants: EventInvariants) -> Self {
+        self.event_invariants = invariants;
+        self
+    }
+
+    /// Check the write set of every call against `rules`
+    pub fn with_write_rules(mut self, rules: WriteRules) -> Self {
+        self.write_rules = rules;
+        self
+    }
+
+    /// Call the view functions of `checks` before the first call and after
+    /// every successful one, comparing their results
+    pub fn with_view_checks(mut self, checks: ViewChecks) -> Self {
+        self.view_checks = checks;
+        self
     }
 
     #[inline]
@@ -69,6 +254,108 @@ impl<EM, Z> AptosMoveExecutor<EM, Z> {
         &mut self.observers.0
     }
 
+    pub fn abort_observer(&self) -> &AbortCodeObserver {
+        &self.observers.1 .0
+    }
+
+    pub fn shift_observer(&self) -> &ShiftOverflowObserver {
+        &self.observers.1 .1 .0
+    }
+
+    pub fn gas_observer(&self) -> &GasObserver {
+        &self.observers.1 .1 .1 .0
+    }
+
+    pub fn arithmetic_observer(&self) -> &ArithmeticObserver {
+        &self.observers.1 .1 .1 .1 .0
+    }
+
+    pub fn event_observer(&self) -> &EventObserver {
+        &self.observers.1 .1 .1 .1 .1 .0
+    }
+
+    pub fn write_set_observer(&self) -> &WriteSetObserver {
+        &self.observers.1 .1 .1 .1 .1 .1 .0
+    }
+
+    pub fn view_observer(&self) -> &ViewObserver {
+        &self.observers.1 .1 .1 .1 .1 .1 .1 .0
+    }
+
+    pub fn bare_vm_observer(&self) -> &BareVmObserver {
+        &self.observers.1 .1 .1 .1 .1 .1 .1 .1 .0
+    }
+
+    /// Event invariants the last execution broke
+    pub fn event_violations(&self) -> Vec<EventViolation> {
+        let observer = self.event_observer();
+        self.event_invariants
+            .violations(observer.events(), observer.completed())
+    }
+
+    /// Execute BCS-encoded `SignedTransaction`s in order, each observing the
+    /// writes of the previous ones, and return their results with the
+    /// combined write set. Signatures are not checked and `state` is left
+    /// unchanged.
+    pub fn simulate_batch(
+        &mut self,
+        state: &mut AptosCustomState,
+        transactions: &[Vec<u8>],
+    ) -> anyhow::Result<BatchResult> {
+        let transactions = transactions
+            .iter()
+            .map(|bytes| bcs::from_bytes::<SignedTransaction>(bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut results = Vec::with_capacity(transactions.len());
+        let mut writes: BTreeMap<StateKey, WriteOp> = BTreeMap::new();
+        state.snapshot();
+        for transaction in transactions {
+            let authenticator = transaction.authenticator_ref();
+            let (result, _, _, _) = if authenticator.fee_payer_address().is_some() ||
+                !authenticator.secondary_signer_addresses().is_empty() ||
+                matches!(transaction.payload(), TransactionPayload::Multisig(_))
+            {
+                Self::execute_signed(&transaction, state)
+            } else {
+                self.execute_transaction(transaction.payload().clone(), state, Some(transaction.sender()))
+            };
+            if let Ok(result) = &result {
+                state.apply_write_set(&result.write_set);
+                for (key, write_op) in result.write_set.write_op_iter() {
+                    writes.insert(key.clone(), write_op.clone());
+                }
+            }
+            results.push(result);
+        }
+        state.rollback();
+
+        Ok(BatchResult {
+            results,
+            write_set: WriteSetMut::new(writes).freeze()?,
+        })
+    }
+
+    /// Execute `call` against `state` as the fuzzing loop does, as a whole
+    /// signed transaction if it has a fee payer, secondary signers or a
+    /// multisig payload
+    pub fn execute_call(
+        &mut self,
+        call: &AptosCall,
+        state: &AptosCustomState,
+    ) -> (
+        core::result::Result<TransactionResult, VMStatus>,
+        ExecOutcomeKind,
+        Vec<u32>,
+        Vec<ShiftEvent>,
+    ) {
+        if call.needs_signed_transaction() {
+            Self::execute_signed(&Self::signed_transaction(call, state), state)
+        } else {
+            self.execute_transaction(call.payload().clone(), state, call.sender())
+        }
+    }
+
     pub fn execute_transaction(
         &mut self,
         transaction: TransactionPayload,
@@ -78,9 +365,10 @@ impl<EM, Z> AptosMoveExecutor<EM, Z> {
         core::result::Result<TransactionResult, VMStatus>,
         ExecOutcomeKind,
         Vec<u32>,
-        Vec<bool>,
+        Vec<ShiftEvent>,
     ) {
         match &transaction {
+            TransactionPayload::EntryFunction(entry) if self.bare_vm => Self::execute_bare(entry, state, sender),
             TransactionPayload::EntryFunction(_) | TransactionPayload::Script(_) => {
                 let view = CustomStateView::new(state);
                 let code_storage =
@@ -90,21 +378,34 @@ impl<EM, Z> AptosMoveExecutor<EM, Z> {
                     self.aptos_vm
                         .execute_user_payload_no_checking(state, &code_storage, &transaction, sender);
                 // Only transform minimal data for caller; no processing here
-                let shift_losses: Vec<bool> = shifts.iter().map(|ev| ev.lost_high_bits).collect();
+                let shift_events: Vec<ShiftEvent> = shifts
+                    .iter()
+                    .map(|ev| ShiftEvent {
+                        module: ev.module_id.to_string(),
+                        function: ev.function.to_string(),
+                        pc: u64::from(ev.pc),
+                        value: ev.value.to_string(),
+                        shift_amount: u64::from(ev.shift_amount),
+                        lost_high_bits: ev.lost_high_bits,
+                    })
+                    .collect();
+                // Payloads run without a gas meter, so instructions stand in
+                // for gas
+                let gas_used = pcs.len() as u64;
 
                 let res = match result {
                     Ok((write_set, events)) => Ok(TransactionResult {
                         status: aptos_types::transaction::TransactionStatus::Keep(
                             aptos_types::vm_status::KeptVMStatus::Executed.into(),
                         ),
-                        gas_used: 0,
+                        gas_used,
                         write_set,
                         events,
                         fee_statement: None,
                     }),
                     Err(e) => Err(e),
                 };
-                (res, outcome, pcs, shift_losses)
+                (res, outcome, pcs, shift_events)
             }
             _ => (
                 Err(VMStatus::Error {
@@ -120,6 +421,144 @@ impl<EM, Z> AptosMoveExecutor<EM, Z> {
     }
 }
 
+impl<EM, Z> AptosMoveExecutor<EM, Z> {
+    /// Run `entry` with [`bare_vm::execute_function`]. The session keeps no
+    /// instruction trace, so the returned values stand in for the PCs: one
+    /// per return value and bit length of its encoding, which tells results
+    /// of different magnitudes apart. No shifts are traced and the change
+    /// set is discarded, so the result has none and an empty write set.
+    fn execute_bare(
+        entry: &EntryFunction,
+        state: &AptosCustomState,
+        sender: Option<aptos_move_core_types::account_address::AccountAddress>,
+    ) -> (
+        core::result::Result<TransactionResult, VMStatus>,
+        ExecOutcomeKind,
+        Vec<u32>,
+        Vec<ShiftEvent>,
+    ) {
+        let sender = sender.unwrap_or(aptos_move_core_types::account_address::AccountAddress::ZERO);
+        match bare_vm::execute_function(state, entry, sender) {
+            Ok(return_values) => {
+                let outcomes = return_values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, bytes)| ((index as u32) << 16) | bit_length(bytes))
+                    .collect();
+                let result = TransactionResult {
+                    status: TransactionStatus::Keep(aptos_types::vm_status::KeptVMStatus::Executed.into()),
+                    gas_used: 0,
+                    write_set: WriteSet::default(),
+                    events: Vec::new(),
+                    fee_statement: None,
+                };
+                (Ok(result), ExecOutcomeKind::Ok, outcomes, Vec::new())
+            }
+            Err(error) => {
+                let status = error.into_vm_status();
+                let outcome = match status.status_type() {
+                    StatusType::InvariantViolation => ExecOutcomeKind::InvariantViolation,
+                    _ => ExecOutcomeKind::OtherError,
+                };
+                (Err(status), outcome, Vec::new(), Vec::new())
+            }
+        }
+    }
+}
+
+impl<EM, Z> AptosMoveExecutor<EM, Z> {
+    /// Run `transaction` whole, prologue and epilogue included, as fee-payer,
+    /// multi-agent and multisig transactions need: the prologue checks the
+    /// payer, the secondary signers and the multisig approval, and the
+    /// epilogue charges the payer.
+    /// Signatures are not checked. The VM keeps no instruction trace on
+    /// this path, so a hash of the status stands in for the PCs.
+    fn execute_signed(
+        transaction: &SignedTransaction,
+        state: &AptosCustomState,
+    ) -> (
+        core::result::Result<TransactionResult, VMStatus>,
+        ExecOutcomeKind,
+        Vec<u32>,
+        Vec<ShiftEvent>,
+    ) {
+        let view = CustomStateView::new(state);
+        let (vm_status, output) = AptosSimulationVM::create_vm_and_simulate_signed_transaction(transaction, &view);
+        let outcome = vec![Self::hash32(format!("{:?}", output.status()).as_bytes())];
+        match output.status() {
+            TransactionStatus::Keep(_) => {
+                let result = TransactionResult {
+                    status: output.status().clone(),
+                    gas_used: output.gas_used(),
+                    write_set: output.write_set().clone(),
+                    events: output.events().to_vec(),
+                    fee_statement: output.try_extract_fee_statement().ok().flatten(),
+                };
+                (Ok(result), ExecOutcomeKind::Ok, outcome, Vec::new())
+            }
+            // Rejected by the prologue
+            _ => {
+                let kind = match vm_status.status_type() {
+                    StatusType::InvariantViolation => ExecOutcomeKind::InvariantViolation,
+                    _ => ExecOutcomeKind::OtherError,
+                };
+                (Err(vm_status), kind, outcome, Vec::new())
+            }
+        }
+    }
+
+    /// `call` as an unsigned transaction with the sender's next sequence
+    /// number. Without a sender it is sent by `0x1`.
+    fn signed_transaction(call: &AptosCall, state: &AptosCustomState) -> SignedTransaction {
+        let sender = call
+            .sender()
+            .unwrap_or(aptos_move_core_types::account_address::AccountAddress::ONE);
+        let sequence_number = StateKey::resource_typed::<AccountResource>(&sender)
+            .ok()
+            .and_then(|key| state.get_state_value(&key))
+            .and_then(|value| bcs::from_bytes::<AccountResource>(value.bytes()).ok())
+            .map_or(0, |account| account.sequence_number());
+        let raw = RawTransaction::new(
+            sender,
+            sequence_number,
+            call.payload().clone(),
+            SIGNED_MAX_GAS_AMOUNT,
+            SIGNED_GAS_UNIT_PRICE,
+            u64::MAX,
+            ChainId::fetch_config(state).unwrap_or_else(ChainId::test),
+        );
+
+        let secondary_signers = call.secondary_signers().to_vec();
+        let unsigned = vec![AccountAuthenticator::NoAccountAuthenticator; secondary_signers.len()];
+        match call.fee_payer() {
+            Some(fee_payer) => SignedTransaction::new_fee_payer(
+                raw,
+                AccountAuthenticator::NoAccountAuthenticator,
+                secondary_signers,
+                unsigned,
+                fee_payer,
+                AccountAuthenticator::NoAccountAuthenticator,
+            ),
+            None if !secondary_signers.is_empty() => SignedTransaction::new_multi_agent(
+                raw,
+                AccountAuthenticator::NoAccountAuthenticator,
+                secondary_signers,
+                unsigned,
+            ),
+            None => SignedTransaction::new_single_sender(raw, AccountAuthenticator::NoAccountAuthenticator),
+        }
+    }
+}
+
+/// Bits up to the highest set one of a little-endian encoding, such as an
+/// integer's BCS
+fn bit_length(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .map_or(0, |index| index as u32 * 8 + (8 - bytes[index].leading_zeros()))
+}
+
 impl<EM, Z> Default for AptosMoveExecutor<EM, Z> {
     fn default() -> Self {
         Self::new()
@@ -134,80 +573,279 @@ impl<EM, Z> Executor<EM, AptosFuzzerInput, AptosFuzzerState, Z> for AptosMoveExe
         _mgr: &mut EM,
         input: &AptosFuzzerInput,
     ) -> Result<ExitKind, libafl::Error> {
-        let (result, outcome, pcs, shift_losses) =
-            self.execute_transaction(input.payload().clone(), state.aptos_state(), None);
-        match result {
-            Ok(result) => {
-                self.success_count += 1;
-                // Build AFL-style edge coverage map from pcs
-                let map = self.observers.0.as_slice_mut();
-                for b in map.iter_mut() {
-                    *b = 0;
+        // Reset coverage map to a clean state; calls of a sequence accumulate
+        // into it
+        for b in self.observers.0.as_slice_mut().iter_mut() {
+            *b = 0;
+        }
+        self.observers.1 .0.set_last(None);
+        let mut gas_used = 0u64;
+        let mut call_gas = Vec::with_capacity(input.calls().len());
+        let mut overflows = Vec::new();
+        let mut arithmetic_events = Vec::new();
+        let mut events = Vec::new();
+        let mut completed = !input.calls().is_empty();
+        let mut write_changes = Vec::new();
+        let mut write_violations = Vec::new();
+        let mut view_violations = Vec::new();
+        let mut divergences = Vec::new();
+        let mut exit_kind = ExitKind::Ok;
+        let mut config_changed = false;
+
+        let _span = debug_span!("execution", id = *state.executions(), calls = input.calls().len()).entered();
+        let started = Instant::now();
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.start(input);
+        }
+
+        // Calls run on a snapshot so every input starts from the same state
+        // unless writes are persisted
+        state.aptos_state_mut().snapshot();
+        // Table overrides are rolled back with the calls' writes
+        for entry in input.tables() {
+            state
+                .aptos_state_mut()
+                .set_table_entry(entry.handle, &entry.key, entry.value.clone());
+        }
+        let mut view_values = if self.view_checks.is_empty() {
+            Vec::new()
+        } else {
+            self.view_checks.values(&mut self.layouts, state.aptos_state())
+        };
+        for (index, call) in input.calls().iter().enumerate() {
+            let (result, outcome, pcs, shifts) = self.execute_call(call, state.aptos_state());
+            trace!(?outcome, instructions = pcs.len(), "call executed");
+            gas_used += pcs.len() as u64;
+            call_gas.push(pcs.len() as u64);
+            overflows.extend(shifts.into_iter().filter(|shift| shift.lost_high_bits));
+            if self.compare_bare_vm && !self.bare_vm && !call.needs_signed_transaction() {
+                if let TransactionPayload::EntryFunction(entry) = call.payload() {
+                    let sender = call
+                        .sender()
+                        .unwrap_or(aptos_move_core_types::account_address::AccountAddress::ZERO);
+                    let bare = bare_vm::execute_function(state.aptos_state(), entry, sender)
+                        .map_err(|error| error.into_vm_status());
+                    divergences.extend(bare_vm::divergence(entry, &result, &bare));
                 }
-                self.prev_loc = 0;
-                // Build a stable per-function base id to reduce inter-function collisions
-                let base_id: u32 = match input.payload() {
-                    TransactionPayload::EntryFunction(ef) => {
-                        let (module, function, _ty_args, _args) = ef.clone().into_inner();
-                        let mut buf = Vec::new();
-                        buf.extend_from_slice(module.address().as_ref());
-                        buf.extend_from_slice(module.name().as_str().as_bytes());
-                        buf.extend_from_slice(function.as_str().as_bytes());
-                        Self::hash32(&buf)
+            }
+            match result {
+                Ok(result) => {
+                    self.success_count += 1;
+                    self.record_coverage(call.payload(), pcs);
+                    completed &= matches!(result.status, TransactionStatus::Keep(ExecutionStatus::Success));
+                    if let TransactionStatus::Keep(ExecutionStatus::MoveAbort { location, code, .. }) = &result.status {
+                        self.observers
+                            .1
+                             .0
+                            .set_last(Some(Self::abort_site(call, location, *code)));
+                    }
+                    if !self.write_rules.is_empty() {
+                        let changes =
+                            self.write_rules
+                                .changes(&mut self.layouts, state.aptos_state(), &result.write_set);
+                        write_violations.extend(self.write_rules.violations(&changes));
+                        write_changes.extend(changes);
+                    }
+                    // Later calls of the sequence observe these writes
+                    state.aptos_state_mut().apply_write_set(&result.write_set);
+                    if !self.event_invariants.is_empty() {
+                        for event in &result.events {
+                            events.push(
+                                self.event_invariants
+                                    .decode(&mut self.layouts, state.aptos_state(), event),
+                            );
+                        }
+                    }
+                    if !self.view_checks.is_empty() {
+                        let values = self.view_checks.values(&mut self.layouts, state.aptos_state());
+                        view_violations.extend(self.view_checks.violations(index, &view_values, &values));
+                        view_values = values;
+                    }
+                    // The VM is reused across executions and only rebuilt when
+                    // the environment it was built from changes
+                    if result.write_set.write_op_iter().any(|(key, _)| is_config_key(key)) {
+                        config_changed = true;
+                        self.rebuild_vm(state.aptos_state());
                     }
-                    TransactionPayload::Script(script) => Self::hash32(script.code()),
-                    _ => 0,
-                };
-                for pc in pcs {
-                    let cur_id = base_id ^ pc;
-                    let idx = ((cur_id ^ self.prev_loc) as usize) & (MAP_SIZE - 1);
-                    let byte = &mut map[idx];
-                    *byte = byte.saturating_add(1);
-                    self.prev_loc = cur_id >> 1;
                 }
-                // Shift overflow observer
-                let cause_loss = shift_losses.into_iter().any(|b| b);
-                self.observers.1 .1 .0.set_cause_loss(cause_loss);
-                if let TransactionStatus::Keep(ExecutionStatus::MoveAbort { location: _, code, .. }) = &result.status {
-                    self.observers.1 .0.set_last(Some(*code));
-                    if *code == 1337 {
-                        println!("[fuzzer] abort code 1337 captured");
+                Err(vm_status) => {
+                    self.error_count += 1;
+                    completed = false;
+                    if let Some(event) = Self::arithmetic_event(state.aptos_state(), &vm_status) {
+                        arithmetic_events.push(event);
+                    }
+                    if let VMStatus::MoveAbort(ref location, code) = vm_status {
+                        self.observers
+                            .1
+                             .0
+                            .set_last(Some(Self::abort_site(call, location, code)));
+                    }
+                    match outcome {
+                        ExecOutcomeKind::Ok |
+                        ExecOutcomeKind::MoveAbort(_) |
+                        ExecOutcomeKind::OutOfGas |
+                        ExecOutcomeKind::OtherError => {}
+                        ExecOutcomeKind::InvariantViolation | ExecOutcomeKind::Panic => {
+                            exit_kind = ExitKind::Crash;
+                            break;
+                        }
                     }
-                } else {
-                    self.observers.1 .0.set_last(None);
                 }
-                // state.aptos_state_mut().apply_write_set(&result.write_set);
-                *state.executions_mut() += 1;
-                Ok(ExitKind::Ok)
             }
-            Err(vm_status) => {
-                self.error_count += 1;
-                // Even on error, reset coverage map to a clean state for next exec
-                let map = self.observers.0.as_slice_mut();
-                for b in map.iter_mut() {
-                    *b = 0;
-                }
-                self.prev_loc = 0;
-                self.observers.1 .1 .0.set_cause_loss(false);
-                if let VMStatus::MoveAbort(ref _loc, code) = vm_status {
-                    self.observers.1 .0.set_last(Some(code));
-                    if code == 1337 {
-                        println!("[fuzzer] abort code 1337 captured");
-                    }
-                } else {
-                    self.observers.1 .0.set_last(None);
+            if self.gas_budget.is_some_and(|budget| gas_used > budget) ||
+                self.timeout.is_some_and(|timeout| started.elapsed() > timeout)
+            {
+                exit_kind = ExitKind::Timeout;
+                completed = false;
+                break;
+            }
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.stop();
+        }
+        if self.persist_writes {
+            state.aptos_state_mut().commit();
+        } else {
+            state.aptos_state_mut().rollback();
+            if config_changed {
+                self.rebuild_vm(state.aptos_state());
+            }
+        }
+
+        self.observers.1 .1 .1 .0.set_gas_used(gas_used);
+        self.observers.1 .1 .1 .0.set_call_gas(call_gas);
+        // Shift overflow observer
+        self.observers.1 .1 .0.set_overflows(overflows);
+        self.observers.1 .1 .1 .1 .0.set_events(arithmetic_events);
+        self.observers.1 .1 .1 .1 .1 .0.set_events(events, completed);
+        self.observers
+            .1
+             .1
+             .1
+             .1
+             .1
+             .1
+             .0
+            .set_changes(write_changes, write_violations);
+        self.observers.1 .1 .1 .1 .1 .1 .1 .0.set_violations(view_violations);
+        self.observers.1 .1 .1 .1 .1 .1 .1 .1 .0.set_divergences(divergences);
+        debug!(
+            ?exit_kind,
+            gas_used,
+            elapsed_us = started.elapsed().as_micros() as u64,
+            "execution finished"
+        );
+        *state.executions_mut() += 1;
+        Ok(exit_kind)
+    }
+}
+
+/// Whether `key` holds an on-chain config the VM environment is built from
+fn is_config_key(key: &StateKey) -> bool {
+    static CONFIG_KEYS: OnceLock<HashSet<StateKey>> = OnceLock::new();
+    CONFIG_KEYS
+        .get_or_init(|| {
+            [
+                StateKey::on_chain_config::<Features>(),
+                StateKey::on_chain_config::<GasScheduleV2>(),
+                StateKey::on_chain_config::<GasSchedule>(),
+                StateKey::on_chain_config::<Version>(),
+                StateKey::on_chain_config::<ChainId>(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()
+        })
+        .contains(key)
+}
+
+impl<EM, Z> AptosMoveExecutor<EM, Z> {
+    /// The VM reports only the aborting module, so the called entry function
+    /// completes the site
+    fn abort_site(call: &AptosCall, location: &AbortLocation, code: u64) -> AbortSite {
+        let module = match location {
+            AbortLocation::Module(module_id) => module_id.to_string(),
+            AbortLocation::Script => "script".to_string(),
+        };
+        let function = match call.entry_function() {
+            Some(ef) => format!("{}::{}", ef.module(), ef.function()),
+            None => "script".to_string(),
+        };
+        AbortSite { module, function, code }
+    }
+
+    /// Classify an `ARITHMETIC_ERROR` failure by the instruction that raised
+    /// it; only overflowing `Add`/`Sub`/`Mul` and truncating casts are kept
+    fn arithmetic_event(state: &AptosCustomState, vm_status: &VMStatus) -> Option<ArithmeticEvent> {
+        let VMStatus::ExecutionFailure {
+            status_code: StatusCode::ARITHMETIC_ERROR,
+            location: AbortLocation::Module(module_id),
+            function,
+            code_offset,
+            ..
+        } = vm_status
+        else {
+            return None;
+        };
+
+        let code = state.get_state_value(&StateKey::module(module_id.address(), module_id.name()))?;
+        let module = CompiledModule::deserialize(code.bytes()).ok()?;
+        let function_def = module.function_defs().get(*function as usize)?;
+        let instruction = function_def.code.as_ref()?.code.get(*code_offset as usize)?;
+        match instruction {
+            Bytecode::Add |
+            Bytecode::Sub |
+            Bytecode::Mul |
+            Bytecode::CastU8 |
+            Bytecode::CastU16 |
+            Bytecode::CastU32 |
+            Bytecode::CastU64 |
+            Bytecode::CastU128 |
+            Bytecode::CastU256 => {}
+            _ => return None,
+        }
+
+        let handle = module.function_handle_at(function_def.function);
+        Some(ArithmeticEvent {
+            module: module_id.to_string(),
+            function: module.identifier_at(handle.name).to_string(),
+            pc: *code_offset as u64,
+            instruction: format!("{instruction:?}"),
+        })
+    }
+
+    /// Add AFL-style edges for the executed pcs of one call to the coverage map
+    fn record_coverage(&mut self, payload: &TransactionPayload, pcs: Vec<u32>) {
+        // Build a stable per-function base id to reduce inter-function collisions
+        let base_id: u32 = match payload {
+            TransactionPayload::EntryFunction(ef) => {
+                let (module, function, _ty_args, _args) = ef.clone().into_inner();
+                let mut buf = Vec::new();
+                buf.extend_from_slice(module.address().as_ref());
+                buf.extend_from_slice(module.name().as_str().as_bytes());
+                buf.extend_from_slice(function.as_str().as_bytes());
+                Self::hash32(&buf)
+            }
+            TransactionPayload::Script(script) => Self::hash32(script.code()),
+            // Multisig calls of a function map apart from its direct calls
+            TransactionPayload::Multisig(multisig) => {
+                let mut buf = multisig.multisig_address.to_vec();
+                if let Some(MultisigTransactionPayload::EntryFunction(ef)) = &multisig.transaction_payload {
+                    buf.extend_from_slice(ef.module().name().as_str().as_bytes());
+                    buf.extend_from_slice(ef.function().as_str().as_bytes());
                 }
-                let exit_kind = match outcome {
-                    ExecOutcomeKind::Ok => ExitKind::Ok,
-                    ExecOutcomeKind::MoveAbort(_) => ExitKind::Ok,
-                    ExecOutcomeKind::OutOfGas => ExitKind::Ok,
-                    ExecOutcomeKind::OtherError => ExitKind::Ok,
-                    ExecOutcomeKind::InvariantViolation => ExitKind::Crash,
-                    ExecOutcomeKind::Panic => ExitKind::Crash,
-                };
-                *state.executions_mut() += 1;
-                Ok(exit_kind)
+                Self::hash32(&buf)
             }
+            _ => 0,
+        };
+        self.prev_loc = 0;
+        let map = self.observers.0.as_slice_mut();
+        for pc in pcs {
+            let cur_id = base_id ^ pc;
+            let idx = ((cur_id ^ self.prev_loc) as usize) & (MAP_SIZE - 1);
+            let byte = &mut map[idx];
+            *byte = byte.saturating_add(1);
+            self.prev_loc = cur_id >> 1;
         }
     }
 }
@@ -0,0 +1,174 @@
+use std::iter;
+
+use aptos_move_binary_format::errors::VMResult;
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::value::MoveValue;
+use aptos_move_core_types::vm_status::VMStatus;
+use aptos_move_vm_runtime::module_traversal::{TraversalContext, TraversalStorage};
+use aptos_move_vm_runtime::native_extensions::NativeContextExtensions;
+use aptos_move_vm_runtime::{
+    dispatch_loader, InstantiatedFunctionLoader, LegacyLoaderConfig, MoveVM, TransactionDataCache,
+};
+use aptos_move_vm_types::gas::UnmeteredGasMeter;
+use aptos_move_vm_types::loaded_data::runtime_types::Type;
+use aptos_types::transaction::{EntryFunction, ExecutionStatus, TransactionStatus};
+use aptos_vm_types::module_and_script_storage::AsAptosCodeStorage;
+
+use super::aptos_custom_state::AptosCustomState;
+use super::custom_state_view::CustomStateView;
+use super::types::{BareVmDivergence, TransactionResult};
+
+/// Call the function `entry` names directly in a MoveVM session over the
+/// modules of `state`, skipping the transaction prologue, epilogue, gas
+/// metering and change set. Any function can be called, not only entry
+/// ones; signer parameters are `sender`. Writes are discarded, and natives
+/// needing a transaction context (events, tables, aggregators) aren't
+/// available, so this suits library code such as math modules.
+///
+/// Returns the BCS-encoded return values.
+pub fn execute_function(
+    state: &AptosCustomState,
+    entry: &EntryFunction,
+    sender: AccountAddress,
+) -> VMResult<Vec<Vec<u8>>> {
+    let view = CustomStateView::new(state);
+    let code_storage = view.as_aptos_code_storage(state);
+    let traversal_storage = TraversalStorage::new();
+    let mut traversal_context = TraversalContext::new(&traversal_storage);
+    let mut gas_meter = UnmeteredGasMeter;
+    let mut data_cache = TransactionDataCache::empty();
+    let mut extensions = NativeContextExtensions::default();
+
+    dispatch_loader!(&code_storage, loader, {
+        let function = loader.load_instantiated_function(
+            &LegacyLoaderConfig::unmetered(),
+            &mut gas_meter,
+            &mut traversal_context,
+            entry.module(),
+            entry.function(),
+            entry.ty_args(),
+        )?;
+
+        // Entry payloads leave out the leading signers
+        let signers = function.param_tys().iter().take_while(|ty| is_signer(ty)).count();
+        let signer = MoveValue::Signer(sender)
+            .simple_serialize()
+            .expect("signers always serialize");
+        let args: Vec<Vec<u8>> = iter::repeat_n(signer, signers)
+            .chain(entry.args().iter().cloned())
+            .collect();
+
+        let returned = MoveVM::execute_loaded_function(
+            function,
+            args,
+            &mut data_cache,
+            &mut gas_meter,
+            &mut traversal_context,
+            &mut extensions,
+            &loader,
+            state,
+        )?;
+        Ok(returned
+            .return_values
+            .into_iter()
+            .map(|(bytes, _layout)| bytes)
+            .collect())
+    })
+}
+
+/// Compare the transaction calling `entry` with a bare VM session calling
+/// it on the same state, [`execute_function`]'s result. Both run the same
+/// bytecode, so differing outcomes point at the argument encoding or at the
+/// natives and setup of the transaction path rather than at the target.
+pub fn divergence(
+    entry: &EntryFunction,
+    transaction: &Result<TransactionResult, VMStatus>,
+    bare: &Result<Vec<Vec<u8>>, VMStatus>,
+) -> Option<BareVmDivergence> {
+    let transaction = outcome(transaction);
+    let bare = match bare {
+        Ok(_) => "executed".to_string(),
+        Err(status) => status_outcome(status),
+    };
+    (transaction != bare).then(|| BareVmDivergence {
+        function: format!("{}::{}", entry.module(), entry.function()),
+        transaction,
+        bare,
+    })
+}
+
+/// Outcome of a call as compared with the bare VM: success, the abort
+/// code or the error's status code. Gas and writes are left out, the bare
+/// VM has neither.
+pub fn outcome(result: &Result<TransactionResult, VMStatus>) -> String {
+    match result {
+        Ok(result) => match &result.status {
+            TransactionStatus::Keep(ExecutionStatus::Success) => "executed".to_string(),
+            TransactionStatus::Keep(ExecutionStatus::MoveAbort { code, .. }) => format!("abort {code}"),
+            status => format!("{status:?}"),
+        },
+        Err(status) => status_outcome(status),
+    }
+}
+
+pub fn status_outcome(status: &VMStatus) -> String {
+    match status {
+        VMStatus::Executed => "executed".to_string(),
+        VMStatus::MoveAbort(_, code) => format!("abort {code}"),
+        status => format!("{:?}", status.status_code()),
+    }
+}
+
+fn is_signer(ty: &Type) -> bool {
+    match ty {
+        Type::Signer => true,
+        Type::Reference(inner) => matches!(inner.as_ref(), Type::Signer),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aptos_move_core_types::identifier::Identifier;
+    use aptos_move_core_types::language_storage::ModuleId;
+    use aptos_move_core_types::vm_status::{AbortLocation, StatusCode};
+
+    use super::*;
+
+    #[test]
+    fn test_status_outcome() {
+        assert_eq!(
+            status_outcome(&VMStatus::MoveAbort(AbortLocation::Script, 7)),
+            "abort 7"
+        );
+        assert_eq!(status_outcome(&VMStatus::Executed), "executed");
+        let error = VMStatus::Error {
+            status_code: StatusCode::ARITHMETIC_ERROR,
+            sub_status: None,
+            message: None,
+        };
+        assert_eq!(status_outcome(&error), "ARITHMETIC_ERROR");
+    }
+
+    #[test]
+    fn test_divergence() {
+        let entry = EntryFunction::new(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("math").unwrap()),
+            Identifier::new("mul_div").unwrap(),
+            Vec::new(),
+            Vec::new(),
+        );
+        let aborted = Err(VMStatus::MoveAbort(AbortLocation::Script, 7));
+
+        let found = divergence(&entry, &aborted, &Ok(Vec::new())).unwrap();
+        assert!(found.function.ends_with("::math::mul_div"));
+        assert_eq!(found.transaction, "abort 7");
+        assert_eq!(found.bare, "executed");
+        assert!(found
+            .to_string()
+            .ends_with("::math::mul_div: abort 7 as a transaction, executed in a bare VM"));
+
+        let bare_aborted = Err(VMStatus::MoveAbort(AbortLocation::Script, 7));
+        assert_eq!(divergence(&entry, &aborted, &bare_aborted), None);
+    }
+}
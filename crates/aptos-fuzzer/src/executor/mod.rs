@@ -1,7 +1,11 @@
 pub mod aptos_custom_state;
 pub mod aptos_move_executor;
+pub mod bare_vm;
 pub mod custom_state_view;
+pub mod rest_state_fetcher;
 pub mod types;
+mod watchdog;
 
 pub use aptos_move_executor::AptosMoveExecutor;
-pub use types::TransactionResult;
+pub use rest_state_fetcher::{ForkConfig, RestStateFetcher};
+pub use types::{ArithmeticEvent, BareVmDivergence, BatchResult, ShiftEvent, TransactionResult};
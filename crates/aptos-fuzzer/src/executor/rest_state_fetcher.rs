@@ -0,0 +1,163 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use aptos_rest_client::aptos_api_types::TransactionData;
+use aptos_rest_client::error::RestError;
+use aptos_rest_client::Client;
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::state_store::state_value::StateValue;
+use aptos_types::transaction::{SignedTransaction, Transaction};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
+use tracing::warn;
+use url::Url;
+
+/// Fullnode to fork state from. Without a version the latest ledger version
+/// is pinned when the fetcher connects.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForkConfig {
+    pub url: String,
+    pub version: Option<u64>,
+}
+
+/// Lazily fetches state from an Aptos fullnode REST API at a pinned ledger
+/// version, so deployed modules can be fuzzed against real on-chain state
+pub struct RestStateFetcher {
+    /// Aptos REST client
+    client: Client,
+    url: String,
+    /// Ledger version every read is pinned at
+    version: u64,
+    /// Runtime driving the async client from the synchronous state views
+    runtime: Runtime,
+    /// Fetched values, `None` for keys absent at `version`
+    cache: DashMap<StateKey, Option<StateValue>>,
+    /// Requests sent so far, how many failed and their total duration, for
+    /// monitoring
+    requests: AtomicU64,
+    failures: AtomicU64,
+    request_nanos: AtomicU64,
+}
+
+impl RestStateFetcher {
+    pub fn new(config: &ForkConfig) -> anyhow::Result<Self> {
+        let url = Url::parse(&config.url).with_context(|| format!("invalid fork url {}", config.url))?;
+        let client = Client::new(url);
+        let runtime = Runtime::new().context("failed to start fork runtime")?;
+        let version = match config.version {
+            Some(version) => version,
+            None => {
+                runtime
+                    .block_on(client.get_ledger_information())
+                    .context("failed to query fork ledger version")?
+                    .into_inner()
+                    .version
+            }
+        };
+
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+            version,
+            runtime,
+            cache: DashMap::new(),
+            requests: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            request_nanos: AtomicU64::new(0),
+        })
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Config reproducing this fetcher's view, with the version pinned
+    pub fn pinned_config(&self) -> ForkConfig {
+        ForkConfig {
+            url: self.url.clone(),
+            version: Some(self.version),
+        }
+    }
+
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Requests sent so far whose value couldn't be fetched or decoded
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Mean duration of the requests sent so far
+    pub fn average_latency(&self) -> Option<Duration> {
+        let requests = self.requests.load(Ordering::Relaxed);
+        let nanos = self.request_nanos.load(Ordering::Relaxed);
+        nanos.checked_div(requests).map(Duration::from_nanos)
+    }
+
+    /// Entries fetched so far that exist at the pinned version
+    pub fn fetched(&self) -> Vec<(StateKey, StateValue)> {
+        self.cache
+            .iter()
+            .filter_map(|entry| entry.value().clone().map(|value| (entry.key().clone(), value)))
+            .collect()
+    }
+
+    /// Value of `state_key` at the pinned version. Request failures are
+    /// reported and read as a missing value without being cached.
+    pub fn get(&self, state_key: &StateKey) -> Option<StateValue> {
+        if let Some(entry) = self.cache.get(state_key) {
+            return entry.clone();
+        }
+
+        let started = Instant::now();
+        let response = self
+            .runtime
+            .block_on(self.client.get_raw_state_value(state_key, self.version));
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.request_nanos
+            .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+
+        let value = match response {
+            Ok(response) => match bcs::from_bytes::<StateValue>(&response.into_inner()) {
+                Ok(value) => Some(value),
+                Err(err) => {
+                    warn!(?state_key, %err, "failed to decode forked state");
+                    self.failures.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+            },
+            Err(RestError::Api(response)) if response.status_code.as_u16() == 404 => None,
+            Err(err) => {
+                warn!(?state_key, %err, "failed to fetch forked state");
+                self.failures.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        self.cache.insert(state_key.clone(), value.clone());
+        value
+    }
+
+    /// User transaction committed at `version`, if it executed successfully
+    pub fn user_transaction(&self, version: u64) -> anyhow::Result<Option<SignedTransaction>> {
+        let data = self
+            .runtime
+            .block_on(self.client.get_transaction_by_version_bcs(version))
+            .with_context(|| format!("failed to fetch transaction {version}"))?
+            .into_inner();
+        let TransactionData::OnChain(data) = data else {
+            return Ok(None);
+        };
+        match data.transaction {
+            Transaction::UserTransaction(txn) if data.info.status().is_success() => Ok(Some(txn)),
+            _ => Ok(None),
+        }
+    }
+}
@@ -1,13 +1,98 @@
 use aptos_types::contract_event::ContractEvent;
 use aptos_types::fee_statement::FeeStatement;
 use aptos_types::transaction::TransactionStatus;
+use aptos_types::vm_status::VMStatus;
 use aptos_types::write_set::WriteSet;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct TransactionResult {
     pub status: TransactionStatus,
+    /// Gas charged to a signed transaction run whole; for calls run without
+    /// a gas meter, the number of instructions executed
     pub gas_used: u64,
     pub write_set: WriteSet,
     pub events: Vec<ContractEvent>,
     pub fee_statement: Option<FeeStatement>,
 }
+
+/// Outcome of simulating a batch of transactions
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    /// Result of each transaction, in order
+    pub results: Vec<Result<TransactionResult, VMStatus>>,
+    /// Writes of the successful transactions; the last write to a key wins
+    pub write_set: WriteSet,
+}
+
+/// A shift executed by the instrumented VM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShiftEvent {
+    /// Module containing the shift, `address::name`
+    pub module: String,
+    /// Function containing the shift
+    pub function: String,
+    /// Bytecode offset of the shift instruction
+    pub pc: u64,
+    /// Shifted value
+    pub value: String,
+    /// Number of bits shifted
+    pub shift_amount: u64,
+    /// Whether set high-order bits were shifted out
+    pub lost_high_bits: bool,
+}
+
+impl std::fmt::Display for ShiftEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}::{} pc {}: {} shifted by {}",
+            self.module, self.function, self.pc, self.value, self.shift_amount
+        )
+    }
+}
+
+/// A call whose outcome in a bare MoveVM session differs from its
+/// transaction's, see [`super::bare_vm::divergence`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BareVmDivergence {
+    /// Called function, `address::module::function`
+    pub function: String,
+    /// Outcome of the transaction, e.g. `executed` or `abort 7`
+    pub transaction: String,
+    /// Outcome of the bare VM session
+    pub bare: String,
+}
+
+impl std::fmt::Display for BareVmDivergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} as a transaction, {} in a bare VM",
+            self.function, self.transaction, self.bare
+        )
+    }
+}
+
+/// An arithmetic overflow or cast truncation that aborted execution
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ArithmeticEvent {
+    /// Module containing the instruction, `address::name`
+    pub module: String,
+    /// Function containing the instruction
+    pub function: String,
+    /// Bytecode offset of the instruction
+    pub pc: u64,
+    /// Faulting instruction, e.g. `Add` or `CastU8`
+    pub instruction: String,
+}
+
+impl std::fmt::Display for ArithmeticEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}::{} pc {}: {} overflowed",
+            self.module, self.function, self.pc, self.instruction
+        )
+    }
+}
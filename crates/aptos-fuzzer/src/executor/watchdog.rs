@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use libafl::inputs::Input;
+use tracing::error;
+
+use crate::AptosFuzzerInput;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Terminates the process when one execution runs past `limit`, after saving
+/// the running input to `dump_dir`.
+///
+/// The VM runs unmetered and can't be interrupted from outside, and the gas
+/// budget is only checked between calls, so an input that makes one call spin
+/// would otherwise hang the client forever. The restarting event manager
+/// respawns the client after the exit.
+pub(crate) struct HangWatchdog {
+    running: Arc<Mutex<Option<(Instant, AptosFuzzerInput)>>>,
+}
+
+impl HangWatchdog {
+    pub(crate) fn spawn(limit: Duration, dump_dir: PathBuf) -> Self {
+        let running: Arc<Mutex<Option<(Instant, AptosFuzzerInput)>>> = Arc::new(Mutex::new(None));
+        let watched = running.clone();
+
+        thread::spawn(move || loop {
+            thread::sleep(POLL_INTERVAL);
+            let Ok(current) = watched.lock() else {
+                return;
+            };
+            let Some((started, input)) = current.as_ref() else {
+                continue;
+            };
+            if started.elapsed() < limit {
+                continue;
+            }
+
+            let path = dump_dir.join(format!("hang-{}", input.generate_name(None)));
+            match input.to_file(&path) {
+                Ok(()) => error!(?limit, path = %path.display(), "execution exceeded the hang limit, input saved"),
+                Err(err) => error!(?limit, %err, "execution exceeded the hang limit, failed to save input"),
+            }
+            std::process::exit(1);
+        });
+
+        Self { running }
+    }
+
+    pub(crate) fn start(&self, input: &AptosFuzzerInput) {
+        if let Ok(mut running) = self.running.lock() {
+            *running = Some((Instant::now(), input.clone()));
+        }
+    }
+
+    pub(crate) fn stop(&self) {
+        if let Ok(mut running) = self.running.lock() {
+            *running = None;
+        }
+    }
+}
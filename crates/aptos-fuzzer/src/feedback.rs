@@ -1,36 +1,61 @@
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 
+use aptos_types::transaction::TransactionPayload;
+use fuzzer_core::aborts::{AbortPolicy, AbortSeverity};
 use libafl::feedbacks::{Feedback, StateInitializer};
 use libafl::observers::ObserversTuple;
-use libafl::Error;
+use libafl::{Error, HasMetadata};
 use libafl_bolts::tuples::{Handle, MatchNameRef};
 use libafl_bolts::Named;
 use serde::{Deserialize, Serialize};
+use tracing::info;
 
-use crate::observers::{AbortCodeObserver, ShiftOverflowObserver};
-use crate::{AptosFuzzerInput, AptosFuzzerState};
+use crate::events::{EventInvariants, EventPredicate, EventViolation};
+use crate::executor::{ArithmeticEvent, BareVmDivergence};
+use crate::observers::{
+    AbortCodeObserver, AbortSite, ArithmeticMetadata, ArithmeticObserver, BareVmObserver, EventObserver, GasObserver,
+    ShiftOverflowMetadata, ShiftOverflowObserver, ViewObserver, WriteSetObserver,
+};
+use crate::views::ViewViolation;
+use crate::write_rules::WriteViolation;
+use crate::{AptosCall, AptosFuzzerInput, AptosFuzzerState};
 
-/// Feedback that tracks abort codes encountered during execution.
-/// Considers an input interesting if it produces a new abort code that hasn't
-/// been seen before.
+/// Feedback that tracks abort sites encountered during execution.
+/// Considers an input interesting if it aborts at a new (module, function,
+/// code) site, so equal codes raised by different functions stay distinct.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AbortCodeFeedback {
-    seen_abort_codes: HashSet<u64>,
     name: Cow<'static, str>,
 }
 
+/// Abort sites already reported by [`AbortCodeFeedback`]. Kept in the state
+/// so respawned clients and resumed campaigns don't flag them again.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AbortSitesMetadata {
+    pub sites: HashSet<AbortSite>,
+}
+
+libafl_bolts::impl_serdeany!(AbortSitesMetadata);
+
+/// Faulting instructions already reported by [`ArithmeticObjective`]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ArithmeticSitesMetadata {
+    pub sites: HashSet<ArithmeticEvent>,
+}
+
+libafl_bolts::impl_serdeany!(ArithmeticSitesMetadata);
+
 impl AbortCodeFeedback {
     pub fn new() -> Self {
         Self {
-            seen_abort_codes: HashSet::new(),
             name: Cow::Borrowed("AbortCodeFeedback"),
         }
     }
 
     pub fn with_name(name: &'static str) -> Self {
         Self {
-            seen_abort_codes: HashSet::new(),
             name: Cow::Borrowed(name),
         }
     }
@@ -51,7 +76,7 @@ where
     #[allow(clippy::wrong_self_convention)]
     fn is_interesting(
         &mut self,
-        _state: &mut AptosFuzzerState,
+        state: &mut AptosFuzzerState,
         _manager: &mut EM,
         _input: &AptosFuzzerInput,
         observers: &OT,
@@ -61,17 +86,12 @@ where
         if matches!(exit_kind, libafl::executors::ExitKind::Crash) {
             return Ok(true);
         }
-        // Check if the last execution produced an abort code
-        let mut code_opt: Option<u64> = None;
-        // Access AbortCodeObserver through Handle
+        // Check if the last execution aborted, and where
         let abort_handle: Handle<AbortCodeObserver> = Handle::new(Cow::Borrowed("AbortCodeObserver"));
-        if let Some(obs_ref) = observers.get(&abort_handle) {
-            code_opt = obs_ref.last();
-        }
-        if let Some(abort_code) = code_opt {
-            // If this is a new abort code we haven't seen before, it's interesting
-            if !self.seen_abort_codes.contains(&abort_code) {
-                self.seen_abort_codes.insert(abort_code);
+        if let Some(site) = observers.get(&abort_handle).and_then(|obs| obs.last_site()) {
+            // If this is a new abort site we haven't seen before, it's interesting
+            let seen = state.metadata_or_insert_with(AbortSitesMetadata::default);
+            if seen.sites.insert(site.clone()) {
                 return Ok(true);
             }
         }
@@ -82,18 +102,31 @@ where
         &mut self,
         _state: &mut AptosFuzzerState,
         _manager: &mut EM,
-        _observers: &OT,
-        _testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
     ) -> Result<(), Error> {
-        // We could add metadata about the abort code to the testcase here
+        append_abort_site(observers, testcase);
         Ok(())
     }
 }
 
+/// Attach the abort site of the last execution to the testcase, if any
+fn append_abort_site<OT>(observers: &OT, testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>)
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    let abort_handle: Handle<AbortCodeObserver> = Handle::new(Cow::Borrowed("AbortCodeObserver"));
+    if let Some(site) = observers.get(&abort_handle).and_then(|obs| obs.last_site()) {
+        testcase.add_metadata(site.clone());
+    }
+}
+
 /// Objective feedback that considers abort codes as objectives
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AbortCodeObjective {
     target_abort_codes: HashSet<u64>,
+    /// Classifies aborts instead of the target codes when set
+    policy: Option<AbortPolicy>,
     name: Cow<'static, str>,
 }
 
@@ -101,6 +134,7 @@ impl AbortCodeObjective {
     pub fn new() -> Self {
         Self {
             target_abort_codes: HashSet::new(),
+            policy: None,
             name: Cow::Borrowed("AbortCodeObjective"),
         }
     }
@@ -108,6 +142,7 @@ impl AbortCodeObjective {
     pub fn with_target_codes(codes: &[u64]) -> Self {
         Self {
             target_abort_codes: codes.iter().cloned().collect(),
+            policy: None,
             name: Cow::Borrowed("AbortCodeObjective"),
         }
     }
@@ -115,9 +150,16 @@ impl AbortCodeObjective {
     pub fn with_name(name: &'static str) -> Self {
         Self {
             target_abort_codes: HashSet::new(),
+            policy: None,
             name: Cow::Borrowed(name),
         }
     }
+
+    /// Only aborts `policy` classifies as bugs are objectives
+    pub fn with_policy(mut self, policy: AbortPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
 }
 
 impl Named for AbortCodeObjective {
@@ -150,6 +192,9 @@ where
         // Access AbortCodeObserver through Handle
         let abort_handle: Handle<AbortCodeObserver> = Handle::new(Cow::Borrowed("AbortCodeObserver"));
         if let Some(obs_ref) = observers.get(&abort_handle) {
+            if let (Some(policy), Some(site)) = (&self.policy, obs_ref.last_site()) {
+                return Ok(policy.classify(&site.module, site.code) == AbortSeverity::Bug);
+            }
             code_opt = obs_ref.last();
         }
         if let Some(abort_code) = code_opt {
@@ -171,10 +216,10 @@ where
         &mut self,
         _state: &mut AptosFuzzerState,
         _manager: &mut EM,
-        _observers: &OT,
-        _testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
     ) -> Result<(), Error> {
-        // We could add metadata about the abort code to the testcase here
+        append_abort_site(observers, testcase);
         Ok(())
     }
 }
@@ -221,6 +266,17 @@ where
         }
         Ok(cause_loss)
     }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        append_shift_overflows(observers, testcase);
+        Ok(())
+    }
 }
 
 /// Treats shift overflow as a bug.
@@ -265,4 +321,817 @@ where
         }
         Ok(cause_loss)
     }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        append_shift_overflows(observers, testcase);
+        Ok(())
+    }
+}
+
+/// Treats an arithmetic overflow or cast truncation at a new instruction as a
+/// bug; repeats of an already reported instruction are ignored.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ArithmeticObjective {
+    name: Cow<'static, str>,
+}
+
+impl ArithmeticObjective {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("ArithmeticObjective"),
+        }
+    }
+}
+
+impl Named for ArithmeticObjective {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for ArithmeticObjective {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for ArithmeticObjective
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let arith_handle: Handle<ArithmeticObserver> = Handle::new(Cow::Borrowed("ArithmeticObserver"));
+        let Some(obs) = observers.get(&arith_handle) else {
+            return Ok(false);
+        };
+        let seen = state.metadata_or_insert_with(ArithmeticSitesMetadata::default);
+        let mut new_site = false;
+        for event in obs.events() {
+            new_site |= seen.sites.insert(event.clone());
+        }
+        Ok(new_site)
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        let arith_handle: Handle<ArithmeticObserver> = Handle::new(Cow::Borrowed("ArithmeticObserver"));
+        if let Some(obs) = observers.get(&arith_handle) {
+            for event in obs.events() {
+                info!(%event, "arithmetic error");
+            }
+            testcase.add_metadata(ArithmeticMetadata {
+                events: obs.events().to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Event types emitted so far, and how low and high each field bounded by an
+/// invariant went
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventProgressMetadata {
+    pub event_types: HashSet<String>,
+    pub extremes: HashMap<String, (u128, u128)>,
+}
+
+libafl_bolts::impl_serdeany!(EventProgressMetadata);
+
+/// Event invariants already reported by [`EventInvariantObjective`]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventViolationsMetadata {
+    pub invariants: HashSet<String>,
+}
+
+libafl_bolts::impl_serdeany!(EventViolationsMetadata);
+
+/// Event invariants an input broke, attached to testcases as metadata
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventInvariantMetadata {
+    pub violations: Vec<EventViolation>,
+}
+
+libafl_bolts::impl_serdeany!(EventInvariantMetadata);
+
+/// Keeps inputs emitting an event type not seen before, or pushing a field an
+/// invariant bounds further towards its bound than any input so far
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventInvariantFeedback {
+    invariants: EventInvariants,
+    name: Cow<'static, str>,
+}
+
+impl EventInvariantFeedback {
+    pub fn new(invariants: EventInvariants) -> Self {
+        Self {
+            invariants,
+            name: Cow::Borrowed("EventInvariantFeedback"),
+        }
+    }
+}
+
+impl Named for EventInvariantFeedback {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for EventInvariantFeedback {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for EventInvariantFeedback
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let event_handle: Handle<EventObserver> = Handle::new(Cow::Borrowed("EventObserver"));
+        let Some(obs) = observers.get(&event_handle) else {
+            return Ok(false);
+        };
+        let progress = state.metadata_or_insert_with(EventProgressMetadata::default);
+        let mut interesting = false;
+        for event in obs.events() {
+            interesting |= progress.event_types.insert(event.type_tag.clone());
+        }
+
+        for invariant in &self.invariants.invariants {
+            let EventPredicate::Bounds { field, min, max } = &invariant.predicate else {
+                continue;
+            };
+            for event in obs.events().iter().filter(|event| invariant.matches(event)) {
+                let Some(&value) = event.fields.get(field) else {
+                    continue;
+                };
+                match progress.extremes.entry(invariant.name.clone()) {
+                    Entry::Vacant(entry) => {
+                        entry.insert((value, value));
+                        interesting = true;
+                    }
+                    Entry::Occupied(mut entry) => {
+                        let (low, high) = entry.get_mut();
+                        if min.is_some() && value < *low {
+                            *low = value;
+                            interesting = true;
+                        }
+                        if max.is_some() && value > *high {
+                            *high = value;
+                            interesting = true;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(interesting)
+    }
+}
+
+/// Treats breaking an event invariant as a bug; each invariant is reported
+/// once
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventInvariantObjective {
+    invariants: EventInvariants,
+    name: Cow<'static, str>,
+}
+
+impl EventInvariantObjective {
+    pub fn new(invariants: EventInvariants) -> Self {
+        Self {
+            invariants,
+            name: Cow::Borrowed("EventInvariantObjective"),
+        }
+    }
+
+    fn violations<OT>(&self, observers: &OT) -> Vec<EventViolation>
+    where
+        OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+    {
+        let event_handle: Handle<EventObserver> = Handle::new(Cow::Borrowed("EventObserver"));
+        observers
+            .get(&event_handle)
+            .map(|obs| self.invariants.violations(obs.events(), obs.completed()))
+            .unwrap_or_default()
+    }
+}
+
+impl Named for EventInvariantObjective {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for EventInvariantObjective {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for EventInvariantObjective
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let violations = self.violations(observers);
+        let seen = state.metadata_or_insert_with(EventViolationsMetadata::default);
+        let mut new_violation = false;
+        for violation in violations {
+            new_violation |= seen.invariants.insert(violation.invariant);
+        }
+        Ok(new_violation)
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        let violations = self.violations(observers);
+        for violation in &violations {
+            info!(%violation, "event invariant broken");
+        }
+        testcase.add_metadata(EventInvariantMetadata { violations });
+        Ok(())
+    }
+}
+
+/// How low and high the field each write rule checks went
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WriteProgressMetadata {
+    pub extremes: HashMap<String, (u128, u128)>,
+}
+
+libafl_bolts::impl_serdeany!(WriteProgressMetadata);
+
+/// Write rules already reported by [`WriteSetObjective`]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WriteViolationsMetadata {
+    pub rules: HashSet<String>,
+}
+
+libafl_bolts::impl_serdeany!(WriteViolationsMetadata);
+
+/// Write rules an input broke, attached to testcases as metadata
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WriteSetMetadata {
+    pub violations: Vec<WriteViolation>,
+}
+
+libafl_bolts::impl_serdeany!(WriteSetMetadata);
+
+/// Keeps inputs that first change the resource of a write rule, or move the
+/// field it checks lower or higher than any input so far
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WriteSetFeedback {
+    name: Cow<'static, str>,
+}
+
+impl WriteSetFeedback {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("WriteSetFeedback"),
+        }
+    }
+}
+
+impl Named for WriteSetFeedback {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for WriteSetFeedback {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for WriteSetFeedback
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let write_handle: Handle<WriteSetObserver> = Handle::new(Cow::Borrowed("WriteSetObserver"));
+        let Some(obs) = observers.get(&write_handle) else {
+            return Ok(false);
+        };
+        let progress = state.metadata_or_insert_with(WriteProgressMetadata::default);
+        let mut interesting = false;
+        for change in obs.changes() {
+            let value = change.after.unwrap_or_default();
+            match progress.extremes.entry(change.rule.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert((value, value));
+                    interesting = true;
+                }
+                Entry::Occupied(mut entry) => {
+                    let (low, high) = entry.get_mut();
+                    if value < *low {
+                        *low = value;
+                        interesting = true;
+                    }
+                    if value > *high {
+                        *high = value;
+                        interesting = true;
+                    }
+                }
+            }
+        }
+        Ok(interesting)
+    }
+}
+
+/// Treats breaking a write rule as a bug; each rule is reported once
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WriteSetObjective {
+    name: Cow<'static, str>,
+}
+
+impl WriteSetObjective {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("WriteSetObjective"),
+        }
+    }
+}
+
+impl Named for WriteSetObjective {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for WriteSetObjective {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for WriteSetObjective
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let write_handle: Handle<WriteSetObserver> = Handle::new(Cow::Borrowed("WriteSetObserver"));
+        let Some(obs) = observers.get(&write_handle) else {
+            return Ok(false);
+        };
+        let seen = state.metadata_or_insert_with(WriteViolationsMetadata::default);
+        let mut new_violation = false;
+        for violation in obs.violations() {
+            new_violation |= seen.rules.insert(violation.rule.clone());
+        }
+        Ok(new_violation)
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        let write_handle: Handle<WriteSetObserver> = Handle::new(Cow::Borrowed("WriteSetObserver"));
+        if let Some(obs) = observers.get(&write_handle) {
+            for violation in obs.violations() {
+                info!(%violation, "write rule broken");
+            }
+            testcase.add_metadata(WriteSetMetadata {
+                violations: obs.violations().to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// View checks already reported by [`ViewObjective`]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ViewViolationsMetadata {
+    pub checks: HashSet<String>,
+}
+
+libafl_bolts::impl_serdeany!(ViewViolationsMetadata);
+
+/// View checks an input broke, attached to testcases as metadata
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ViewMetadata {
+    pub violations: Vec<ViewViolation>,
+}
+
+libafl_bolts::impl_serdeany!(ViewMetadata);
+
+/// Treats breaking a view check as a bug; each check is reported once
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ViewObjective {
+    name: Cow<'static, str>,
+}
+
+impl ViewObjective {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("ViewObjective"),
+        }
+    }
+}
+
+impl Named for ViewObjective {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for ViewObjective {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for ViewObjective
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let view_handle: Handle<ViewObserver> = Handle::new(Cow::Borrowed("ViewObserver"));
+        let Some(obs) = observers.get(&view_handle) else {
+            return Ok(false);
+        };
+        let seen = state.metadata_or_insert_with(ViewViolationsMetadata::default);
+        let mut new_violation = false;
+        for violation in obs.violations() {
+            new_violation |= seen.checks.insert(violation.check.clone());
+        }
+        Ok(new_violation)
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        let view_handle: Handle<ViewObserver> = Handle::new(Cow::Borrowed("ViewObserver"));
+        if let Some(obs) = observers.get(&view_handle) {
+            for violation in obs.violations() {
+                info!(%violation, "view check broken");
+            }
+            testcase.add_metadata(ViewMetadata {
+                violations: obs.violations().to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Divergences already reported by [`BareVmObjective`]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BareVmDivergencesMetadata {
+    pub divergences: HashSet<BareVmDivergence>,
+}
+
+libafl_bolts::impl_serdeany!(BareVmDivergencesMetadata);
+
+/// Calls an input ran differently in a bare MoveVM session, attached to
+/// testcases as metadata
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BareVmMetadata {
+    pub divergences: Vec<BareVmDivergence>,
+}
+
+libafl_bolts::impl_serdeany!(BareVmMetadata);
+
+/// Treats a call whose outcome differs in a bare MoveVM session as a bug;
+/// each function and pair of outcomes is reported once
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BareVmObjective {
+    name: Cow<'static, str>,
+}
+
+impl BareVmObjective {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("BareVmObjective"),
+        }
+    }
+}
+
+impl Named for BareVmObjective {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for BareVmObjective {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for BareVmObjective
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let bare_vm_handle: Handle<BareVmObserver> = Handle::new(Cow::Borrowed("BareVmObserver"));
+        let Some(obs) = observers.get(&bare_vm_handle) else {
+            return Ok(false);
+        };
+        let seen = state.metadata_or_insert_with(BareVmDivergencesMetadata::default);
+        let mut new_divergence = false;
+        for divergence in obs.divergences() {
+            new_divergence |= seen.divergences.insert(divergence.clone());
+        }
+        Ok(new_divergence)
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        let bare_vm_handle: Handle<BareVmObserver> = Handle::new(Cow::Borrowed("BareVmObserver"));
+        if let Some(obs) = observers.get(&bare_vm_handle) {
+            for divergence in obs.divergences() {
+                info!(%divergence, "bare VM divergence");
+            }
+            testcase.add_metadata(BareVmMetadata {
+                divergences: obs.divergences().to_vec(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Attach the lossy shifts of the last execution to the testcase, if any
+fn append_shift_overflows<OT>(observers: &OT, testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>)
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    let shift_handle: Handle<ShiftOverflowObserver> = Handle::new(Cow::Borrowed("ShiftOverflowObserver"));
+    if let Some(obs) = observers.get(&shift_handle) {
+        if obs.cause_loss() {
+            for overflow in obs.overflows() {
+                info!(%overflow, "lossy shift");
+            }
+            testcase.add_metadata(ShiftOverflowMetadata {
+                overflows: obs.overflows().to_vec(),
+            });
+        }
+    }
+}
+
+/// Maximum number of gas-heavy inputs kept for reporting
+const MAX_TOP_GAS_INPUTS: usize = 10;
+
+/// An input together with the gas it consumed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GasRecord {
+    pub gas_used: u64,
+    pub input: String,
+}
+
+/// Keeps inputs that raise the maximum gas consumed by a significant margin,
+/// steering the fuzzer towards unbounded loops and gas-griefing vectors.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GasFeedback {
+    max_gas: u64,
+    /// Required increase over the current maximum, in percent
+    min_increase_percent: u64,
+    top_inputs: Vec<GasRecord>,
+    name: Cow<'static, str>,
+}
+
+impl GasFeedback {
+    pub fn new() -> Self {
+        Self {
+            max_gas: 0,
+            min_increase_percent: 10,
+            top_inputs: Vec::new(),
+            name: Cow::Borrowed("GasFeedback"),
+        }
+    }
+
+    pub fn with_min_increase_percent(mut self, percent: u64) -> Self {
+        self.min_increase_percent = percent;
+        self
+    }
+
+    pub fn max_gas(&self) -> u64 {
+        self.max_gas
+    }
+
+    /// Inputs with the highest gas consumption, most expensive first
+    pub fn top_inputs(&self) -> &[GasRecord] {
+        &self.top_inputs
+    }
+
+    fn is_significant(&self, gas_used: u64) -> bool {
+        let threshold = self
+            .max_gas
+            .saturating_add(self.max_gas.saturating_mul(self.min_increase_percent) / 100);
+        gas_used > threshold
+    }
+
+    fn record(&mut self, gas_used: u64, input: &AptosFuzzerInput) {
+        let description = input.calls().iter().map(describe_call).collect::<Vec<_>>().join("; ");
+
+        info!(gas_used, calls = %description, "new max gas");
+
+        self.top_inputs.push(GasRecord {
+            gas_used,
+            input: description,
+        });
+        self.top_inputs.sort_by(|a, b| b.gas_used.cmp(&a.gas_used));
+        self.top_inputs.truncate(MAX_TOP_GAS_INPUTS);
+    }
+}
+
+impl Default for GasFeedback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// `call` with its type and hex-encoded BCS arguments
+fn describe_call(call: &AptosCall) -> String {
+    match call.payload() {
+        TransactionPayload::EntryFunction(ef) => format!(
+            "{}::{}<{}>({})",
+            ef.module(),
+            ef.function(),
+            ef.ty_args()
+                .iter()
+                .map(|ty| ty.to_canonical_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            ef.args()
+                .iter()
+                .map(|arg| format!("0x{}", hex_encode(arg)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => format!("{other:?}"),
+    }
+}
+
+impl Named for GasFeedback {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for GasFeedback {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for GasFeedback
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let mut gas_used = 0;
+        // Access GasObserver through Handle
+        let gas_handle: Handle<GasObserver> = Handle::new(Cow::Borrowed("GasObserver"));
+        if let Some(obs_ref) = observers.get(&gas_handle) {
+            gas_used = obs_ref.gas_used();
+        }
+
+        if !self.is_significant(gas_used) {
+            return Ok(false);
+        }
+
+        self.max_gas = gas_used;
+        self.record(gas_used, input);
+        Ok(true)
+    }
+}
+
+/// Execution over the instruction ceiling, attached to solutions as
+/// metadata: the call running the most instructions and the arguments it
+/// was given
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InstructionCeilingMetadata {
+    pub instructions: u64,
+    pub ceiling: u64,
+    pub heaviest_call: Option<GasRecord>,
+}
+
+libafl_bolts::impl_serdeany!(InstructionCeilingMetadata);
+
+/// Treats executions running more instructions than the executor's
+/// ceiling as potential DoS findings. Calls run without a gas meter, so the
+/// instruction count is what bounds them, not gas.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InstructionCeilingObjective {
+    name: Cow<'static, str>,
+}
+
+impl InstructionCeilingObjective {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("InstructionCeilingObjective"),
+        }
+    }
+}
+
+impl Named for InstructionCeilingObjective {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl StateInitializer<AptosFuzzerState> for InstructionCeilingObjective {}
+
+impl<EM, OT> Feedback<EM, AptosFuzzerInput, OT, AptosFuzzerState> for InstructionCeilingObjective
+where
+    OT: ObserversTuple<AptosFuzzerInput, AptosFuzzerState>,
+{
+    fn is_interesting(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        _input: &AptosFuzzerInput,
+        observers: &OT,
+        _exit_kind: &libafl::executors::ExitKind,
+    ) -> Result<bool, Error> {
+        let gas_handle: Handle<GasObserver> = Handle::new(Cow::Borrowed("GasObserver"));
+        Ok(observers.get(&gas_handle).is_some_and(|obs| obs.exceeds_ceiling()))
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _manager: &mut EM,
+        observers: &OT,
+        testcase: &mut libafl::corpus::Testcase<AptosFuzzerInput>,
+    ) -> Result<(), Error> {
+        let gas_handle: Handle<GasObserver> = Handle::new(Cow::Borrowed("GasObserver"));
+        let Some(obs) = observers.get(&gas_handle) else {
+            return Ok(());
+        };
+        let heaviest_call = obs.heaviest_call().and_then(|(index, gas_used)| {
+            let call = testcase.input().as_ref()?.calls().get(index)?;
+            Some(GasRecord {
+                gas_used,
+                input: describe_call(call),
+            })
+        });
+        info!(
+            instructions = obs.gas_used(),
+            call = heaviest_call.as_ref().map(|record| record.input.as_str()),
+            "instruction ceiling exceeded"
+        );
+        testcase.add_metadata(InstructionCeilingMetadata {
+            instructions: obs.gas_used(),
+            ceiling: obs.ceiling().unwrap_or_default(),
+            heaviest_call,
+        });
+        Ok(())
+    }
 }
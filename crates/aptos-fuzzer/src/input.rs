@@ -1,19 +1,140 @@
-use aptos_types::transaction::TransactionPayload;
+use std::fs;
+use std::path::Path;
+
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::language_storage::TypeTag;
+use aptos_move_core_types::u256::U256;
+use aptos_types::transaction::{EntryFunction, MultisigTransactionPayload, TransactionPayload};
 use libafl::inputs::Input;
 use serde::{Deserialize, Serialize};
 
+/// Maximum number of calls in one transaction sequence
+pub const MAX_SEQUENCE_LEN: usize = 8;
+
+/// Maximum number of table entries one input overrides
+pub const MAX_TABLE_OVERRIDES: usize = 8;
+
+/// A sequence of transactions executed in order against the same state, so
+/// later calls observe the writes of earlier ones
 #[derive(Debug, Clone, Hash, Deserialize, Serialize)]
 pub struct AptosFuzzerInput {
+    calls: Vec<AptosCall>,
+    /// Table entries written before the first call
+    #[serde(default)]
+    tables: Vec<TableEntry>,
+}
+
+/// One `0x1::table` entry, with the BCS-encoded key and value. In JSON the
+/// bytes are hex strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct TableEntry {
+    pub handle: AccountAddress,
+    #[serde(with = "hex_bytes")]
+    pub key: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    pub value: Vec<u8>,
+}
+
+mod hex_bytes {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+        } else {
+            serializer.serialize_bytes(bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            hex::decode(s.trim_start_matches("0x")).map_err(D::Error::custom)
+        } else {
+            Vec::<u8>::deserialize(deserializer)
+        }
+    }
+}
+
+/// One transaction of an [`AptosFuzzerInput`]
+#[derive(Debug, Clone, Hash, Deserialize, Serialize)]
+pub struct AptosCall {
     payload: TransactionPayload,
+    /// Entry function argument types from the ABI, empty if unknown
+    #[serde(default)]
+    arg_types: Vec<TypeTag>,
+    /// Transaction sender, drawn from the state's account pool
+    #[serde(default)]
+    sender: Option<AccountAddress>,
+    /// Additional signers for multi-agent entry functions
+    #[serde(default)]
+    secondary_signers: Vec<AccountAddress>,
+    /// Account sponsoring the gas of the call instead of the sender
+    #[serde(default)]
+    fee_payer: Option<AccountAddress>,
 }
 
-impl Input for AptosFuzzerInput {}
+// Stored as BCS, the canonical encoding of Aptos payloads
+impl Input for AptosFuzzerInput {
+    fn to_file<P>(&self, path: P) -> Result<(), libafl::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = bcs::to_bytes(self).map_err(|e| libafl::Error::serialize(e.to_string()))?;
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    fn from_file<P>(path: P) -> Result<Self, libafl::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = fs::read(path)?;
+        bcs::from_bytes(&bytes).map_err(|e| libafl::Error::serialize(e.to_string()))
+    }
+}
 
-// Currently we only support TransactionPayload::EntryFunction
-// TODO: add script
 impl AptosFuzzerInput {
+    pub fn new(call: AptosCall) -> Self {
+        Self {
+            calls: vec![call],
+            tables: Vec::new(),
+        }
+    }
+
+    pub fn calls(&self) -> &[AptosCall] {
+        &self.calls
+    }
+
+    pub fn calls_mut(&mut self) -> &mut Vec<AptosCall> {
+        &mut self.calls
+    }
+
+    pub fn tables(&self) -> &[TableEntry] {
+        &self.tables
+    }
+
+    pub fn tables_mut(&mut self) -> &mut Vec<TableEntry> {
+        &mut self.tables
+    }
+}
+
+// Entry function, multisig and script payloads are supported
+impl AptosCall {
     pub fn new(payload: TransactionPayload) -> Self {
-        Self { payload }
+        Self {
+            payload,
+            arg_types: Vec::new(),
+            sender: None,
+            secondary_signers: Vec::new(),
+            fee_payer: None,
+        }
+    }
+
+    pub fn with_arg_types(mut self, arg_types: Vec<TypeTag>) -> Self {
+        self.arg_types = arg_types;
+        self
     }
 
     pub fn payload(&self) -> &TransactionPayload {
@@ -23,4 +144,103 @@ impl AptosFuzzerInput {
     pub fn payload_mut(&mut self) -> &mut TransactionPayload {
         &mut self.payload
     }
+
+    pub fn arg_types(&self) -> &[TypeTag] {
+        &self.arg_types
+    }
+
+    pub fn sender(&self) -> Option<AccountAddress> {
+        self.sender
+    }
+
+    pub fn set_sender(&mut self, sender: Option<AccountAddress>) {
+        self.sender = sender;
+    }
+
+    pub fn secondary_signers(&self) -> &[AccountAddress] {
+        &self.secondary_signers
+    }
+
+    pub fn secondary_signers_mut(&mut self) -> &mut Vec<AccountAddress> {
+        &mut self.secondary_signers
+    }
+
+    pub fn fee_payer(&self) -> Option<AccountAddress> {
+        self.fee_payer
+    }
+
+    pub fn set_fee_payer(&mut self, fee_payer: Option<AccountAddress>) {
+        self.fee_payer = fee_payer;
+    }
+
+    /// The entry function called, directly or through a multisig account
+    pub fn entry_function(&self) -> Option<&EntryFunction> {
+        match &self.payload {
+            TransactionPayload::EntryFunction(entry) => Some(entry),
+            TransactionPayload::Multisig(multisig) => match &multisig.transaction_payload {
+                Some(MultisigTransactionPayload::EntryFunction(entry)) => Some(entry),
+                None => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether the call only runs as a whole signed transaction: fee-payer,
+    /// multi-agent and multisig transactions are handled by the prologue and
+    /// epilogue
+    pub fn needs_signed_transaction(&self) -> bool {
+        self.fee_payer.is_some() ||
+            !self.secondary_signers.is_empty() ||
+            matches!(self.payload, TransactionPayload::Multisig(_))
+    }
+
+    /// Human-readable entry function arguments, decoded with the ABI types
+    /// when available and shown as hex otherwise
+    pub fn decoded_args(&self) -> Vec<String> {
+        let entry = match &self.payload {
+            TransactionPayload::Script(script) => return script.args().iter().map(|arg| format!("{arg:?}")).collect(),
+            _ => match self.entry_function() {
+                Some(entry) => entry,
+                None => return Vec::new(),
+            },
+        };
+
+        entry
+            .args()
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| {
+                self.arg_types
+                    .get(i)
+                    .and_then(|ty| Self::decode_arg(bytes, ty).map(|value| format!("{ty} = {value}")))
+                    .unwrap_or_else(|| format!("0x{}", bytes.iter().map(|b| format!("{b:02x}")).collect::<String>()))
+            })
+            .collect()
+    }
+
+    fn decode_arg(bytes: &[u8], type_tag: &TypeTag) -> Option<String> {
+        Some(match type_tag {
+            TypeTag::Bool => bcs::from_bytes::<bool>(bytes).ok()?.to_string(),
+            TypeTag::U8 => bcs::from_bytes::<u8>(bytes).ok()?.to_string(),
+            TypeTag::U16 => bcs::from_bytes::<u16>(bytes).ok()?.to_string(),
+            TypeTag::U32 => bcs::from_bytes::<u32>(bytes).ok()?.to_string(),
+            TypeTag::U64 => bcs::from_bytes::<u64>(bytes).ok()?.to_string(),
+            TypeTag::U128 => bcs::from_bytes::<u128>(bytes).ok()?.to_string(),
+            TypeTag::U256 => bcs::from_bytes::<U256>(bytes).ok()?.to_string(),
+            TypeTag::Address => bcs::from_bytes::<AccountAddress>(bytes).ok()?.to_hex_literal(),
+            TypeTag::Vector(inner) => match inner.as_ref() {
+                TypeTag::U8 => {
+                    let value = bcs::from_bytes::<Vec<u8>>(bytes).ok()?;
+                    format!("0x{}", value.iter().map(|b| format!("{b:02x}")).collect::<String>())
+                }
+                TypeTag::U64 => format!("{:?}", bcs::from_bytes::<Vec<u64>>(bytes).ok()?),
+                TypeTag::Address => {
+                    let value = bcs::from_bytes::<Vec<AccountAddress>>(bytes).ok()?;
+                    format!("{:?}", value.iter().map(|a| a.to_hex_literal()).collect::<Vec<_>>())
+                }
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
 }
@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashMap};
+
+use aptos_move_binary_format::access::ModuleAccess;
+use aptos_move_binary_format::file_format::{SignatureToken, StructFieldInformation, StructHandleIndex};
+use aptos_move_binary_format::CompiledModule;
+use aptos_move_core_types::identifier::IdentStr;
+use aptos_move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use aptos_move_core_types::value::{MoveFieldLayout, MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue};
+use aptos_types::state_store::state_key::StateKey;
+
+use crate::executor::aptos_custom_state::AptosCustomState;
+
+/// Deepest nesting of structs and vectors followed when building a layout
+const MAX_LAYOUT_DEPTH: usize = 16;
+
+/// Layouts of the types values are decoded as, built from the ABIs of the
+/// modules declaring them. Cached, as compatible upgrades can't change a
+/// struct.
+#[derive(Debug, Default)]
+pub struct LayoutCache {
+    layouts: HashMap<TypeTag, Option<MoveTypeLayout>>,
+}
+
+impl LayoutCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Layout of `ty`, fields named; `None` if a module is missing or the
+    /// type holds an enum
+    pub fn layout(&mut self, state: &AptosCustomState, ty: &TypeTag) -> Option<&MoveTypeLayout> {
+        self.layouts
+            .entry(ty.clone())
+            .or_insert_with(|| type_layout(state, ty, 0))
+            .as_ref()
+    }
+
+    /// The numeric and boolean values within the BCS `bytes` of a `ty`, by
+    /// dotted field path. Booleans are 0 or 1 and `u256` values above
+    /// `u128::MAX` saturate.
+    pub fn numeric_fields(
+        &mut self,
+        state: &AptosCustomState,
+        ty: &TypeTag,
+        bytes: &[u8],
+    ) -> Option<BTreeMap<String, u128>> {
+        let value = MoveValue::simple_deserialize(bytes, self.layout(state, ty)?).ok()?;
+        let mut fields = BTreeMap::new();
+        flatten(String::new(), &value, &mut fields);
+        Some(fields)
+    }
+}
+
+/// Collect the numeric and boolean values within `value` by field path
+fn flatten(path: String, value: &MoveValue, fields: &mut BTreeMap<String, u128>) {
+    let number = match value {
+        MoveValue::Bool(b) => u128::from(*b),
+        MoveValue::U8(n) => u128::from(*n),
+        MoveValue::U16(n) => u128::from(*n),
+        MoveValue::U32(n) => u128::from(*n),
+        MoveValue::U64(n) => u128::from(*n),
+        MoveValue::U128(n) => *n,
+        MoveValue::U256(n) => u128::try_from(*n).unwrap_or(u128::MAX),
+        MoveValue::Struct(MoveStruct::WithFields(struct_fields)) |
+        MoveValue::Struct(MoveStruct::WithTypes {
+            _fields: struct_fields, ..
+        }) => {
+            for (name, field) in struct_fields {
+                let path = if path.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{path}.{name}")
+                };
+                flatten(path, field, fields);
+            }
+            return;
+        }
+        _ => return,
+    };
+    fields.insert(path, number);
+}
+
+fn type_layout(state: &AptosCustomState, ty: &TypeTag, depth: usize) -> Option<MoveTypeLayout> {
+    if depth > MAX_LAYOUT_DEPTH {
+        return None;
+    }
+    Some(match ty {
+        TypeTag::Bool => MoveTypeLayout::Bool,
+        TypeTag::U8 => MoveTypeLayout::U8,
+        TypeTag::U16 => MoveTypeLayout::U16,
+        TypeTag::U32 => MoveTypeLayout::U32,
+        TypeTag::U64 => MoveTypeLayout::U64,
+        TypeTag::U128 => MoveTypeLayout::U128,
+        TypeTag::U256 => MoveTypeLayout::U256,
+        TypeTag::Address => MoveTypeLayout::Address,
+        TypeTag::Signer => MoveTypeLayout::Signer,
+        TypeTag::Vector(inner) => MoveTypeLayout::Vector(Box::new(type_layout(state, inner, depth + 1)?)),
+        TypeTag::Struct(tag) => struct_layout(state, tag, depth)?,
+        _ => return None,
+    })
+}
+
+/// Layout of `tag`, fields named, from the declaring module in `state`;
+/// enums aren't supported
+fn struct_layout(state: &AptosCustomState, tag: &StructTag, depth: usize) -> Option<MoveTypeLayout> {
+    let code = state.get_state_value(&StateKey::module(&tag.address, &tag.module))?;
+    let module = CompiledModule::deserialize(code.bytes()).ok()?;
+    let def = module
+        .struct_defs()
+        .iter()
+        .find(|def| module.identifier_at(module.struct_handle_at(def.struct_handle).name) == tag.name.as_ident_str())?;
+    let StructFieldInformation::Declared(fields) = &def.field_information else {
+        return None;
+    };
+
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let ty = field_type(&module, &field.signature.0, &tag.type_args)?;
+            Some(MoveFieldLayout::new(
+                module.identifier_at(field.name).to_owned(),
+                type_layout(state, &ty, depth + 1)?,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(MoveTypeLayout::Struct(MoveStructLayout::with_fields(fields)))
+}
+
+/// Types `function` of `module` returns, instantiated with `type_args`
+pub(crate) fn return_types(
+    state: &AptosCustomState,
+    module: &ModuleId,
+    function: &IdentStr,
+    type_args: &[TypeTag],
+) -> Option<Vec<TypeTag>> {
+    let code = state.get_state_value(&StateKey::module_id(module))?;
+    let compiled = CompiledModule::deserialize(code.bytes()).ok()?;
+    let handle = compiled
+        .function_defs()
+        .iter()
+        .map(|def| compiled.function_handle_at(def.function))
+        .find(|handle| compiled.identifier_at(handle.name) == function)?;
+    compiled
+        .signature_at(handle.return_)
+        .0
+        .iter()
+        .map(|token| field_type(&compiled, token, type_args))
+        .collect()
+}
+
+/// Type of a field declared as `token`, instantiated with `type_args`
+fn field_type(module: &CompiledModule, token: &SignatureToken, type_args: &[TypeTag]) -> Option<TypeTag> {
+    Some(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U8 => TypeTag::U8,
+        SignatureToken::U16 => TypeTag::U16,
+        SignatureToken::U32 => TypeTag::U32,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::U128 => TypeTag::U128,
+        SignatureToken::U256 => TypeTag::U256,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Signer => TypeTag::Signer,
+        SignatureToken::Vector(inner) => TypeTag::Vector(Box::new(field_type(module, inner, type_args)?)),
+        SignatureToken::Struct(handle) => TypeTag::Struct(Box::new(struct_tag(module, *handle, Vec::new()))),
+        SignatureToken::StructInstantiation(handle, tys) => {
+            let tys = tys
+                .iter()
+                .map(|ty| field_type(module, ty, type_args))
+                .collect::<Option<Vec<_>>>()?;
+            TypeTag::Struct(Box::new(struct_tag(module, *handle, tys)))
+        }
+        SignatureToken::TypeParameter(index) => type_args.get(*index as usize)?.clone(),
+        _ => return None,
+    })
+}
+
+fn struct_tag(module: &CompiledModule, handle: StructHandleIndex, type_args: Vec<TypeTag>) -> StructTag {
+    let handle = module.struct_handle_at(handle);
+    let module_handle = module.module_handle_at(handle.module);
+    StructTag {
+        address: *module.address_identifier_at(module_handle.address),
+        module: module.identifier_at(module_handle.name).to_owned(),
+        name: module.identifier_at(handle.name).to_owned(),
+        type_args,
+    }
+}
@@ -1,12 +1,36 @@
+pub mod campaign;
+pub mod events;
 pub mod executor;
 pub mod feedback;
 pub mod input;
+pub mod layout;
 pub mod mutator;
 pub mod observers;
+pub mod package;
+pub mod seeding;
 pub mod state;
+pub mod templates;
+pub mod views;
+pub mod write_rules;
 
+pub use campaign::{
+    CampaignBuilder, CampaignFinding, CampaignHandle, CampaignSummary, Detector, FuzzCampaign, FuzzerSetup,
+};
+pub use events::{EventInvariant, EventInvariants, EventPredicate, EventViolation};
 pub use executor::aptos_move_executor::AptosMoveExecutor;
-pub use feedback::{AbortCodeFeedback, AbortCodeObjective, ShiftOverflowObjective};
-pub use input::AptosFuzzerInput;
-pub use mutator::AptosFuzzerMutator;
-pub use state::AptosFuzzerState;
+pub use feedback::{
+    AbortCodeFeedback, AbortCodeObjective, ArithmeticObjective, BareVmObjective, EventInvariantFeedback,
+    EventInvariantObjective, GasFeedback, InstructionCeilingObjective, ShiftOverflowObjective, ViewObjective,
+    WriteSetFeedback, WriteSetObjective,
+};
+pub use fuzzer_core::aborts::{AbortPolicy, AbortSeverity};
+pub use fuzzer_core::findings::{Campaign, Finding, FindingsDb};
+pub use input::{AptosCall, AptosFuzzerInput, TableEntry};
+pub use mutator::{
+    AptosFuzzerMutator, BoundaryValueMutator, MutationStrategy, PowerOfTwoMutator, SenderMutator, SequenceMutator,
+    SerializedBytesMutator, TableMutator, TransactionKindMutator, TypeArgMutator,
+};
+pub use package::{budget_share, EntryTarget, FunctionSummary, PackageSummaryMetadata};
+pub use state::{AptosFuzzerState, FeatureFlags, GasMode};
+pub use views::{ViewCheck, ViewChecks, ViewPredicate, ViewViolation};
+pub use write_rules::{WriteCheck, WriteRule, WriteRules, WriteViolation};
@@ -0,0 +1,57 @@
+use std::borrow::Cow;
+
+use aptos_move_core_types::u256::U256;
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+
+use super::{integer_max, replace_integer_arg};
+use crate::input::AptosFuzzerInput;
+use crate::state::AptosFuzzerState;
+
+/// Replaces an integer argument with a value at the boundary of its type:
+/// 0, 1, MAX - 1 or MAX
+#[derive(Default)]
+pub struct BoundaryValueMutator {}
+
+impl BoundaryValueMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn generate(bits: u16, state: &mut AptosFuzzerState) -> U256 {
+        let max = integer_max(bits);
+        match state.rand_mut().below_or_zero(4) {
+            0 => U256::zero(),
+            1 => U256::one(),
+            2 => max - U256::one(),
+            _ => max,
+        }
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for BoundaryValueMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        Ok(replace_integer_arg(input, state, Self::generate))
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for BoundaryValueMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("BoundaryValueMutator");
+        &NAME
+    }
+}
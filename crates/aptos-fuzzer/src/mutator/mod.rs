@@ -0,0 +1,481 @@
+mod boundary_value;
+mod power_of_two;
+mod sender;
+mod sequence;
+mod serialized;
+mod strategy;
+mod table;
+mod transaction_kind;
+mod type_args;
+
+use std::borrow::Cow;
+
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::language_storage::TypeTag;
+use aptos_move_core_types::u256::U256;
+use aptos_types::transaction::{EntryFunction, Script, TransactionArgument, TransactionPayload};
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub use self::boundary_value::BoundaryValueMutator;
+pub use self::power_of_two::PowerOfTwoMutator;
+pub use self::sender::SenderMutator;
+pub use self::sequence::SequenceMutator;
+pub use self::serialized::{LearnedLayout, SerializedArgsMetadata, SerializedBytesMutator};
+pub use self::strategy::MutationStrategy;
+pub use self::table::TableMutator;
+pub use self::transaction_kind::TransactionKindMutator;
+pub use self::type_args::TypeArgMutator;
+use crate::input::{AptosCall, AptosFuzzerInput};
+use crate::state::AptosFuzzerState;
+
+/// Percentage of typed argument mutations given to registered strategies
+const REGISTERED_STRATEGY_PERCENT: usize = 30;
+
+#[derive(Default)]
+pub struct AptosFuzzerMutator {
+    /// Strategies added by users of the fuzzer, e.g. protocol-specific ones
+    strategies: Vec<Box<dyn MutationStrategy>>,
+}
+
+impl AptosFuzzerMutator {
+    /// Add a strategy to those applied to arguments of the types it can
+    /// handle. Registered strategies take 30% of the mutations of such
+    /// arguments, spread evenly among them.
+    pub fn register_strategy(&mut self, strategy: Box<dyn MutationStrategy>) {
+        self.strategies.push(strategy);
+    }
+
+    fn mutate_entry_function_args(
+        &mut self,
+        entry_func: &mut EntryFunction,
+        arg_types: &[TypeTag],
+        state: &mut AptosFuzzerState,
+    ) -> bool {
+        let args = entry_func.args();
+        if args.is_empty() {
+            return false;
+        }
+
+        // Create new mutated arguments
+        let mut new_args = Vec::new();
+        let mut mutated = false;
+        // Only trust the ABI types if they line up with the encoded arguments
+        let typed = arg_types.len() == args.len();
+
+        for (i, arg_bytes) in args.iter().enumerate() {
+            let mut mutated_arg = arg_bytes.clone();
+            let arg_mutated = if typed {
+                self.mutate_registered(&mut mutated_arg, &arg_types[i], state)
+                    .unwrap_or_else(|| Self::mutate_typed_arg(&mut mutated_arg, &arg_types[i], state))
+            } else {
+                Self::mutate_byte_vector(&mut mutated_arg, state)
+            };
+            if arg_mutated {
+                mutated = true;
+            }
+            new_args.push(mutated_arg);
+        }
+
+        if mutated {
+            // Reconstruct EntryFunction with mutated args
+            let (module, function, ty_args, _) = entry_func.clone().into_inner();
+            *entry_func = EntryFunction::new(module, function, ty_args, new_args);
+        }
+
+        mutated
+    }
+
+    /// Mutate Script arguments using state's random source (pure random)
+    fn mutate_script_args(script: &mut Script, state: &mut AptosFuzzerState) -> bool {
+        let args = script.args();
+        if args.is_empty() {
+            return false;
+        }
+
+        // Create new mutated arguments
+        let mut new_args = Vec::new();
+        let mut mutated = false;
+
+        for arg in args.iter() {
+            let mut mutated_arg = arg.clone();
+            if Self::mutate_transaction_argument(&mut mutated_arg, state) {
+                mutated = true;
+            }
+            new_args.push(mutated_arg);
+        }
+
+        if mutated {
+            // Reconstruct Script with mutated args
+            let (code, ty_args, _) = script.clone().into_inner();
+            *script = Script::new(code, ty_args, new_args);
+        }
+
+        mutated
+    }
+
+    /// Mutate an argument with a registered strategy, if one that applies is
+    /// picked
+    fn mutate_registered(
+        &mut self,
+        bytes: &mut Vec<u8>,
+        type_tag: &TypeTag,
+        state: &mut AptosFuzzerState,
+    ) -> Option<bool> {
+        if state.rand_mut().below_or_zero(100) >= REGISTERED_STRATEGY_PERCENT {
+            return None;
+        }
+        let applicable: Vec<usize> = (0..self.strategies.len())
+            .filter(|&i| self.strategies[i].can_apply(type_tag))
+            .collect();
+        if applicable.is_empty() {
+            return None;
+        }
+        let index = applicable[state.rand_mut().below_or_zero(applicable.len())];
+        Some(self.strategies[index].mutate(bytes, type_tag, state))
+    }
+
+    /// Decode a BCS argument of the given type, mutate the typed value and
+    /// re-encode it. Types without a typed mutation are left untouched rather
+    /// than being corrupted into undecodable bytes.
+    fn mutate_typed_arg(bytes: &mut Vec<u8>, type_tag: &TypeTag, state: &mut AptosFuzzerState) -> bool {
+        match type_tag {
+            TypeTag::Bool => Self::mutate_bcs::<bool>(bytes, state, |v, _| *v = !*v),
+            TypeTag::U8 => {
+                Self::mutate_bcs::<u8>(bytes, state, |v, s| *v = Self::mutate_integer(*v as u128, 8, s) as u8)
+            }
+            TypeTag::U16 => {
+                Self::mutate_bcs::<u16>(bytes, state, |v, s| *v = Self::mutate_integer(*v as u128, 16, s) as u16)
+            }
+            TypeTag::U32 => {
+                Self::mutate_bcs::<u32>(bytes, state, |v, s| *v = Self::mutate_integer(*v as u128, 32, s) as u32)
+            }
+            TypeTag::U64 => {
+                Self::mutate_bcs::<u64>(bytes, state, |v, s| *v = Self::mutate_integer(*v as u128, 64, s) as u64)
+            }
+            TypeTag::U128 => Self::mutate_bcs::<u128>(bytes, state, |v, s| *v = Self::mutate_integer(*v, 128, s)),
+            TypeTag::U256 => Self::mutate_bcs::<U256>(bytes, state, Self::mutate_u256),
+            TypeTag::Address => Self::mutate_bcs::<AccountAddress>(bytes, state, Self::mutate_address),
+            TypeTag::Vector(inner) => match inner.as_ref() {
+                TypeTag::U8 => Self::mutate_bcs::<Vec<u8>>(bytes, state, |v, s| {
+                    Self::mutate_byte_vector(v, s);
+                }),
+                TypeTag::U64 => Self::mutate_bcs::<Vec<u64>>(bytes, state, |v, s| {
+                    Self::mutate_vector(v, s, |e, s| *e = Self::mutate_integer(*e as u128, 64, s) as u64)
+                }),
+                TypeTag::Address => Self::mutate_bcs::<Vec<AccountAddress>>(bytes, state, |v, s| {
+                    Self::mutate_vector(v, s, Self::mutate_address)
+                }),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn mutate_bcs<T: Serialize + DeserializeOwned>(
+        bytes: &mut Vec<u8>,
+        state: &mut AptosFuzzerState,
+        mutate: impl FnOnce(&mut T, &mut AptosFuzzerState),
+    ) -> bool {
+        let Ok(mut value) = bcs::from_bytes::<T>(bytes) else {
+            return false;
+        };
+        mutate(&mut value, state);
+        match bcs::to_bytes(&value) {
+            Ok(encoded) => {
+                *bytes = encoded;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Integer of `bits` width: random value, off-by-one or single bit flip
+    fn mutate_integer(value: u128, bits: u32, state: &mut AptosFuzzerState) -> u128 {
+        let mask = if bits == 128 { u128::MAX } else { (1u128 << bits) - 1 };
+        let mutated = match state.rand_mut().below_or_zero(4) {
+            0 => ((state.rand_mut().next() as u128) << 64) | state.rand_mut().next() as u128,
+            1 => value.wrapping_add(1),
+            2 => value.wrapping_sub(1),
+            _ => value ^ (1u128 << (state.rand_mut().next() % bits as u64)),
+        };
+        mutated & mask
+    }
+
+    fn mutate_u256(value: &mut U256, state: &mut AptosFuzzerState) {
+        let mut bytes = value.to_le_bytes();
+        if state.rand_mut().coinflip(0.5) {
+            for byte in bytes.iter_mut() {
+                *byte = (state.rand_mut().next() & 0xFF) as u8;
+            }
+        } else {
+            let bit = (state.rand_mut().next() % 256) as usize;
+            bytes[bit / 8] ^= 1 << (bit % 8);
+        }
+        *value = U256::from_le_bytes(&bytes);
+    }
+
+    fn mutate_address(addr: &mut AccountAddress, state: &mut AptosFuzzerState) {
+        *addr = match state.rand_mut().below_or_zero(4) {
+            0 => AccountAddress::ZERO,
+            1 => AccountAddress::ONE,
+            _ => {
+                let mut addr_bytes = [0u8; AccountAddress::LENGTH];
+                for byte in addr_bytes.iter_mut() {
+                    *byte = (state.rand_mut().next() & 0xFF) as u8;
+                }
+                AccountAddress::new(addr_bytes)
+            }
+        };
+    }
+
+    /// Mutate one element of a vector, or grow/shrink it by one element
+    fn mutate_vector<T: Clone>(
+        vec: &mut Vec<T>,
+        state: &mut AptosFuzzerState,
+        mutate_element: impl Fn(&mut T, &mut AptosFuzzerState),
+    ) {
+        let Some(first) = vec.first().cloned() else {
+            return;
+        };
+        match state.rand_mut().below_or_zero(3) {
+            0 => {
+                let mut element = first;
+                mutate_element(&mut element, state);
+                vec.push(element);
+            }
+            1 if vec.len() > 1 => {
+                vec.pop();
+            }
+            _ => {
+                let index = state.rand_mut().below_or_zero(vec.len());
+                mutate_element(&mut vec[index], state);
+            }
+        }
+    }
+
+    /// Mutate a byte vector using state's random source (pure random bytes)
+    fn mutate_byte_vector(bytes: &mut Vec<u8>, state: &mut AptosFuzzerState) -> bool {
+        let len = if bytes.is_empty() {
+            // choose a small random length
+            (1 + (state.rand_mut().next() % 16)) as usize
+        } else {
+            // keep current length
+            bytes.len()
+        };
+        bytes.resize(len, 0);
+        for b in bytes.iter_mut() {
+            *b = (state.rand_mut().next() & 0xFF) as u8;
+        }
+        true
+    }
+
+    /// Mutate a TransactionArgument using state's random source (pure random)
+    fn mutate_transaction_argument(arg: &mut TransactionArgument, state: &mut AptosFuzzerState) -> bool {
+        match arg {
+            TransactionArgument::U8(val) => {
+                *val = (state.rand_mut().next() & 0xFF) as u8;
+                true
+            }
+            TransactionArgument::U16(val) => {
+                *val = (state.rand_mut().next() % 65536) as u16;
+                true
+            }
+            TransactionArgument::U32(val) => {
+                *val = (state.rand_mut().next() & 0xFFFF_FFFF) as u32;
+                true
+            }
+            TransactionArgument::U64(val) => {
+                *val = state.rand_mut().next();
+                true
+            }
+            TransactionArgument::U128(val) => {
+                let hi = state.rand_mut().next() as u128;
+                let lo = state.rand_mut().next() as u128;
+                *val = (hi << 64) | lo;
+                true
+            }
+            TransactionArgument::U256(val) => {
+                let high_part = {
+                    let hi = state.rand_mut().next() as u128;
+                    let lo = state.rand_mut().next() as u128;
+                    (hi << 64) | lo
+                };
+                let low_part = {
+                    let hi = state.rand_mut().next() as u128;
+                    let lo = state.rand_mut().next() as u128;
+                    (hi << 64) | lo
+                };
+                let mut bytes = [0u8; 32];
+                bytes[0..16].copy_from_slice(&low_part.to_le_bytes());
+                bytes[16..32].copy_from_slice(&high_part.to_le_bytes());
+                *val = aptos_move_core_types::u256::U256::from_le_bytes(&bytes);
+                true
+            }
+            TransactionArgument::Bool(val) => {
+                *val = (state.rand_mut().next() & 1) == 0;
+                true
+            }
+            TransactionArgument::Address(_addr) => {
+                let mut addr_bytes = [0u8; 32];
+                for byte in addr_bytes.iter_mut() {
+                    *byte = (state.rand_mut().next() % 256) as u8;
+                }
+                *_addr = aptos_move_core_types::account_address::AccountAddress::try_from(addr_bytes.to_vec())
+                    .unwrap_or(*_addr);
+                true
+            }
+            TransactionArgument::U8Vector(vec) => {
+                let len = (state.rand_mut().next() % 64) as usize;
+                vec.clear();
+                for _ in 0..len {
+                    vec.push((state.rand_mut().next() & 0xFF) as u8);
+                }
+                true
+            }
+            TransactionArgument::Serialized(bytes) => {
+                let len = (state.rand_mut().next() % 64) as usize;
+                bytes.clear();
+                bytes.resize(len, 0);
+                for b in bytes.iter_mut() {
+                    *b = (state.rand_mut().next() & 0xFF) as u8;
+                }
+                true
+            }
+        }
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for AptosFuzzerMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        let Some(call) = pick_call(input, state) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let arg_types = call.arg_types().to_vec();
+        let mutated = match call.payload_mut() {
+            TransactionPayload::EntryFunction(entry_func) => {
+                self.mutate_entry_function_args(entry_func, &arg_types, state)
+            }
+            TransactionPayload::Script(script) => Self::mutate_script_args(script, state),
+            _ => false, // Other payload types not supported for current mutator
+        };
+
+        if mutated {
+            Ok(MutationResult::Mutated)
+        } else {
+            Ok(MutationResult::Skipped)
+        }
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for AptosFuzzerMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("AptosFuzzerMutator");
+        &NAME
+    }
+}
+
+/// Bit width of an integer argument type
+fn integer_bits(type_tag: &TypeTag) -> Option<u16> {
+    match type_tag {
+        TypeTag::U8 => Some(8),
+        TypeTag::U16 => Some(16),
+        TypeTag::U32 => Some(32),
+        TypeTag::U64 => Some(64),
+        TypeTag::U128 => Some(128),
+        TypeTag::U256 => Some(256),
+        _ => None,
+    }
+}
+
+/// Largest value representable in `bits`
+fn integer_max(bits: u16) -> U256 {
+    if bits >= 256 {
+        U256::max_value()
+    } else {
+        (U256::one() << bits as u8) - U256::one()
+    }
+}
+
+/// BCS-encode `value` as the given integer type, if it fits
+pub(crate) fn encode_integer(value: U256, type_tag: &TypeTag) -> Option<Vec<u8>> {
+    match type_tag {
+        TypeTag::U8 => bcs::to_bytes(&u8::try_from(value).ok()?).ok(),
+        TypeTag::U16 => bcs::to_bytes(&u16::try_from(value).ok()?).ok(),
+        TypeTag::U32 => bcs::to_bytes(&u32::try_from(value).ok()?).ok(),
+        TypeTag::U64 => bcs::to_bytes(&u64::try_from(value).ok()?).ok(),
+        TypeTag::U128 => bcs::to_bytes(&u128::try_from(value).ok()?).ok(),
+        TypeTag::U256 => bcs::to_bytes(&value).ok(),
+        _ => None,
+    }
+}
+
+/// Pick one call of a sequence input to mutate
+fn pick_call<'a>(input: &'a mut AptosFuzzerInput, state: &mut AptosFuzzerState) -> Option<&'a mut AptosCall> {
+    let len = input.calls().len();
+    if len == 0 {
+        return None;
+    }
+    let index = state.rand_mut().below_or_zero(len);
+    input.calls_mut().get_mut(index)
+}
+
+/// Replace one randomly chosen integer argument of an entry function call
+/// with a value produced by `generate(bits, state)`.
+///
+/// Inputs without ABI argument types or without integer arguments are
+/// skipped.
+fn replace_integer_arg(
+    input: &mut AptosFuzzerInput,
+    state: &mut AptosFuzzerState,
+    generate: impl FnOnce(u16, &mut AptosFuzzerState) -> U256,
+) -> MutationResult {
+    let Some(call) = pick_call(input, state) else {
+        return MutationResult::Skipped;
+    };
+    let arg_types = call.arg_types().to_vec();
+    let TransactionPayload::EntryFunction(entry_func) = call.payload_mut() else {
+        return MutationResult::Skipped;
+    };
+    if arg_types.len() != entry_func.args().len() {
+        return MutationResult::Skipped;
+    }
+
+    let candidates: Vec<usize> = arg_types
+        .iter()
+        .enumerate()
+        .filter(|(_, ty)| integer_bits(ty).is_some())
+        .map(|(i, _)| i)
+        .collect();
+    if candidates.is_empty() {
+        return MutationResult::Skipped;
+    }
+
+    let index = candidates[state.rand_mut().below_or_zero(candidates.len())];
+    let type_tag = &arg_types[index];
+    let bits = integer_bits(type_tag).unwrap_or(64);
+    let Some(encoded) = encode_integer(generate(bits, state), type_tag) else {
+        return MutationResult::Skipped;
+    };
+
+    let (module, function, ty_args, mut args) = entry_func.clone().into_inner();
+    args[index] = encoded;
+    *entry_func = EntryFunction::new(module, function, ty_args, args);
+    MutationResult::Mutated
+}
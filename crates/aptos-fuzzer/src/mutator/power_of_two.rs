@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+
+use aptos_move_core_types::u256::U256;
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+
+use super::replace_integer_arg;
+use crate::input::AptosFuzzerInput;
+use crate::state::AptosFuzzerState;
+
+/// Replaces an integer argument with a power of two or one of its neighbours
+/// (2^n - 1 masks, 2^n + 1), the values most likely to expose shift and
+/// overflow edge cases
+#[derive(Default)]
+pub struct PowerOfTwoMutator {}
+
+impl PowerOfTwoMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn generate(bits: u16, state: &mut AptosFuzzerState) -> U256 {
+        let exponent = state.rand_mut().below_or_zero(bits as usize) as u8;
+        let power = U256::one() << exponent;
+        match state.rand_mut().below_or_zero(3) {
+            0 => power,
+            1 => power - U256::one(),
+            // 2^(bits-1) + 1 still fits for every width above 1 bit
+            _ => power + U256::one(),
+        }
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for PowerOfTwoMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        Ok(replace_integer_arg(input, state, Self::generate))
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for PowerOfTwoMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("PowerOfTwoMutator");
+        &NAME
+    }
+}
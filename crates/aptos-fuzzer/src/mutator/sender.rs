@@ -0,0 +1,70 @@
+use std::borrow::Cow;
+
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+
+use crate::input::AptosFuzzerInput;
+use crate::state::AptosFuzzerState;
+
+/// Replaces the transaction sender or one of the secondary signers of a
+/// multi-agent call with another account from the state's funded sender pool
+#[derive(Default)]
+pub struct SenderMutator {}
+
+impl SenderMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for SenderMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        let sender_count = state.senders().len();
+        if sender_count < 2 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let choice = state.rand_mut().below_or_zero(sender_count);
+        let account = state.senders()[choice];
+        let Some(call) = super::pick_call(input, state) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let slot = state.rand_mut().below_or_zero(1 + call.secondary_signers().len());
+
+        if slot == 0 {
+            if call.sender() == Some(account) {
+                return Ok(MutationResult::Skipped);
+            }
+            call.set_sender(Some(account));
+        } else {
+            let signer = &mut call.secondary_signers_mut()[slot - 1];
+            if *signer == account {
+                return Ok(MutationResult::Skipped);
+            }
+            *signer = account;
+        }
+
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for SenderMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("SenderMutator");
+        &NAME
+    }
+}
@@ -0,0 +1,91 @@
+use std::borrow::Cow;
+
+use libafl::corpus::Corpus;
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::random_corpus_id;
+use libafl::state::{HasCorpus, HasRand};
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+
+use crate::input::{AptosFuzzerInput, MAX_SEQUENCE_LEN};
+use crate::state::AptosFuzzerState;
+
+/// Mutates the shape of a transaction sequence: inserts a call copied from
+/// another corpus entry (or the input itself), removes a call or swaps two
+/// calls, so setup transactions can be discovered and reordered
+#[derive(Default)]
+pub struct SequenceMutator {}
+
+impl SequenceMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert_call(state: &mut AptosFuzzerState, input: &mut AptosFuzzerInput) -> MutationResult {
+        let len = input.calls().len();
+        if len >= MAX_SEQUENCE_LEN {
+            return MutationResult::Skipped;
+        }
+
+        // Prefer calls from other corpus entries to combine what they reached
+        let donor = if state.corpus().count() > 0 && state.rand_mut().coinflip(0.5) {
+            let id = random_corpus_id!(state.corpus(), state.rand_mut());
+            state.corpus().cloned_input_for_id(id).ok()
+        } else {
+            None
+        };
+        let source = donor.as_ref().unwrap_or(input).calls();
+        if source.is_empty() {
+            return MutationResult::Skipped;
+        }
+        let call = source[state.rand_mut().below_or_zero(source.len())].clone();
+
+        let position = state.rand_mut().below_or_zero(len + 1);
+        input.calls_mut().insert(position, call);
+        MutationResult::Mutated
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for SequenceMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        let len = input.calls().len();
+        let result = match state.rand_mut().below_or_zero(3) {
+            1 if len > 1 => {
+                let index = state.rand_mut().below_or_zero(len);
+                input.calls_mut().remove(index);
+                MutationResult::Mutated
+            }
+            2 if len > 1 => {
+                let a = state.rand_mut().below_or_zero(len);
+                let b = state.rand_mut().below_or_zero(len);
+                if a == b {
+                    return Ok(MutationResult::Skipped);
+                }
+                input.calls_mut().swap(a, b);
+                MutationResult::Mutated
+            }
+            _ => Self::insert_call(state, input),
+        };
+
+        Ok(result)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for SequenceMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("SequenceMutator");
+        &NAME
+    }
+}
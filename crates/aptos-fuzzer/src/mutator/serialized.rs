@@ -0,0 +1,395 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use aptos_move_binary_format::access::ModuleAccess;
+use aptos_move_binary_format::file_format::{Bytecode, SignatureToken};
+use aptos_move_binary_format::CompiledModule;
+use aptos_move_core_types::identifier::IdentStr;
+use aptos_move_core_types::language_storage::{ModuleId, TypeTag};
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::transaction::{EntryFunction, TransactionPayload};
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl::HasMetadata;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+use serde::{Deserialize, Serialize};
+
+use crate::input::AptosFuzzerInput;
+use crate::seeding::uleb128;
+use crate::state::AptosFuzzerState;
+
+/// Functions of a module followed from an entry function when collecting
+/// the constants it compares against
+const MAX_SCANNED_FUNCTIONS: usize = 64;
+
+/// Longest `vector<u8>` argument this mutator grows
+const MAX_SERIALIZED_LEN: usize = 4096;
+
+/// Offsets and values remembered per argument
+const MAX_LEARNED: usize = 32;
+
+/// Length prefixes and tag bytes of a `vector<u8>` argument whose mutation
+/// reached new coverage
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LearnedLayout {
+    /// Offsets of ULEB128 length prefixes
+    pub prefixes: BTreeSet<usize>,
+    /// Bytes written at an offset
+    pub tags: BTreeMap<usize, BTreeSet<Vec<u8>>>,
+}
+
+/// What [`SerializedBytesMutator`] knows of the `vector<u8>` arguments
+/// holding serialized structures
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SerializedArgsMetadata {
+    /// Constants each entry function compares against
+    pub tokens: HashMap<String, Vec<Vec<u8>>>,
+    /// Layouts learned for each `module::function#argument`
+    pub learned: HashMap<String, LearnedLayout>,
+}
+
+libafl_bolts::impl_serdeany!(SerializedArgsMetadata);
+
+/// An edit made to a `vector<u8>` argument, remembered if it finds new
+/// coverage
+#[derive(Debug, Clone)]
+enum Edit {
+    /// Body behind the length prefix at the offset resized
+    Prefix(usize),
+    /// Bytes written at the offset
+    Tag(usize, Vec<u8>),
+}
+
+/// Mutates `vector<u8>` arguments as the BCS of a richer structure: resizes
+/// the body behind a ULEB128 length prefix and rewrites the prefix, or
+/// writes a constant the called function compares against (a tag byte, a
+/// magic value) at an offset. Edits that lead to a new corpus entry are
+/// learned per argument and replayed more often than fresh ones.
+#[derive(Default)]
+pub struct SerializedBytesMutator {
+    last: Option<(String, Edit)>,
+}
+
+impl SerializedBytesMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constants `function` compares against, collected once per function
+    fn tokens(state: &mut AptosFuzzerState, module: &ModuleId, function: &IdentStr) -> Vec<Vec<u8>> {
+        let key = format!("{module}::{function}");
+        if let Some(tokens) = state
+            .metadata_map()
+            .get::<SerializedArgsMetadata>()
+            .and_then(|meta| meta.tokens.get(&key))
+        {
+            return tokens.clone();
+        }
+        let tokens = state
+            .aptos_state()
+            .get_state_value(&StateKey::module_id(module))
+            .and_then(|code| CompiledModule::deserialize(code.bytes()).ok())
+            .map(|compiled| comparison_tokens(&compiled, function))
+            .unwrap_or_default();
+        state
+            .metadata_or_insert_with(SerializedArgsMetadata::default)
+            .tokens
+            .insert(key, tokens.clone());
+        tokens
+    }
+
+    /// Resize the body behind a length prefix, or append a new prefixed
+    /// body if none fits
+    fn resize_prefixed(
+        bytes: &mut Vec<u8>,
+        learned: &LearnedLayout,
+        tokens: &[Vec<u8>],
+        state: &mut AptosFuzzerState,
+    ) -> Option<Edit> {
+        let known: Vec<usize> = learned
+            .prefixes
+            .iter()
+            .copied()
+            .filter(|&offset| prefix_at(bytes, offset).is_some())
+            .collect();
+        let mut found = if !known.is_empty() && state.rand_mut().coinflip(0.5) {
+            let offset = known[state.rand_mut().below_or_zero(known.len())];
+            prefix_at(bytes, offset).map(|prefix| (offset, prefix))
+        } else {
+            None
+        };
+        for _ in 0..8 {
+            if found.is_some() || bytes.is_empty() {
+                break;
+            }
+            let offset = state.rand_mut().below_or_zero(bytes.len());
+            found = prefix_at(bytes, offset).map(|prefix| (offset, prefix));
+        }
+        let (offset, (len, size)) = found.unwrap_or_else(|| {
+            bytes.push(0);
+            (bytes.len() - 1, (0, 1))
+        });
+
+        // Lengths the function checks, e.g. a fixed hash or key size
+        let checked: Vec<usize> = tokens
+            .iter()
+            .filter_map(|token| token_value(token))
+            .filter(|&value| value <= 256)
+            .collect();
+        let new_len = match state.rand_mut().below_or_zero(3) {
+            0 => len + 1 + state.rand_mut().below_or_zero(4),
+            1 => len.saturating_sub(1 + state.rand_mut().below_or_zero(4)),
+            _ if !checked.is_empty() => checked[state.rand_mut().below_or_zero(checked.len())],
+            _ => state.rand_mut().below_or_zero(64),
+        };
+        if bytes.len() - len + new_len > MAX_SERIALIZED_LEN {
+            return None;
+        }
+
+        let body_end = offset + size + len;
+        if new_len > len {
+            let grown: Vec<u8> = (len..new_len).map(|_| (state.rand_mut().next() & 0xFF) as u8).collect();
+            bytes.splice(body_end..body_end, grown);
+        } else {
+            bytes.drain(offset + size + new_len..body_end);
+        }
+        bytes.splice(offset..offset + size, uleb128(new_len));
+        Some(Edit::Prefix(offset))
+    }
+
+    /// Overwrite bytes at an offset with a learned tag, a compared constant
+    /// or a random byte
+    fn write_tag(
+        bytes: &mut Vec<u8>,
+        learned: &LearnedLayout,
+        tokens: &[Vec<u8>],
+        state: &mut AptosFuzzerState,
+    ) -> Option<Edit> {
+        let (offset, tag) = if !learned.tags.is_empty() && state.rand_mut().coinflip(0.5) {
+            let (offset, tags) = learned
+                .tags
+                .iter()
+                .nth(state.rand_mut().below_or_zero(learned.tags.len()))?;
+            let tag = tags.iter().nth(state.rand_mut().below_or_zero(tags.len()))?;
+            (*offset, tag.clone())
+        } else {
+            let tag = if !tokens.is_empty() && state.rand_mut().coinflip(0.75) {
+                tokens[state.rand_mut().below_or_zero(tokens.len())].clone()
+            } else {
+                vec![(state.rand_mut().next() & 0xFF) as u8]
+            };
+            (state.rand_mut().below_or_zero(bytes.len() + 1), tag)
+        };
+
+        let end = offset + tag.len();
+        if end > MAX_SERIALIZED_LEN {
+            return None;
+        }
+        if end > bytes.len() {
+            bytes.resize(end, 0);
+        }
+        bytes[offset..end].copy_from_slice(&tag);
+        Some(Edit::Tag(offset, tag))
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for SerializedBytesMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        self.last = None;
+        let Some(call) = super::pick_call(input, state) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let arg_types = call.arg_types().to_vec();
+        let TransactionPayload::EntryFunction(entry_func) = call.payload_mut() else {
+            return Ok(MutationResult::Skipped);
+        };
+        if arg_types.len() != entry_func.args().len() {
+            return Ok(MutationResult::Skipped);
+        }
+        let candidates: Vec<usize> = arg_types
+            .iter()
+            .enumerate()
+            .filter(|(_, ty)| matches!(ty, TypeTag::Vector(inner) if **inner == TypeTag::U8))
+            .map(|(i, _)| i)
+            .collect();
+        if candidates.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let index = candidates[state.rand_mut().below_or_zero(candidates.len())];
+        let Ok(mut bytes) = bcs::from_bytes::<Vec<u8>>(&entry_func.args()[index]) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let tokens = Self::tokens(state, entry_func.module(), entry_func.function());
+        let key = format!("{}::{}#{index}", entry_func.module(), entry_func.function());
+        let learned = state
+            .metadata_map()
+            .get::<SerializedArgsMetadata>()
+            .and_then(|meta| meta.learned.get(&key))
+            .cloned()
+            .unwrap_or_default();
+
+        let edit = if state.rand_mut().coinflip(0.5) {
+            Self::resize_prefixed(&mut bytes, &learned, &tokens, state)
+        } else {
+            Self::write_tag(&mut bytes, &learned, &tokens, state)
+        };
+        let Some(edit) = edit else {
+            return Ok(MutationResult::Skipped);
+        };
+        let Ok(encoded) = bcs::to_bytes(&bytes) else {
+            return Ok(MutationResult::Skipped);
+        };
+
+        let (module, function, ty_args, mut args) = entry_func.clone().into_inner();
+        args[index] = encoded;
+        *entry_func = EntryFunction::new(module, function, ty_args, args);
+        self.last = Some((key, edit));
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        let Some((key, edit)) = self.last.take() else {
+            return Ok(());
+        };
+        if new_corpus_id.is_none() {
+            return Ok(());
+        }
+        let learned = state
+            .metadata_or_insert_with(SerializedArgsMetadata::default)
+            .learned
+            .entry(key)
+            .or_default();
+        match edit {
+            Edit::Prefix(offset) if learned.prefixes.len() < MAX_LEARNED => {
+                learned.prefixes.insert(offset);
+            }
+            Edit::Tag(offset, tag) if learned.tags.len() < MAX_LEARNED || learned.tags.contains_key(&offset) => {
+                let tags = learned.tags.entry(offset).or_default();
+                if tags.len() < MAX_LEARNED {
+                    tags.insert(tag);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Named for SerializedBytesMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("SerializedBytesMutator");
+        &NAME
+    }
+}
+
+/// Constants compared against in `function` and the functions of its
+/// module it calls, as the bytes they would take in a BCS encoding:
+/// integer literals little-endian, and `vector<u8>` constants as is. The
+/// VM reports no comparison operands, so these stand in for the values a
+/// deserializer checks its input bytes against.
+fn comparison_tokens(module: &CompiledModule, function: &IdentStr) -> Vec<Vec<u8>> {
+    let Some(start) = module
+        .function_defs()
+        .iter()
+        .position(|def| module.identifier_at(module.function_handle_at(def.function).name) == function)
+    else {
+        return Vec::new();
+    };
+
+    let mut pending = vec![start];
+    let mut visited = HashSet::new();
+    let mut tokens = BTreeSet::new();
+    while let Some(index) = pending.pop() {
+        if visited.len() >= MAX_SCANNED_FUNCTIONS || !visited.insert(index) {
+            continue;
+        }
+        let Some(code) = &module.function_defs()[index].code else {
+            continue;
+        };
+        for (pc, instruction) in code.code.iter().enumerate() {
+            let callee = match instruction {
+                Bytecode::Call(handle) => Some(*handle),
+                Bytecode::CallGeneric(instantiation) => Some(module.function_instantiation_at(*instantiation).handle),
+                _ => None,
+            };
+            // Callees of other modules have no definition here
+            if let Some(callee) =
+                callee.and_then(|handle| module.function_defs().iter().position(|def| def.function == handle))
+            {
+                pending.push(callee);
+            }
+
+            // The literal is the last operand pushed, or the first with the
+            // other loaded right after it
+            if !code.code[pc + 1..].iter().take(2).any(is_comparison) {
+                continue;
+            }
+            let token = match instruction {
+                Bytecode::LdU8(value) => vec![*value],
+                Bytecode::LdU16(value) => value.to_le_bytes().to_vec(),
+                Bytecode::LdU32(value) => value.to_le_bytes().to_vec(),
+                Bytecode::LdU64(value) => value.to_le_bytes().to_vec(),
+                Bytecode::LdConst(constant) => {
+                    let constant = module.constant_at(*constant);
+                    if constant.type_ != SignatureToken::Vector(Box::new(SignatureToken::U8)) {
+                        continue;
+                    }
+                    match bcs::from_bytes::<Vec<u8>>(&constant.data) {
+                        Ok(bytes) if !bytes.is_empty() => bytes,
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            tokens.insert(token);
+        }
+    }
+
+    tokens.into_iter().collect()
+}
+
+fn is_comparison(instruction: &Bytecode) -> bool {
+    matches!(
+        instruction,
+        Bytecode::Eq | Bytecode::Neq | Bytecode::Lt | Bytecode::Gt | Bytecode::Le | Bytecode::Ge
+    )
+}
+
+/// Integer a token of at most 8 bytes encodes
+fn token_value(token: &[u8]) -> Option<usize> {
+    if token.len() > 8 {
+        return None;
+    }
+    let mut bytes = [0u8; 8];
+    bytes[..token.len()].copy_from_slice(token);
+    usize::try_from(u64::from_le_bytes(bytes)).ok()
+}
+
+/// Length and size of the prefix at `offset` if its body fits in `bytes`
+fn prefix_at(bytes: &[u8], offset: usize) -> Option<(usize, usize)> {
+    let (len, size) = read_uleb128(bytes.get(offset..)?)?;
+    (offset + size + len <= bytes.len()).then_some((len, size))
+}
+
+/// Value and size of the canonical ULEB128 length at the start of `bytes`
+fn read_uleb128(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0usize;
+    // BCS lengths fit in 32 bits
+    for (i, &byte) in bytes.iter().take(5).enumerate() {
+        value |= usize::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return (i == 0 || byte != 0).then_some((value, i + 1));
+        }
+    }
+    None
+}
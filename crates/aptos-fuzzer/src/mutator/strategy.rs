@@ -0,0 +1,18 @@
+use aptos_move_core_types::language_storage::TypeTag;
+
+use crate::state::AptosFuzzerState;
+
+/// Mutation of one BCS-encoded entry function argument, registered on
+/// [`AptosFuzzerMutator`](super::AptosFuzzerMutator) by crates adding
+/// protocol-specific mutations, e.g. valid tick values for an AMM
+pub trait MutationStrategy: Send + Sync {
+    /// Mutate `bytes`, an argument of type `type_tag`, returning whether it
+    /// changed
+    fn mutate(&mut self, bytes: &mut Vec<u8>, type_tag: &TypeTag, state: &mut AptosFuzzerState) -> bool;
+
+    /// Check if this strategy can be applied to arguments of `type_tag`
+    fn can_apply(&self, type_tag: &TypeTag) -> bool;
+
+    /// Get a description of this strategy (for debugging/logging)
+    fn description(&self) -> &'static str;
+}
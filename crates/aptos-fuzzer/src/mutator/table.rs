@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+
+use crate::input::{AptosFuzzerInput, TableEntry, MAX_TABLE_OVERRIDES};
+use crate::state::AptosFuzzerState;
+
+/// Perturbs `0x1::table` contents seen by an input: copies an entry of the
+/// state's tables into the input's overrides with its value perturbed, or
+/// perturbs or drops an existing override. Overrides are written before the
+/// first call, so functions branching on table contents can be fuzzed.
+#[derive(Default)]
+pub struct TableMutator {}
+
+impl TableMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add_override(state: &mut AptosFuzzerState, input: &mut AptosFuzzerInput) -> MutationResult {
+        let count = state.aptos_state().table_entries().count();
+        if count == 0 {
+            return MutationResult::Skipped;
+        }
+        let index = state.rand_mut().below_or_zero(count);
+        let Some(mut entry) = state
+            .aptos_state()
+            .table_entries()
+            .nth(index)
+            .map(|(handle, key, value)| TableEntry {
+                handle,
+                key: key.to_vec(),
+                value: value.to_vec(),
+            })
+        else {
+            return MutationResult::Skipped;
+        };
+        Self::perturb(&mut entry.value, state);
+
+        let tables = input.tables_mut();
+        match tables
+            .iter_mut()
+            .find(|existing| existing.handle == entry.handle && existing.key == entry.key)
+        {
+            Some(existing) => existing.value = entry.value,
+            None if tables.len() < MAX_TABLE_OVERRIDES => tables.push(entry),
+            None => return MutationResult::Skipped,
+        }
+        MutationResult::Mutated
+    }
+
+    /// Small in-place change so the BCS value mostly keeps decoding
+    fn perturb(value: &mut [u8], state: &mut AptosFuzzerState) {
+        if value.is_empty() {
+            return;
+        }
+        let index = state.rand_mut().below_or_zero(value.len());
+        let byte = &mut value[index];
+        *byte = match state.rand_mut().below_or_zero(4) {
+            0 => *byte ^ (1 << state.rand_mut().below_or_zero(8)),
+            1 => byte.wrapping_add(1 + state.rand_mut().below_or_zero(16) as u8),
+            2 => byte.wrapping_sub(1 + state.rand_mut().below_or_zero(16) as u8),
+            _ => {
+                if state.rand_mut().coinflip(0.5) {
+                    0
+                } else {
+                    u8::MAX
+                }
+            }
+        };
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for TableMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        let len = input.tables().len();
+        let result = match state.rand_mut().below_or_zero(3) {
+            1 if len > 0 => {
+                let index = state.rand_mut().below_or_zero(len);
+                input.tables_mut().remove(index);
+                MutationResult::Mutated
+            }
+            2 if len > 0 => {
+                let index = state.rand_mut().below_or_zero(len);
+                let mut value = std::mem::take(&mut input.tables_mut()[index].value);
+                Self::perturb(&mut value, state);
+                input.tables_mut()[index].value = value;
+                MutationResult::Mutated
+            }
+            _ => Self::add_override(state, input),
+        };
+
+        Ok(result)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for TableMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("TableMutator");
+        &NAME
+    }
+}
@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_types::transaction::{Multisig, MultisigTransactionPayload, TransactionPayload};
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+
+use crate::input::{AptosCall, AptosFuzzerInput};
+use crate::state::AptosFuzzerState;
+
+/// Turns a call into a fee-payer or multisig transaction and back: sets,
+/// replaces or drops a fee payer other than the sender, or sends an entry
+/// function through a multisig account from the state's account pool.
+/// Such calls run whole, so the prologue's handling of payers and multisig
+/// approvals gets fuzzed too.
+#[derive(Default)]
+pub struct TransactionKindMutator {}
+
+impl TransactionKindMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A pool account other than `excluded`
+    fn other_account(state: &mut AptosFuzzerState, excluded: Option<AccountAddress>) -> Option<AccountAddress> {
+        let candidates: Vec<AccountAddress> = state
+            .senders()
+            .iter()
+            .copied()
+            .filter(|account| Some(*account) != excluded)
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(candidates[state.rand_mut().below_or_zero(candidates.len())])
+    }
+
+    fn mutate_fee_payer(call: &mut AptosCall, state: &mut AptosFuzzerState) -> MutationResult {
+        if call.fee_payer().is_some() && state.rand_mut().coinflip(0.3) {
+            call.set_fee_payer(None);
+            return MutationResult::Mutated;
+        }
+        let Some(payer) = Self::other_account(state, call.sender()) else {
+            return MutationResult::Skipped;
+        };
+        if call.fee_payer() == Some(payer) {
+            return MutationResult::Skipped;
+        }
+        call.set_fee_payer(Some(payer));
+        MutationResult::Mutated
+    }
+
+    fn toggle_multisig(call: &mut AptosCall, state: &mut AptosFuzzerState) -> MutationResult {
+        let multisig_address = Self::other_account(state, call.sender());
+        let payload = call.payload_mut();
+        *payload = match payload {
+            TransactionPayload::EntryFunction(entry) => {
+                let Some(multisig_address) = multisig_address else {
+                    return MutationResult::Skipped;
+                };
+                TransactionPayload::Multisig(Multisig {
+                    multisig_address,
+                    transaction_payload: Some(MultisigTransactionPayload::EntryFunction(entry.clone())),
+                })
+            }
+            TransactionPayload::Multisig(Multisig {
+                transaction_payload: Some(MultisigTransactionPayload::EntryFunction(entry)),
+                ..
+            }) => TransactionPayload::EntryFunction(entry.clone()),
+            _ => return MutationResult::Skipped,
+        };
+        MutationResult::Mutated
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for TransactionKindMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        if state.senders().len() < 2 {
+            return Ok(MutationResult::Skipped);
+        }
+        let fee_payer = state.rand_mut().coinflip(0.5);
+        let Some(call) = super::pick_call(input, state) else {
+            return Ok(MutationResult::Skipped);
+        };
+        Ok(if fee_payer {
+            Self::mutate_fee_payer(call, state)
+        } else {
+            Self::toggle_multisig(call, state)
+        })
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for TransactionKindMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("TransactionKindMutator");
+        &NAME
+    }
+}
@@ -0,0 +1,82 @@
+use std::borrow::Cow;
+
+use aptos_types::transaction::{EntryFunction, Script, TransactionPayload};
+use libafl::mutators::{MutationResult, Mutator};
+use libafl::state::HasRand;
+use libafl_bolts::rands::Rand;
+use libafl_bolts::Named;
+
+use crate::input::AptosFuzzerInput;
+use crate::state::AptosFuzzerState;
+
+/// Re-instantiates one type parameter of a generic entry function or script
+/// with another candidate type from the state
+#[derive(Default)]
+pub struct TypeArgMutator {}
+
+impl TypeArgMutator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Mutator<AptosFuzzerInput, AptosFuzzerState> for TypeArgMutator {
+    fn mutate(
+        &mut self,
+        state: &mut AptosFuzzerState,
+        input: &mut AptosFuzzerInput,
+    ) -> Result<MutationResult, libafl::Error> {
+        let Some(call) = super::pick_call(input, state) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let candidate_count = state.type_arg_candidates().len();
+        let type_param_count = match call.payload() {
+            TransactionPayload::EntryFunction(entry_func) => entry_func.ty_args().len(),
+            TransactionPayload::Script(script) => script.ty_args().len(),
+            _ => 0,
+        };
+        if type_param_count == 0 || candidate_count < 2 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let choice = state.rand_mut().below_or_zero(candidate_count);
+        let candidate = state.type_arg_candidates()[choice].clone();
+        let index = state.rand_mut().below_or_zero(type_param_count);
+        match call.payload_mut() {
+            TransactionPayload::EntryFunction(entry_func) => {
+                let (module, function, mut ty_args, args) = entry_func.clone().into_inner();
+                if ty_args[index] == candidate {
+                    return Ok(MutationResult::Skipped);
+                }
+                ty_args[index] = candidate;
+                *entry_func = EntryFunction::new(module, function, ty_args, args);
+            }
+            TransactionPayload::Script(script) => {
+                let (code, mut ty_args, args) = script.clone().into_inner();
+                if ty_args[index] == candidate {
+                    return Ok(MutationResult::Skipped);
+                }
+                ty_args[index] = candidate;
+                *script = Script::new(code, ty_args, args);
+            }
+            _ => return Ok(MutationResult::Skipped),
+        }
+
+        Ok(MutationResult::Mutated)
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut AptosFuzzerState,
+        _new_corpus_id: Option<libafl::corpus::CorpusId>,
+    ) -> Result<(), libafl::Error> {
+        Ok(())
+    }
+}
+
+impl Named for TypeArgMutator {
+    fn name(&self) -> &Cow<'static, str> {
+        static NAME: Cow<'static, str> = Cow::Borrowed("TypeArgMutator");
+        &NAME
+    }
+}
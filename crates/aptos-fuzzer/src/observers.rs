@@ -4,10 +4,33 @@ use libafl::observers::Observer;
 use libafl_bolts::Named;
 use serde::{Deserialize, Serialize};
 
+use crate::events::DecodedEvent;
+use crate::executor::{ArithmeticEvent, BareVmDivergence, ShiftEvent};
+use crate::views::ViewViolation;
+use crate::write_rules::{FieldChange, WriteViolation};
+
+/// Where a transaction aborted: the module raising the abort, the entry
+/// function (or script) the aborting transaction called, and the abort code.
+/// Also attached to testcases as metadata for triage.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AbortSite {
+    pub module: String,
+    pub function: String,
+    pub code: u64,
+}
+
+libafl_bolts::impl_serdeany!(AbortSite);
+
+impl std::fmt::Display for AbortSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (called via {}) code {}", self.module, self.function, self.code)
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AbortCodeObserver {
     name: Cow<'static, str>,
-    last: Option<u64>,
+    last: Option<AbortSite>,
 }
 
 impl AbortCodeObserver {
@@ -19,10 +42,14 @@ impl AbortCodeObserver {
     }
 
     pub fn last(&self) -> Option<u64> {
-        self.last
+        self.last.as_ref().map(|site| site.code)
     }
 
-    pub fn set_last(&mut self, v: Option<u64>) {
+    pub fn last_site(&self) -> Option<&AbortSite> {
+        self.last.as_ref()
+    }
+
+    pub fn set_last(&mut self, v: Option<AbortSite>) {
         self.last = v;
     }
 }
@@ -35,26 +62,39 @@ impl Named for AbortCodeObserver {
 
 impl<I, S> Observer<I, S> for AbortCodeObserver {}
 
+/// Lossy shifts of the last execution, attached to testcases as metadata
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ShiftOverflowMetadata {
+    pub overflows: Vec<ShiftEvent>,
+}
+
+libafl_bolts::impl_serdeany!(ShiftOverflowMetadata);
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ShiftOverflowObserver {
     name: Cow<'static, str>,
-    cause_loss: bool,
+    /// Shifts of the last execution that lost high-order bits
+    overflows: Vec<ShiftEvent>,
 }
 
 impl ShiftOverflowObserver {
     pub fn new() -> Self {
         Self {
             name: Cow::Borrowed("ShiftOverflowObserver"),
-            cause_loss: false,
+            overflows: Vec::new(),
         }
     }
 
     pub fn cause_loss(&self) -> bool {
-        self.cause_loss
+        !self.overflows.is_empty()
+    }
+
+    pub fn overflows(&self) -> &[ShiftEvent] {
+        &self.overflows
     }
 
-    pub fn set_cause_loss(&mut self, v: bool) {
-        self.cause_loss = v;
+    pub fn set_overflows(&mut self, overflows: Vec<ShiftEvent>) {
+        self.overflows = overflows;
     }
 }
 
@@ -65,3 +105,257 @@ impl Named for ShiftOverflowObserver {
 }
 
 impl<I, S> Observer<I, S> for ShiftOverflowObserver {}
+
+/// Records the gas consumed by the last execution. The fuzzing VM runs
+/// unmetered, so gas is estimated as the number of executed instructions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GasObserver {
+    name: Cow<'static, str>,
+    gas_used: u64,
+    /// Gas of each call, in order
+    call_gas: Vec<u64>,
+    /// Instructions above which an execution is a potential DoS
+    ceiling: Option<u64>,
+}
+
+impl GasObserver {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("GasObserver"),
+            gas_used: 0,
+            call_gas: Vec::new(),
+            ceiling: None,
+        }
+    }
+
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    pub fn set_gas_used(&mut self, v: u64) {
+        self.gas_used = v;
+    }
+
+    pub fn call_gas(&self) -> &[u64] {
+        &self.call_gas
+    }
+
+    pub fn set_call_gas(&mut self, call_gas: Vec<u64>) {
+        self.call_gas = call_gas;
+    }
+
+    /// Index and gas of the most expensive call
+    pub fn heaviest_call(&self) -> Option<(usize, u64)> {
+        self.call_gas.iter().copied().enumerate().max_by_key(|(_, gas)| *gas)
+    }
+
+    pub fn ceiling(&self) -> Option<u64> {
+        self.ceiling
+    }
+
+    pub fn set_ceiling(&mut self, ceiling: Option<u64>) {
+        self.ceiling = ceiling;
+    }
+
+    pub fn exceeds_ceiling(&self) -> bool {
+        self.ceiling.is_some_and(|ceiling| self.gas_used > ceiling)
+    }
+}
+
+impl Named for GasObserver {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<I, S> Observer<I, S> for GasObserver {}
+
+/// Arithmetic overflows and cast truncations of the last execution. Move
+/// aborts on these, but an abort on attacker-controlled arithmetic often hides
+/// a missing bounds check.
+/// Arithmetic overflows of the last execution, attached to testcases as
+/// metadata
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ArithmeticMetadata {
+    pub events: Vec<ArithmeticEvent>,
+}
+
+libafl_bolts::impl_serdeany!(ArithmeticMetadata);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ArithmeticObserver {
+    name: Cow<'static, str>,
+    events: Vec<ArithmeticEvent>,
+}
+
+impl ArithmeticObserver {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("ArithmeticObserver"),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn events(&self) -> &[ArithmeticEvent] {
+        &self.events
+    }
+
+    pub fn set_events(&mut self, events: Vec<ArithmeticEvent>) {
+        self.events = events;
+    }
+}
+
+impl Named for ArithmeticObserver {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<I, S> Observer<I, S> for ArithmeticObserver {}
+
+/// Events emitted by the calls of the last execution
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventObserver {
+    name: Cow<'static, str>,
+    events: Vec<DecodedEvent>,
+    /// Whether every call succeeded
+    completed: bool,
+}
+
+impl EventObserver {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("EventObserver"),
+            events: Vec::new(),
+            completed: false,
+        }
+    }
+
+    pub fn events(&self) -> &[DecodedEvent] {
+        &self.events
+    }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn set_events(&mut self, events: Vec<DecodedEvent>, completed: bool) {
+        self.events = events;
+        self.completed = completed;
+    }
+}
+
+impl Named for EventObserver {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<I, S> Observer<I, S> for EventObserver {}
+
+/// Changes the calls of the last execution made to the resources of write
+/// rules, and the rules they broke
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WriteSetObserver {
+    name: Cow<'static, str>,
+    changes: Vec<FieldChange>,
+    violations: Vec<WriteViolation>,
+}
+
+impl WriteSetObserver {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("WriteSetObserver"),
+            changes: Vec::new(),
+            violations: Vec::new(),
+        }
+    }
+
+    pub fn changes(&self) -> &[FieldChange] {
+        &self.changes
+    }
+
+    pub fn violations(&self) -> &[WriteViolation] {
+        &self.violations
+    }
+
+    pub fn set_changes(&mut self, changes: Vec<FieldChange>, violations: Vec<WriteViolation>) {
+        self.changes = changes;
+        self.violations = violations;
+    }
+}
+
+impl Named for WriteSetObserver {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<I, S> Observer<I, S> for WriteSetObserver {}
+
+/// View checks the calls of the last execution broke
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ViewObserver {
+    name: Cow<'static, str>,
+    violations: Vec<ViewViolation>,
+}
+
+impl ViewObserver {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("ViewObserver"),
+            violations: Vec::new(),
+        }
+    }
+
+    pub fn violations(&self) -> &[ViewViolation] {
+        &self.violations
+    }
+
+    pub fn set_violations(&mut self, violations: Vec<ViewViolation>) {
+        self.violations = violations;
+    }
+}
+
+impl Named for ViewObserver {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<I, S> Observer<I, S> for ViewObserver {}
+
+/// Calls of the last execution whose outcome differed in a bare MoveVM
+/// session, see [`AptosMoveExecutor::with_bare_vm_comparison`]
+///
+/// [`AptosMoveExecutor::with_bare_vm_comparison`]: crate::AptosMoveExecutor::with_bare_vm_comparison
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BareVmObserver {
+    name: Cow<'static, str>,
+    divergences: Vec<BareVmDivergence>,
+}
+
+impl BareVmObserver {
+    pub fn new() -> Self {
+        Self {
+            name: Cow::Borrowed("BareVmObserver"),
+            divergences: Vec::new(),
+        }
+    }
+
+    pub fn divergences(&self) -> &[BareVmDivergence] {
+        &self.divergences
+    }
+
+    pub fn set_divergences(&mut self, divergences: Vec<BareVmDivergence>) {
+        self.divergences = divergences;
+    }
+}
+
+impl Named for BareVmObserver {
+    fn name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+}
+
+impl<I, S> Observer<I, S> for BareVmObserver {}
@@ -0,0 +1,181 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use aptos_move_binary_format::access::ModuleAccess;
+use aptos_move_binary_format::file_format::{Bytecode, FunctionDefinition, SignatureToken};
+use aptos_move_binary_format::CompiledModule;
+use aptos_move_core_types::language_storage::ModuleId;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Prefix of the files package campaigns save their summaries to
+pub const PACKAGE_SUMMARY_PREFIX: &str = ".package-summary";
+
+/// An entry function of the fuzzed package, see
+/// [`AptosFuzzerState::package_entry_functions`](crate::AptosFuzzerState::package_entry_functions)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryTarget {
+    pub module: ModuleId,
+    pub function: String,
+    /// First parameter type that can't be generated, if any
+    pub unsupported: Option<String>,
+    /// Static likelihood of arithmetic bugs, scored from the bytecode
+    pub score: u64,
+}
+
+/// Score a function by the signals of arithmetic bugs in its bytecode:
+/// shifts weigh 3, multiplications, divisions and modulos 2, additions,
+/// subtractions and casts 1, plus 2 per integer parameter and up to 10 for
+/// the share of arithmetic among its instructions
+pub(crate) fn static_score(module: &CompiledModule, function_def: &FunctionDefinition) -> u64 {
+    let handle = module.function_handle_at(function_def.function);
+    let int_params = module
+        .signature_at(handle.parameters)
+        .0
+        .iter()
+        .filter(|token| {
+            matches!(
+                token,
+                SignatureToken::U8 |
+                    SignatureToken::U16 |
+                    SignatureToken::U32 |
+                    SignatureToken::U64 |
+                    SignatureToken::U128 |
+                    SignatureToken::U256
+            )
+        })
+        .count() as u64;
+
+    let code = function_def
+        .code
+        .as_ref()
+        .map(|unit| unit.code.as_slice())
+        .unwrap_or_default();
+    let mut weighted = 0;
+    let mut arithmetic = 0;
+    for instruction in code {
+        let weight = match instruction {
+            Bytecode::Shl | Bytecode::Shr => 3,
+            Bytecode::Mul | Bytecode::Div | Bytecode::Mod => 2,
+            Bytecode::Add |
+            Bytecode::Sub |
+            Bytecode::CastU8 |
+            Bytecode::CastU16 |
+            Bytecode::CastU32 |
+            Bytecode::CastU64 |
+            Bytecode::CastU128 |
+            Bytecode::CastU256 => 1,
+            _ => continue,
+        };
+        weighted += weight;
+        arithmetic += 1;
+    }
+    let density = (arithmetic * 10).checked_div(code.len() as u64).unwrap_or(0);
+
+    weighted + 2 * int_params + density
+}
+
+/// Fuzzing time of `target` when every function of `targets` gets
+/// `per_function` on average: shares are proportional to `score + 1`, so
+/// functions without arithmetic still get some time
+pub fn budget_share(target: &EntryTarget, targets: &[EntryTarget], per_function: Duration) -> Duration {
+    let total: u64 = targets.iter().map(|target| target.score + 1).sum();
+    if total == 0 {
+        return per_function;
+    }
+    per_function.mul_f64((target.score + 1) as f64 * targets.len() as f64 / total as f64)
+}
+
+impl std::fmt::Display for EntryTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}", self.module, self.function)
+    }
+}
+
+/// Outcome of fuzzing one entry function of a package campaign
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionSummary {
+    pub target: EntryTarget,
+    pub executions: u64,
+    /// Inputs added to the corpus while fuzzing the function
+    pub corpus_entries: usize,
+    /// Inputs saved as solutions while fuzzing the function
+    pub solutions: usize,
+    pub seconds: u64,
+}
+
+impl FunctionSummary {
+    /// Summary of a function that wasn't fuzzed because of its parameters
+    pub fn skipped(target: EntryTarget) -> Self {
+        Self {
+            target,
+            executions: 0,
+            corpus_entries: 0,
+            solutions: 0,
+            seconds: 0,
+        }
+    }
+}
+
+impl std::fmt::Display for FunctionSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.target.unsupported {
+            Some(param) => write!(f, "{}: skipped, unsupported parameter {param}", self.target),
+            None => write!(
+                f,
+                "{} (score {}): {} executions in {}s, {} corpus entries, {} solutions",
+                self.target, self.target.score, self.executions, self.seconds, self.corpus_entries, self.solutions
+            ),
+        }
+    }
+}
+
+/// Functions a package campaign client has finished. Kept in the state so a
+/// respawned client resumes with the next function.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PackageSummaryMetadata {
+    pub functions: Vec<FunctionSummary>,
+}
+
+libafl_bolts::impl_serdeany!(PackageSummaryMetadata);
+
+impl PackageSummaryMetadata {
+    pub fn is_done(&self, target: &EntryTarget) -> bool {
+        self.functions.iter().any(|summary| summary.target == *target)
+    }
+
+    /// Write the summaries to `path` as JSON
+    pub fn save(&self, path: &Path) -> Result<(), libafl::Error> {
+        let json = serde_json::to_vec_pretty(&self.functions).map_err(|e| libafl::Error::serialize(e.to_string()))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Summaries saved in `dir` by every client of a package campaign
+    pub fn load_dir(dir: &Path) -> Vec<FunctionSummary> {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut functions = Vec::new();
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let is_summary = path
+                .file_name()
+                .map(|name| name.to_string_lossy().starts_with(PACKAGE_SUMMARY_PREFIX))
+                .unwrap_or(false);
+            if !is_summary {
+                continue;
+            }
+            match fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| serde_json::from_slice::<Vec<FunctionSummary>>(&bytes).map_err(|e| e.to_string()))
+            {
+                Ok(summaries) => functions.extend(summaries),
+                Err(err) => warn!(path = %path.display(), %err, "skipping package summary"),
+            }
+        }
+        functions
+    }
+}
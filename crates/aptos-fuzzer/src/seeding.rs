@@ -0,0 +1,223 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::language_storage::{ModuleId, TypeTag};
+use aptos_move_core_types::u256::U256;
+use aptos_types::transaction::{EntryFunction, TransactionPayload};
+use fuzzer_core::seeding::{scan_call_literals, MoveLiteral};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, warn};
+
+use crate::executor::{ForkConfig, RestStateFetcher};
+use crate::input::AptosFuzzerInput;
+use crate::mutator::encode_integer;
+
+/// Indexer query for the latest user transactions calling any of the given
+/// entry functions up to a ledger version
+const USER_TRANSACTIONS_QUERY: &str = r#"
+query SeedTransactions($functions: [String!], $version: bigint, $limit: Int) {
+  user_transactions(
+    where: { entry_function_id_str: { _in: $functions }, version: { _lte: $version } }
+    order_by: { version: desc }
+    limit: $limit
+  ) {
+    version
+  }
+}"#;
+
+#[derive(Deserialize)]
+struct GraphQlResponse {
+    data: Option<UserTransactions>,
+    #[serde(default)]
+    errors: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct UserTransactions {
+    user_transactions: Vec<UserTransaction>,
+}
+
+#[derive(Deserialize)]
+struct UserTransaction {
+    version: u64,
+}
+
+/// Inputs replaying the arguments of up to `limit` recent successful
+/// on-chain calls of the entry functions `templates` call, found through the
+/// indexer at `indexer_url` and fetched from the fork's fullnode at or before
+/// its pinned version. Each keeps the argument types, sender and signers of
+/// the template it matches.
+pub fn onchain_seeds(
+    fork: &ForkConfig,
+    indexer_url: &str,
+    templates: &[AptosFuzzerInput],
+    limit: usize,
+) -> anyhow::Result<Vec<AptosFuzzerInput>> {
+    let mut by_function = HashMap::new();
+    for template in templates {
+        let [call] = template.calls() else {
+            continue;
+        };
+        if let TransactionPayload::EntryFunction(entry) = call.payload() {
+            by_function
+                .entry((entry.module().clone(), entry.function().to_string()))
+                .or_insert(call);
+        }
+    }
+    if by_function.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let fetcher = RestStateFetcher::new(fork)?;
+    let functions: Vec<String> = by_function
+        .keys()
+        .flat_map(|(module, function)| function_ids(module, function))
+        .collect();
+    let versions = query_versions(indexer_url, &functions, fetcher.version(), limit)?;
+    debug!(versions = versions.len(), "found on-chain calls");
+
+    let mut seeds = Vec::new();
+    for version in versions {
+        let txn = match fetcher.user_transaction(version) {
+            Ok(Some(txn)) => txn,
+            Ok(None) => continue,
+            Err(err) => {
+                warn!(version, err = %format!("{err:#}"), "skipping on-chain transaction");
+                continue;
+            }
+        };
+        let TransactionPayload::EntryFunction(entry) = txn.payload() else {
+            continue;
+        };
+        let Some(template) = by_function.get(&(entry.module().clone(), entry.function().to_string())) else {
+            continue;
+        };
+        // Arguments are decoded with the template's types, so they must line
+        // up with them
+        let TransactionPayload::EntryFunction(expected) = template.payload() else {
+            continue;
+        };
+        if entry.args().len() != expected.args().len() || entry.ty_args().len() != expected.ty_args().len() {
+            continue;
+        }
+        let mut call = (*template).clone();
+        *call.payload_mut() = TransactionPayload::EntryFunction(entry.clone());
+        seeds.push(AptosFuzzerInput::new(call));
+    }
+    Ok(seeds)
+}
+
+/// The indexer's `entry_function_id_str` of a function, with the address
+/// both in short and in full form
+fn function_ids(module: &ModuleId, function: &str) -> [String; 2] {
+    [
+        format!("{}::{}::{function}", module.address().to_hex_literal(), module.name()),
+        format!(
+            "{}::{}::{function}",
+            module.address().to_standard_string(),
+            module.name()
+        ),
+    ]
+}
+
+fn query_versions(indexer_url: &str, functions: &[String], version: u64, limit: usize) -> anyhow::Result<Vec<u64>> {
+    let response: GraphQlResponse = reqwest::blocking::Client::new()
+        .post(indexer_url)
+        .json(&json!({
+            "query": USER_TRANSACTIONS_QUERY,
+            "variables": { "functions": functions, "version": version, "limit": limit },
+        }))
+        .send()
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("failed to query indexer {indexer_url}"))?
+        .json()
+        .with_context(|| format!("invalid indexer response from {indexer_url}"))?;
+    if !response.errors.is_empty() {
+        bail!("indexer {indexer_url} rejected the query: {:?}", response.errors);
+    }
+    Ok(response
+        .data
+        .map(|data| data.user_transactions.into_iter().map(|txn| txn.version).collect())
+        .unwrap_or_default())
+}
+
+/// Inputs calling the entry functions `templates` call with the literal
+/// arguments their calls in the Move sources and tests under `package_dir`
+/// pass. Non-literal arguments keep the template's value; call sites
+/// without any usable literal are skipped.
+pub fn source_seeds(package_dir: &Path, templates: &[AptosFuzzerInput]) -> Vec<AptosFuzzerInput> {
+    let mut seen = HashSet::new();
+    let mut seeds = Vec::new();
+    for template in templates {
+        let [call] = template.calls() else {
+            continue;
+        };
+        let TransactionPayload::EntryFunction(entry) = call.payload() else {
+            continue;
+        };
+        let arg_types = call.arg_types();
+        if arg_types.len() != entry.args().len() {
+            continue;
+        }
+        let literals = scan_call_literals(package_dir, entry.module().name().as_str(), entry.function().as_str());
+        for literals in literals {
+            // Signers come first in source but aren't payload arguments
+            let Some(skip) = literals.len().checked_sub(arg_types.len()) else {
+                continue;
+            };
+            let mut args = entry.args().to_vec();
+            let mut replaced = false;
+            for ((arg, literal), type_tag) in args.iter_mut().zip(&literals[skip..]).zip(arg_types) {
+                if let Some(bytes) = literal.as_ref().and_then(|literal| encode_literal(literal, type_tag)) {
+                    *arg = bytes;
+                    replaced = true;
+                }
+            }
+            if !replaced || !seen.insert((entry.module().clone(), entry.function().to_owned(), args.clone())) {
+                continue;
+            }
+            let mut call = call.clone();
+            if let TransactionPayload::EntryFunction(entry) = call.payload_mut() {
+                let (module, function, ty_args, _) = entry.clone().into_inner();
+                *entry = EntryFunction::new(module, function, ty_args, args);
+            }
+            seeds.push(AptosFuzzerInput::new(call));
+        }
+    }
+    debug!(seeds = seeds.len(), "found literal arguments in Move sources");
+    seeds
+}
+
+/// BCS encoding of a source literal as an argument of type `type_tag`
+fn encode_literal(literal: &MoveLiteral, type_tag: &TypeTag) -> Option<Vec<u8>> {
+    match (literal, type_tag) {
+        (MoveLiteral::Integer(value), _) => encode_integer(U256::from(*value), type_tag),
+        (MoveLiteral::Bool(value), TypeTag::Bool) => bcs::to_bytes(value).ok(),
+        (MoveLiteral::Address(address), TypeTag::Address) => {
+            bcs::to_bytes(&AccountAddress::from_hex_literal(address).ok()?).ok()
+        }
+        (MoveLiteral::Bytes(bytes), TypeTag::Vector(inner)) if **inner == TypeTag::U8 => bcs::to_bytes(bytes).ok(),
+        (MoveLiteral::Vector(items), TypeTag::Vector(inner)) => {
+            let mut bytes = uleb128(items.len());
+            for item in items {
+                bytes.extend(encode_literal(item, inner)?);
+            }
+            Some(bytes)
+        }
+        _ => None,
+    }
+}
+
+/// BCS length prefix of a sequence
+pub(crate) fn uleb128(mut value: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+    bytes
+}
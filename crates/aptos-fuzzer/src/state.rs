@@ -1,15 +1,31 @@
 use std::cell::{Ref, RefMut};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::Context;
+use aptos_framework::ReleaseBundle;
+use aptos_move_binary_format::access::{ModuleAccess, ScriptAccess};
+use aptos_move_binary_format::file_format::{CompiledScript, SignatureToken, Visibility};
 use aptos_move_binary_format::CompiledModule;
 use aptos_move_core_types::account_address::AccountAddress;
 use aptos_move_core_types::identifier::Identifier;
-use aptos_move_core_types::language_storage::{ModuleId, TypeTag};
+use aptos_move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use aptos_move_core_types::u256::U256;
-use aptos_types::transaction::{EntryABI, EntryFunction as AptosEntryFunction, EntryFunctionABI, TransactionPayload};
-use libafl::corpus::{Corpus, CorpusId, HasCurrentCorpusId, HasTestcase, InMemoryCorpus, Testcase};
+use aptos_types::chain_id::ChainId;
+use aptos_types::on_chain_config::{Features, GasScheduleV2};
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::transaction::{
+    ArgumentABI, EntryABI, EntryFunction as AptosEntryFunction, EntryFunctionABI, Script, TransactionArgument,
+    TransactionPayload, TransactionScriptABI, TypeArgumentABI,
+};
+use libafl::corpus::{CachedOnDiskCorpus, Corpus, CorpusId, HasCurrentCorpusId, HasTestcase, OnDiskCorpus, Testcase};
+use libafl::feedbacks::MapFeedbackMetadata;
+use libafl::inputs::Input;
 use libafl::stages::StageId;
 use libafl::state::{
     HasCorpus, HasCurrentStageId, HasExecutions, HasImported, HasLastFoundTime, HasLastReportTime, HasRand,
@@ -18,11 +34,27 @@ use libafl::state::{
 use libafl::{HasMetadata, HasNamedMetadata};
 use libafl_bolts::rands::StdRand;
 use libafl_bolts::serdeany::{NamedSerdeAnyMap, SerdeAnyMap};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::executor::aptos_custom_state::{AptosCustomState, DEFAULT_ACCOUNT_BALANCE};
+use crate::executor::aptos_move_executor::EDGES_OBSERVER_NAME;
+use crate::executor::rest_state_fetcher::{ForkConfig, RestStateFetcher};
+use crate::feedback::{AbortSitesMetadata, ArithmeticSitesMetadata};
+use crate::input::{AptosCall, AptosFuzzerInput, TableEntry};
+use crate::package::{static_score, EntryTarget};
+
+/// Number of corpus testcases kept in memory
+const CORPUS_CACHE_SIZE: usize = 4096;
 
-use crate::executor::aptos_custom_state::AptosCustomState;
-use crate::input::AptosFuzzerInput;
+/// Set once the process is asked to stop, e.g. by Ctrl-C
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
 
 // Similar to libafl::state::StdState
+//
+// Serializable so restarting event managers can hand the state to a respawned
+// client; the Aptos state itself is rebuilt with `restore_aptos_state`.
+#[derive(Serialize, Deserialize)]
 pub struct AptosFuzzerState {
     // RNG instance
     rand: StdRand,
@@ -32,10 +64,10 @@ pub struct AptosFuzzerState {
     start_time: Duration,
     /// the number of new paths that imported from other fuzzers
     imported: usize,
-    /// The corpus
-    corpus: InMemoryCorpus<AptosFuzzerInput>,
+    /// The corpus, persisted to disk with an in-memory cache
+    corpus: CachedOnDiskCorpus<AptosFuzzerInput>,
     /// Solution corpus
-    solutions: InMemoryCorpus<AptosFuzzerInput>,
+    solutions: OnDiskCorpus<AptosFuzzerInput>,
     /// Metadata stored for this state by one of the components
     metadata: SerdeAnyMap,
     /// Metadata stored with names
@@ -50,46 +82,513 @@ pub struct AptosFuzzerState {
     /// Request the fuzzer to stop at the start of the next stage
     /// or at the beginning of the next fuzzing iteration
     stop_requested: bool,
+    /// Stop flag of the in-process campaign owning this state, which then
+    /// ignores the process-wide interrupt
+    #[serde(skip)]
+    stop_flag: Option<Arc<AtomicBool>>,
     stage_stack: StageStack,
 
     /// Aptos specific fields
+    #[serde(skip)]
     aptos_state: AptosCustomState,
+    /// Fullnode `aptos_state` reads through to, pinned at a version
+    fork: Option<ForkConfig>,
+    /// Module, package directory or bundle deployed into `aptos_state`
+    module_path: Option<PathBuf>,
+    /// Modules of `module_path` that weren't already on chain
+    package_modules: Vec<ModuleId>,
+    /// State snapshot loaded into `aptos_state` after setup
+    state_snapshot: Option<PathBuf>,
+    /// Framework release bundle deployed over the built-in framework
+    framework_bundle: Option<PathBuf>,
+    /// Gas configuration installed into `aptos_state`, if not the default
+    gas_mode: Option<GasMode>,
+    /// Feature flags installed into `aptos_state`, if not the default
+    features: Option<Features>,
+    /// Chain ID installed into `aptos_state`, if not the default or forked
+    /// one
+    chain_id: Option<u8>,
+    /// Table entries seeded into `aptos_state` from fixtures
+    seeded_tables: Vec<TableEntry>,
+    /// Module, package directory or bundle the package was upgraded to
+    upgrade_path: Option<PathBuf>,
+    /// Types used to instantiate generic entry functions
+    type_arg_candidates: Vec<TypeTag>,
+    /// Funded accounts the mutator rotates through as transaction senders
+    senders: Vec<AccountAddress>,
+}
+
+/// Gas schedule installed on chain, see [`AptosFuzzerState::set_gas_mode`].
+/// Entry function and script calls run without a gas meter either way:
+/// the schedule only sets the gas parameters natives read, and charges the
+/// fee-payer and multisig transactions run whole.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum GasMode {
+    /// No gas schedule: natives get zeroed gas parameters
+    Unmetered,
+    /// Install this schedule, e.g. for natives whose behavior depends on
+    /// their gas parameters
+    Schedule(GasScheduleV2),
+}
+
+impl FromStr for GasMode {
+    type Err = String;
+
+    /// `unmetered`, `release` for the framework's gas schedule or the path
+    /// of a JSON `GasScheduleV2`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unmetered" => Ok(Self::Unmetered),
+            "release" => Ok(Self::Schedule(AptosCustomState::release_gas_schedule())),
+            path => {
+                let json = fs::read_to_string(path).map_err(|e| format!("failed to read gas schedule {path}: {e}"))?;
+                serde_json::from_str(&json)
+                    .map(Self::Schedule)
+                    .map_err(|e| format!("invalid gas schedule {path}: {e}"))
+            }
+        }
+    }
+}
+
+/// Feature flags executions run with, see [`AptosFuzzerState::set_features`]
+#[derive(Clone, Debug)]
+pub struct FeatureFlags(pub Features);
+
+impl FeatureFlags {
+    /// Flags currently enabled on the network served by the fullnode at `url`
+    fn fetch(url: &str) -> anyhow::Result<Features> {
+        let fetcher = RestStateFetcher::new(&ForkConfig {
+            url: url.to_string(),
+            version: None,
+        })?;
+        let value = fetcher
+            .get(&StateKey::on_chain_config::<Features>()?)
+            .context("no feature flags on chain")?;
+        Ok(bcs::from_bytes(value.bytes())?)
+    }
+}
+
+impl FromStr for FeatureFlags {
+    type Err = String;
+
+    /// `default` for the flags of the framework release the fuzzer is built
+    /// with, the REST URL of a fullnode to pull the network's flags from, or
+    /// the path of a JSON list of enabled flag numbers
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "default" {
+            return Ok(Self(Features::default()));
+        }
+        if s.starts_with("http://") || s.starts_with("https://") {
+            return Self::fetch(s)
+                .map(Self)
+                .map_err(|e| format!("failed to fetch feature flags from {s}: {e:#}"));
+        }
+
+        let json = fs::read_to_string(s).map_err(|e| format!("failed to read feature flags {s}: {e}"))?;
+        let flags: Vec<usize> = serde_json::from_str(&json).map_err(|e| format!("invalid feature flags {s}: {e}"))?;
+        let mut bitmap = vec![0u8; flags.iter().max().map_or(0, |max| max / 8 + 1)];
+        for flag in flags {
+            bitmap[flag / 8] |= 1 << (flag % 8);
+        }
+        Ok(Self(Features { features: bitmap }))
+    }
+}
+
+/// Feedback state saved next to the corpus by
+/// [`AptosFuzzerState::save_history`]
+#[derive(Default, Serialize, Deserialize)]
+struct FeedbackHistory {
+    edges: Option<MapFeedbackMetadata<u8>>,
+    abort_sites: AbortSitesMetadata,
+    arithmetic_sites: ArithmeticSitesMetadata,
 }
 
 impl AptosFuzzerState {
-    pub fn new(abi_path: Option<PathBuf>, module_path: Option<PathBuf>) -> Self {
-        let entry_abis = Self::load_abis_from_path(abi_path);
-        let module_bytes = Self::load_module_from_path(module_path);
+    pub fn new(
+        abi_path: Option<PathBuf>,
+        module_path: Option<PathBuf>,
+        script_path: Option<PathBuf>,
+        senders: Vec<AccountAddress>,
+        fork: Option<ForkConfig>,
+        corpus_dir: &Path,
+        solutions_dir: &Path,
+    ) -> Self {
+        let mut entry_abis = Vec::new();
+        let mut script_abis = Self::load_scripts_from_path(script_path);
+        for abi in Self::load_abis_from_path(abi_path) {
+            match abi {
+                EntryABI::EntryFunction(abi) => entry_abis.push(abi),
+                EntryABI::TransactionScript(abi) => script_abis.push(abi),
+            }
+        }
+        // Pin the version once so respawned clients fork the same state
+        let (aptos_state, fork) = match fork {
+            Some(fork) => {
+                let fetcher = Self::fork_fetcher(&fork);
+                let fork = fetcher.pinned_config();
+                info!(url = %fork.url, version = fetcher.version(), "forking state");
+                (AptosCustomState::new_forked(Arc::new(fetcher)), Some(fork))
+            }
+            None => (AptosCustomState::new_default(), None),
+        };
         let mut state = Self {
-            // TODO: replace me with actual aptos state
-            aptos_state: AptosCustomState::new_default(),
+            aptos_state,
             rand: StdRand::new(),
             executions: 0,
             start_time: Duration::from_secs(0),
             imported: 0,
-            corpus: InMemoryCorpus::new(),
-            solutions: InMemoryCorpus::new(),
+            corpus: CachedOnDiskCorpus::new(corpus_dir, CORPUS_CACHE_SIZE).expect("failed to create corpus directory"),
+            solutions: OnDiskCorpus::new(solutions_dir).expect("failed to create solutions directory"),
             metadata: SerdeAnyMap::new(),
             named_metadata: NamedSerdeAnyMap::new(),
             last_found_time: Duration::from_secs(0),
             last_report_time: None,
             corpus_id: None,
             stop_requested: false,
+            stop_flag: None,
             stage_stack: StageStack::default(),
+            fork,
+            module_path,
+            package_modules: Vec::new(),
+            state_snapshot: None,
+            framework_bundle: None,
+            gas_mode: None,
+            features: None,
+            chain_id: None,
+            seeded_tables: Vec::new(),
+            upgrade_path: None,
+            type_arg_candidates: Vec::new(),
+            senders,
         };
 
-        if let Some((module_id, code)) = module_bytes {
-            state.aptos_state.deploy_module_bytes(module_id, code);
-        }
+        let deployed = state.setup_aptos_state();
+        state.package_modules = deployed.iter().map(|module| module.self_id()).collect();
+        state.type_arg_candidates = Self::type_arg_candidates_for(deployed.iter());
+        let signer_counts = Self::signer_counts(deployed.iter());
 
-        for payload in Self::padding_abis(entry_abis) {
-            let input = AptosFuzzerInput::new(payload);
-            let _ = state.corpus.add(Testcase::new(input));
+        // Seeds are single calls; the sequence mutator grows them
+        let mut seeds = Self::padding_abis(entry_abis, &state.type_arg_candidates);
+        seeds.extend(Self::padding_script_abis(script_abis, &state.type_arg_candidates));
+        for call in seeds {
+            let signers = match call.payload() {
+                TransactionPayload::EntryFunction(entry) => signer_counts
+                    .get(&(entry.module().clone(), entry.function().to_string()))
+                    .copied()
+                    .unwrap_or(1),
+                _ => 1,
+            };
+            let seed = state.seed_input(call, signers);
+            let _ = state.corpus.add(Testcase::new(seed));
         }
 
         state
     }
 
+    /// Single-call input sent by the first sender, with `signers - 1`
+    /// secondary signers
+    fn seed_input(&self, mut call: AptosCall, signers: usize) -> AptosFuzzerInput {
+        call.set_sender(self.senders.first().copied());
+        // Seed secondary signers with distinct pool accounts where possible
+        let secondary: Vec<AccountAddress> = (1..signers)
+            .filter_map(|i| self.senders.get(i % self.senders.len().max(1)).copied())
+            .collect();
+        *call.secondary_signers_mut() = secondary;
+        AptosFuzzerInput::new(call)
+    }
+
+    /// Entry functions of the deployed package modules, found from their
+    /// bytecode so no ABI files are needed, highest score first. With
+    /// `include_public`, their public functions too, for an executor calling
+    /// them in a bare VM.
+    pub fn package_entry_functions(&self, include_public: bool) -> Vec<EntryTarget> {
+        let mut targets = Vec::new();
+        for (module, _) in Self::load_modules_from_path(self.module_path.clone()) {
+            if !self.package_modules.contains(&module.self_id()) {
+                continue;
+            }
+            for function_def in module.function_defs() {
+                if !function_def.is_entry && !(include_public && function_def.visibility == Visibility::Public) {
+                    continue;
+                }
+                let handle = module.function_handle_at(function_def.function);
+                let unsupported = module
+                    .signature_at(handle.parameters)
+                    .0
+                    .iter()
+                    .find(|token| !Self::is_signer(token) && Self::primitive_type_tag(token).is_none())
+                    .map(|token| format!("{token:?}"));
+                targets.push(EntryTarget {
+                    module: module.self_id(),
+                    function: module.identifier_at(handle.name).to_string(),
+                    unsupported,
+                    score: static_score(&module, function_def),
+                });
+            }
+        }
+        targets.sort_by(|a, b| b.score.cmp(&a.score));
+        targets
+    }
+
+    /// Seeds calling `target` with default arguments, one per type argument
+    /// candidate if it is generic. Empty if its parameters are unsupported.
+    pub fn entry_function_seeds(&self, target: &EntryTarget) -> Vec<AptosFuzzerInput> {
+        let Some((module, _)) = Self::load_modules_from_path(self.module_path.clone())
+            .into_iter()
+            .find(|(module, _)| module.self_id() == target.module)
+        else {
+            return Vec::new();
+        };
+        let Some(handle) = module
+            .function_defs()
+            .iter()
+            .map(|function_def| module.function_handle_at(function_def.function))
+            .find(|handle| module.identifier_at(handle.name).as_str() == target.function)
+        else {
+            return Vec::new();
+        };
+
+        let mut signers = 0;
+        let mut args = Vec::new();
+        for (i, token) in module.signature_at(handle.parameters).0.iter().enumerate() {
+            if Self::is_signer(token) {
+                signers += 1;
+                continue;
+            }
+            let Some(type_tag) = Self::primitive_type_tag(token) else {
+                return Vec::new();
+            };
+            args.push(ArgumentABI::new(format!("arg{i}"), type_tag));
+        }
+        let ty_args = (0..handle.type_parameters.len())
+            .map(|i| TypeArgumentABI::new(format!("T{i}")))
+            .collect();
+        let abi = EntryFunctionABI::new(
+            target.function.clone(),
+            target.module.clone(),
+            String::new(),
+            ty_args,
+            args,
+        );
+
+        Self::padding_abis(vec![abi], &self.type_arg_candidates)
+            .into_iter()
+            .map(|call| self.seed_input(call, signers))
+            .collect()
+    }
+
+    /// Rebuild the Aptos state after the fuzzer state was deserialized, e.g.
+    /// when a restarting event manager respawns a client
+    pub fn restore_aptos_state(&mut self) {
+        if let Some(fork) = &self.fork {
+            self.aptos_state = AptosCustomState::new_forked(Arc::new(Self::fork_fetcher(fork)));
+        }
+        self.setup_aptos_state();
+        if let Some(path) = self.framework_bundle.clone() {
+            if let Err(err) = self.apply_framework_bundle(&path) {
+                warn!(path = %path.display(), %err, "failed to deploy framework bundle");
+            }
+        }
+        if let Some(path) = &self.state_snapshot {
+            if let Err(err) = self.aptos_state.load_snapshot(path) {
+                warn!(path = %path.display(), err = %format!("{err:#}"), "failed to load state snapshot");
+            }
+        }
+        for entry in &self.seeded_tables {
+            self.aptos_state
+                .set_table_entry(entry.handle, &entry.key, entry.value.clone());
+        }
+        if let Some(mode) = self.gas_mode.clone() {
+            if let Err(err) = self.apply_gas_mode(&mode) {
+                warn!(%err, "failed to apply gas schedule");
+            }
+        }
+        if let Some(features) = self.features.clone() {
+            if let Err(err) = self.aptos_state.set_features(&features) {
+                warn!(err = %format!("{err:#}"), "failed to install feature flags");
+            }
+        }
+        if let Some(chain_id) = self.chain_id {
+            if let Err(err) = self.aptos_state.set_chain_id(ChainId::new(chain_id)) {
+                warn!(err = %format!("{err:#}"), "failed to install chain ID");
+            }
+        }
+        if let Some(path) = self.upgrade_path.clone() {
+            if let Err(err) = self.apply_upgrade(&path) {
+                warn!(path = %path.display(), %err, "failed to upgrade modules");
+            }
+        }
+    }
+
+    /// Write the table entries of a fixture into the Aptos state: a JSON
+    /// list of `{"handle", "key", "value"}` objects with hex-encoded BCS key
+    /// and value if the file ends in `.json`, else a BCS `Vec<TableEntry>`.
+    /// They are written again whenever the Aptos state is restored.
+    pub fn seed_tables(&mut self, path: &Path) -> Result<usize, libafl::Error> {
+        let bytes = fs::read(path)?;
+        let entries: Vec<TableEntry> = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_slice(&bytes).map_err(|e| libafl::Error::serialize(e.to_string()))?
+        } else {
+            bcs::from_bytes(&bytes).map_err(|e| libafl::Error::serialize(e.to_string()))?
+        };
+
+        for entry in &entries {
+            self.aptos_state
+                .set_table_entry(entry.handle, &entry.key, entry.value.clone());
+        }
+        let count = entries.len();
+        self.seeded_tables.extend(entries);
+        Ok(count)
+    }
+
+    /// Replace the gas schedule of the Aptos state, which otherwise is
+    /// absent or, when forked, the on-chain one. It is installed again
+    /// whenever the Aptos state is restored.
+    pub fn set_gas_mode(&mut self, mode: GasMode) -> Result<(), libafl::Error> {
+        self.apply_gas_mode(&mode)?;
+        self.gas_mode = Some(mode);
+        Ok(())
+    }
+
+    fn apply_gas_mode(&mut self, mode: &GasMode) -> Result<(), libafl::Error> {
+        match mode {
+            GasMode::Unmetered => self.aptos_state.clear_gas_schedule(),
+            GasMode::Schedule(schedule) => self
+                .aptos_state
+                .set_gas_schedule(schedule)
+                .map_err(|e| libafl::Error::illegal_argument(format!("failed to install gas schedule: {e:#}")))?,
+        }
+        Ok(())
+    }
+
+    /// Whether the on-chain configs may differ from the default environment,
+    /// so executors should be built with
+    /// [`AptosMoveExecutor::with_environment_of`](crate::AptosMoveExecutor::with_environment_of)
+    pub fn has_custom_environment(&self) -> bool {
+        self.fork.is_some() || self.gas_mode.is_some() || self.features.is_some() || self.chain_id.is_some()
+    }
+
+    /// Run executions with `chain_id`, e.g. for a network whose state
+    /// doesn't carry a usable one. It is installed again whenever the Aptos
+    /// state is restored.
+    pub fn set_chain_id(&mut self, chain_id: u8) -> Result<(), libafl::Error> {
+        self.aptos_state
+            .set_chain_id(ChainId::new(chain_id))
+            .map_err(|e| libafl::Error::illegal_argument(format!("failed to install chain ID: {e:#}")))?;
+        self.chain_id = Some(chain_id);
+        Ok(())
+    }
+
+    /// Replace the feature flags of the Aptos state, which otherwise are the
+    /// default ones or, when forked, the on-chain ones. They are installed
+    /// again whenever the Aptos state is restored.
+    pub fn set_features(&mut self, features: Features) -> Result<(), libafl::Error> {
+        self.aptos_state
+            .set_features(&features)
+            .map_err(|e| libafl::Error::illegal_argument(format!("failed to install feature flags: {e:#}")))?;
+        self.features = Some(features);
+        Ok(())
+    }
+
+    /// Deploy the framework of a release bundle (`.mrb`) over the one the
+    /// fuzzer is built with, e.g. the release currently on mainnet. Returns
+    /// the number of modules. It is deployed again whenever the Aptos state
+    /// is restored.
+    pub fn load_framework_bundle(&mut self, path: PathBuf) -> Result<usize, libafl::Error> {
+        let count = self.apply_framework_bundle(&path)?;
+        self.framework_bundle = Some(path);
+        Ok(count)
+    }
+
+    fn apply_framework_bundle(&mut self, path: &Path) -> Result<usize, libafl::Error> {
+        let bundle: ReleaseBundle = bcs::from_bytes(&fs::read(path)?)
+            .map_err(|e| libafl::Error::serialize(format!("invalid framework bundle {}: {e}", path.display())))?;
+        Ok(self.aptos_state.deploy_bundle(&bundle))
+    }
+
+    /// Load a state exported with [`AptosCustomState::export_snapshot`] on
+    /// top of the deployed modules and funded senders. It is loaded again
+    /// whenever the Aptos state is restored.
+    pub fn load_state_snapshot(&mut self, path: PathBuf) -> Result<(), libafl::Error> {
+        self.aptos_state.load_snapshot(&path).map_err(|e| {
+            libafl::Error::serialize(format!("failed to load state snapshot {}: {e:#}", path.display()))
+        })?;
+        self.state_snapshot = Some(path);
+        Ok(())
+    }
+
+    /// Upgrade the deployed package to the modules at `path`, keeping the
+    /// resources stored by the old version so the new code runs against
+    /// them. Modules that are new get published, unchanged ones are
+    /// skipped. Returns the number of upgraded modules. The upgrade is
+    /// applied again whenever the Aptos state is restored.
+    pub fn upgrade_modules(&mut self, path: PathBuf) -> Result<usize, libafl::Error> {
+        let upgraded = self.apply_upgrade(&path)?;
+        self.upgrade_path = Some(path);
+        Ok(upgraded)
+    }
+
+    /// Module, package directory or bundle the package was upgraded to
+    pub fn upgrade_path(&self) -> Option<&Path> {
+        self.upgrade_path.as_deref()
+    }
+
+    fn apply_upgrade(&mut self, path: &Path) -> Result<usize, libafl::Error> {
+        let modules = Self::load_modules_from_path(Some(path.to_path_buf()));
+        if modules.is_empty() {
+            return Err(libafl::Error::illegal_argument(format!(
+                "no modules to upgrade to in {}",
+                path.display()
+            )));
+        }
+
+        let mut upgraded = 0;
+        for (module, code) in modules {
+            let module_id = module.self_id();
+            let state_key = StateKey::module(module_id.address(), module_id.name());
+            match self.aptos_state.get_state_value(&state_key) {
+                Some(published) if published.bytes().as_ref() == code.as_slice() => continue,
+                Some(_) => {
+                    self.aptos_state
+                        .upgrade_module(code)
+                        .map_err(|e| libafl::Error::illegal_argument(format!("{e:#}")))?;
+                    info!(module = %module_id, "upgraded module");
+                    upgraded += 1;
+                }
+                None => {
+                    info!(module = %module_id, "deploying module");
+                    self.aptos_state.deploy_module_bytes(module_id, code);
+                }
+            }
+        }
+        Ok(upgraded)
+    }
+
+    fn fork_fetcher(fork: &ForkConfig) -> RestStateFetcher {
+        RestStateFetcher::new(fork).unwrap_or_else(|e| panic!("failed to fork {}: {e:#}", fork.url))
+    }
+
+    /// Fund the sender accounts and deploy the target modules, returning the
+    /// modules that were deployed
+    fn setup_aptos_state(&mut self) -> Vec<CompiledModule> {
+        for sender in &self.senders {
+            self.aptos_state.create_funded_account(*sender, DEFAULT_ACCOUNT_BALANCE);
+        }
+
+        let mut deployed = Vec::new();
+        for (module, code) in Self::load_modules_from_path(self.module_path.clone()) {
+            let module_id = module.self_id();
+            let state_key = StateKey::module(module_id.address(), module_id.name());
+            // Framework dependencies bundled with a package are already deployed
+            if self.aptos_state.get_state_value(&state_key).is_some() {
+                continue;
+            }
+            info!(module = %module_id, "deploying module");
+            self.aptos_state.deploy_module_bytes(module_id, code);
+            deployed.push(module);
+        }
+        deployed
+    }
+
     /// Drain current corpus entries into a vector of inputs and clear the
     /// corpus. Useful to re-insert seeds via fuzzer.add_input so
     /// events/feedback are fired.
@@ -108,6 +607,110 @@ impl AptosFuzzerState {
         inputs
     }
 
+    /// Write the coverage history and the already reported abort and
+    /// arithmetic sites to `path`
+    pub fn save_history(&self, path: &Path) -> Result<(), libafl::Error> {
+        let history = FeedbackHistory {
+            edges: self
+                .named_metadata_map()
+                .get::<MapFeedbackMetadata<u8>>(EDGES_OBSERVER_NAME)
+                .cloned(),
+            abort_sites: self
+                .metadata_map()
+                .get::<AbortSitesMetadata>()
+                .cloned()
+                .unwrap_or_default(),
+            arithmetic_sites: self
+                .metadata_map()
+                .get::<ArithmeticSitesMetadata>()
+                .cloned()
+                .unwrap_or_default(),
+        };
+        let bytes = bcs::to_bytes(&history).map_err(|e| libafl::Error::serialize(e.to_string()))?;
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Merge every history file (`.history*`) saved in `dir` by earlier
+    /// clients into this state, so resumed campaigns don't report old
+    /// coverage and findings as new. Call after the feedbacks initialized the
+    /// state.
+    pub fn load_history(&mut self, dir: &Path) {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let is_history = path
+                .file_name()
+                .map(|name| name.to_string_lossy().starts_with(".history"))
+                .unwrap_or(false);
+            if !is_history {
+                continue;
+            }
+            let history = match fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| bcs::from_bytes::<FeedbackHistory>(&bytes).map_err(|e| e.to_string()))
+            {
+                Ok(history) => history,
+                Err(err) => {
+                    warn!(path = %path.display(), %err, "skipping history file");
+                    continue;
+                }
+            };
+            self.merge_history(history);
+        }
+    }
+
+    fn merge_history(&mut self, history: FeedbackHistory) {
+        if let Some(edges) = history.edges {
+            let map = self
+                .named_metadata_map_mut()
+                .get_mut::<MapFeedbackMetadata<u8>>(EDGES_OBSERVER_NAME);
+            if let Some(map) = map {
+                if map.history_map.len() < edges.history_map.len() {
+                    map.history_map.resize(edges.history_map.len(), 0);
+                }
+                for (current, saved) in map.history_map.iter_mut().zip(edges.history_map) {
+                    *current = (*current).max(saved);
+                }
+                map.num_covered_map_indexes = map.history_map.iter().filter(|hits| **hits > 0).count();
+            }
+        }
+        self.metadata_or_insert_with(AbortSitesMetadata::default)
+            .sites
+            .extend(history.abort_sites.sites);
+        self.metadata_or_insert_with(ArithmeticSitesMetadata::default)
+            .sites
+            .extend(history.arithmetic_sites.sites);
+    }
+
+    /// Inputs saved in a corpus directory by a previous run
+    pub fn load_inputs_from_dir(dir: &Path) -> Vec<AptosFuzzerInput> {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut inputs = Vec::new();
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            // Skip libafl metadata and lock files
+            let hidden = path
+                .file_name()
+                .map(|name| name.to_string_lossy().starts_with('.'))
+                .unwrap_or(true);
+            if hidden || !path.is_file() {
+                continue;
+            }
+            match AptosFuzzerInput::from_file(&path) {
+                Ok(input) => inputs.push(input),
+                Err(err) => warn!(path = %path.display(), %err, "skipping corpus file"),
+            }
+        }
+        inputs
+    }
+
     pub fn aptos_state(&self) -> &AptosCustomState {
         &self.aptos_state
     }
@@ -115,17 +718,25 @@ impl AptosFuzzerState {
     pub fn aptos_state_mut(&mut self) -> &mut AptosCustomState {
         &mut self.aptos_state
     }
+
+    pub fn type_arg_candidates(&self) -> &[TypeTag] {
+        &self.type_arg_candidates
+    }
+
+    pub fn senders(&self) -> &[AccountAddress] {
+        &self.senders
+    }
 }
 
 // initial inputs
 impl HasCorpus<AptosFuzzerInput> for AptosFuzzerState {
-    type Corpus = InMemoryCorpus<AptosFuzzerInput>;
+    type Corpus = CachedOnDiskCorpus<AptosFuzzerInput>;
 
-    fn corpus(&self) -> &InMemoryCorpus<AptosFuzzerInput> {
+    fn corpus(&self) -> &CachedOnDiskCorpus<AptosFuzzerInput> {
         &self.corpus
     }
 
-    fn corpus_mut(&mut self) -> &mut InMemoryCorpus<AptosFuzzerInput> {
+    fn corpus_mut(&mut self) -> &mut CachedOnDiskCorpus<AptosFuzzerInput> {
         &mut self.corpus
     }
 }
@@ -158,9 +769,32 @@ impl HasCurrentCorpusId for AptosFuzzerState {
     }
 }
 
+impl AptosFuzzerState {
+    /// Make every state in the process without a stop flag of its own
+    /// request a stop, so fuzzing loops end after the current input. Safe
+    /// to call from a signal handler.
+    pub fn interrupt() {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+    }
+
+    pub fn interrupted() -> bool {
+        INTERRUPTED.load(Ordering::Relaxed)
+    }
+
+    /// Stop when `flag` is set instead of on [`Self::interrupt`], so
+    /// campaigns sharing the process stop one at a time
+    pub fn set_stop_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.stop_flag = Some(flag);
+    }
+}
+
 impl Stoppable for AptosFuzzerState {
     fn stop_requested(&self) -> bool {
-        self.stop_requested
+        self.stop_requested ||
+            match &self.stop_flag {
+                Some(flag) => flag.load(Ordering::Relaxed),
+                None => Self::interrupted(),
+            }
     }
 
     fn request_stop(&mut self) {
@@ -214,12 +848,12 @@ impl HasLastFoundTime for AptosFuzzerState {
 
 // inputs that can trigger a bug
 impl HasSolutions<AptosFuzzerInput> for AptosFuzzerState {
-    type Solutions = InMemoryCorpus<AptosFuzzerInput>;
-    fn solutions(&self) -> &InMemoryCorpus<AptosFuzzerInput> {
+    type Solutions = OnDiskCorpus<AptosFuzzerInput>;
+    fn solutions(&self) -> &OnDiskCorpus<AptosFuzzerInput> {
         &self.solutions
     }
 
-    fn solutions_mut(&mut self) -> &mut InMemoryCorpus<AptosFuzzerInput> {
+    fn solutions_mut(&mut self) -> &mut OnDiskCorpus<AptosFuzzerInput> {
         &mut self.solutions
     }
 }
@@ -279,7 +913,7 @@ impl HasStartTime for AptosFuzzerState {
 }
 
 impl AptosFuzzerState {
-    fn load_abis_from_path(path: Option<PathBuf>) -> Vec<EntryFunctionABI> {
+    fn load_abis_from_path(path: Option<PathBuf>) -> Vec<EntryABI> {
         let Some(path) = path else {
             return Vec::new();
         };
@@ -290,7 +924,7 @@ impl AptosFuzzerState {
         abis
     }
 
-    fn collect_abis(path: &Path, paths: &mut Vec<PathBuf>, abis: &mut Vec<EntryFunctionABI>) {
+    fn collect_abis(path: &Path, paths: &mut Vec<PathBuf>, abis: &mut Vec<EntryABI>) {
         if path.is_dir() {
             let read_dir = match fs::read_dir(path) {
                 Ok(rd) => rd,
@@ -299,7 +933,7 @@ impl AptosFuzzerState {
             for entry in read_dir {
                 match entry {
                     Ok(dir_entry) => Self::collect_abis(&dir_entry.path(), paths, abis),
-                    Err(err) => eprintln!("[aptos-fuzzer] failed to read entry in {}: {err}", path.display()),
+                    Err(err) => warn!(path = %path.display(), %err, "failed to read directory entry"),
                 }
             }
             return;
@@ -312,36 +946,91 @@ impl AptosFuzzerState {
         let bytes = match fs::read(path) {
             Ok(bytes) => bytes,
             Err(err) => {
-                eprintln!("[aptos-fuzzer] failed to read ABI file {}: {err}", path.display());
+                warn!(path = %path.display(), %err, "failed to read ABI file");
                 return;
             }
         };
         // Try to decode as EntryABI first (new format from aptos move compile)
         match bcs::from_bytes::<EntryABI>(&bytes) {
             Ok(entry_abi) => {
-                if let EntryABI::EntryFunction(abi) = entry_abi {
-                    paths.push(path.to_path_buf());
-                    abis.push(abi);
-                }
+                // Script ABIs carry the script bytecode and seed script payloads
+                paths.push(path.to_path_buf());
+                abis.push(entry_abi);
             }
             Err(_) => {
                 // Fallback: try to decode as EntryFunctionABI directly (legacy format)
                 if let Ok(abi) = bcs::from_bytes::<EntryFunctionABI>(&bytes) {
                     paths.push(path.to_path_buf());
-                    abis.push(abi);
+                    abis.push(EntryABI::EntryFunction(abi));
                 }
             }
         }
     }
 
-    fn padding_abis(abis: Vec<EntryFunctionABI>) -> Vec<TransactionPayload> {
-        let mut payloads = Vec::new();
+    /// Candidate type arguments: AptosCoin, a few primitives and every
+    /// non-generic struct declared by the fuzzed modules
+    fn type_arg_candidates_for<'a>(modules: impl Iterator<Item = &'a CompiledModule>) -> Vec<TypeTag> {
+        let mut candidates = vec![
+            TypeTag::Struct(Box::new(StructTag {
+                address: AccountAddress::ONE,
+                module: Identifier::new("aptos_coin").expect("valid identifier"),
+                name: Identifier::new("AptosCoin").expect("valid identifier"),
+                type_args: Vec::new(),
+            })),
+            TypeTag::U8,
+            TypeTag::U64,
+            TypeTag::U128,
+            TypeTag::Bool,
+            TypeTag::Address,
+        ];
 
-        for abi in abis {
-            if !abi.ty_args().is_empty() {
-                continue;
+        for module in modules {
+            for struct_def in module.struct_defs() {
+                let handle = module.struct_handle_at(struct_def.struct_handle);
+                if !handle.type_parameters.is_empty() {
+                    continue;
+                }
+                candidates.push(TypeTag::Struct(Box::new(StructTag {
+                    address: *module.address(),
+                    module: module.name().to_owned(),
+                    name: module.identifier_at(handle.name).to_owned(),
+                    type_args: Vec::new(),
+                })));
+            }
+        }
+
+        candidates
+    }
+
+    /// Number of signer parameters of every function in the given modules
+    fn signer_counts<'a>(modules: impl Iterator<Item = &'a CompiledModule>) -> BTreeMap<(ModuleId, String), usize> {
+        let mut counts = BTreeMap::new();
+
+        for module in modules {
+            for function_def in module.function_defs() {
+                let handle = module.function_handle_at(function_def.function);
+                let signers = module
+                    .signature_at(handle.parameters)
+                    .0
+                    .iter()
+                    .filter(|token| match token {
+                        SignatureToken::Signer => true,
+                        SignatureToken::Reference(inner) => matches!(inner.as_ref(), SignatureToken::Signer),
+                        _ => false,
+                    })
+                    .count();
+                let name = module.identifier_at(handle.name).to_string();
+                counts.insert((module.self_id(), name), signers);
             }
+        }
+
+        counts
+    }
+
+    fn padding_abis(abis: Vec<EntryFunctionABI>, type_arg_candidates: &[TypeTag]) -> Vec<AptosCall> {
+        let mut inputs = Vec::new();
 
+        for abi in abis {
             let identifier = match Identifier::new(abi.name()) {
                 Ok(id) => id,
                 Err(_) => continue,
@@ -355,11 +1044,11 @@ impl AptosFuzzerState {
                     Some(bytes) => arg_bytes.push(bytes),
                     None => {
                         unsupported = true;
-                        eprintln!(
-                            "[aptos-fuzzer] skipping {}::{}: unsupported argument type {:?}",
-                            abi.module_name(),
-                            abi.name(),
-                            arg.type_tag()
+                        warn!(
+                            module = %abi.module_name(),
+                            function = abi.name(),
+                            arg_type = ?arg.type_tag(),
+                            "skipping function with unsupported argument type"
                         );
                         break;
                     }
@@ -379,11 +1068,83 @@ impl AptosFuzzerState {
                 }
             }
 
-            let entry = AptosEntryFunction::new(abi.module_name().clone(), identifier, Vec::new(), arg_bytes);
-            payloads.push(TransactionPayload::EntryFunction(entry));
+            let arg_types: Vec<TypeTag> = abi.args().iter().map(|arg| arg.type_tag().clone()).collect();
+
+            // Generic functions get one seed per candidate, used for every type
+            // parameter; the type argument mutator explores mixed instantiations
+            let instantiations = if abi.ty_args().is_empty() {
+                vec![Vec::new()]
+            } else {
+                type_arg_candidates
+                    .iter()
+                    .map(|candidate| vec![candidate.clone(); abi.ty_args().len()])
+                    .collect()
+            };
+
+            for ty_args in instantiations {
+                let entry = AptosEntryFunction::new(
+                    abi.module_name().clone(),
+                    identifier.clone(),
+                    ty_args,
+                    arg_bytes.clone(),
+                );
+                inputs.push(AptosCall::new(TransactionPayload::EntryFunction(entry)).with_arg_types(arg_types.clone()));
+            }
+        }
+
+        inputs
+    }
+
+    /// Script seeds with default arguments; generic scripts are instantiated
+    /// like generic entry functions
+    fn padding_script_abis(abis: Vec<TransactionScriptABI>, type_arg_candidates: &[TypeTag]) -> Vec<AptosCall> {
+        let mut inputs = Vec::new();
+
+        for abi in abis {
+            let args: Option<Vec<TransactionArgument>> = abi
+                .args()
+                .iter()
+                .map(|arg| Self::default_script_arg(arg.type_tag()))
+                .collect();
+            let Some(args) = args else {
+                warn!(script = abi.name(), "skipping script with unsupported argument type");
+                continue;
+            };
+            let arg_types: Vec<TypeTag> = abi.args().iter().map(|arg| arg.type_tag().clone()).collect();
+
+            let instantiations = if abi.ty_args().is_empty() {
+                vec![Vec::new()]
+            } else {
+                type_arg_candidates
+                    .iter()
+                    .map(|candidate| vec![candidate.clone(); abi.ty_args().len()])
+                    .collect()
+            };
+
+            for ty_args in instantiations {
+                let script = Script::new(abi.code().to_vec(), ty_args, args.clone());
+                inputs.push(AptosCall::new(TransactionPayload::Script(script)).with_arg_types(arg_types.clone()));
+            }
         }
 
-        payloads
+        inputs
+    }
+
+    fn default_script_arg(type_tag: &TypeTag) -> Option<TransactionArgument> {
+        Some(match type_tag {
+            TypeTag::Bool => TransactionArgument::Bool(false),
+            TypeTag::U8 => TransactionArgument::U8(0),
+            TypeTag::U16 => TransactionArgument::U16(0),
+            TypeTag::U32 => TransactionArgument::U32(0),
+            TypeTag::U64 => TransactionArgument::U64(0),
+            TypeTag::U128 => TransactionArgument::U128(0),
+            TypeTag::U256 => TransactionArgument::U256(U256::from(0u8)),
+            TypeTag::Address => TransactionArgument::Address(AccountAddress::ZERO),
+            TypeTag::Vector(inner) if matches!(inner.as_ref(), TypeTag::U8) => {
+                TransactionArgument::U8Vector(Vec::new())
+            }
+            other => TransactionArgument::Serialized(Self::default_arg_bytes(other)?),
+        })
     }
 
     fn default_arg_bytes(type_tag: &TypeTag) -> Option<Vec<u8>> {
@@ -411,20 +1172,207 @@ impl AptosFuzzerState {
         }
     }
 
-    fn load_module_from_path(path: Option<PathBuf>) -> Option<(ModuleId, Vec<u8>)> {
-        let path = path?;
-        let bytes = match fs::read(&path) {
+    /// Load the modules to publish from a single compiled module (`.mv`), a
+    /// package build directory or a release bundle (`.mrb`), ordered so that
+    /// every module comes after the package modules it depends on
+    fn load_modules_from_path(path: Option<PathBuf>) -> Vec<(CompiledModule, Vec<u8>)> {
+        let Some(path) = path else {
+            return Vec::new();
+        };
+
+        let mut modules = Vec::new();
+        if path.is_dir() {
+            Self::collect_modules(&path, &mut modules);
+        } else if path.extension().map(|ext| ext == "mrb").unwrap_or(false) {
+            match Self::load_bundle(&path) {
+                Ok(bundle_modules) => modules = bundle_modules,
+                Err(err) => warn!(path = %path.display(), %err, "failed to read bundle"),
+            }
+        } else if let Some(module) = Self::load_module_file(&path) {
+            modules.push(module);
+        }
+
+        Self::topological_order(modules)
+    }
+
+    fn load_bundle(path: &Path) -> anyhow::Result<Vec<(CompiledModule, Vec<u8>)>> {
+        let bundle: ReleaseBundle = bcs::from_bytes(&fs::read(path)?)?;
+        Ok(bundle
+            .code_and_compiled_modules()
+            .into_iter()
+            .map(|(code, module)| (module.clone(), code.to_vec()))
+            .collect())
+    }
+
+    fn collect_modules(path: &Path, modules: &mut Vec<(CompiledModule, Vec<u8>)>) {
+        if path.is_dir() {
+            // Compiled scripts of a package build are loaded as script seeds instead
+            if path.file_name().map(|name| name == "bytecode_scripts").unwrap_or(false) {
+                return;
+            }
+            let read_dir = match fs::read_dir(path) {
+                Ok(rd) => rd,
+                Err(_) => return,
+            };
+            for entry in read_dir.flatten() {
+                Self::collect_modules(&entry.path(), modules);
+            }
+            return;
+        }
+
+        if path.extension().map(|ext| ext == "mv").unwrap_or(false) {
+            if let Some(module) = Self::load_module_file(path) {
+                modules.push(module);
+            }
+        }
+    }
+
+    fn load_module_file(path: &Path) -> Option<(CompiledModule, Vec<u8>)> {
+        let bytes = match fs::read(path) {
             Ok(bytes) => bytes,
-            Err(_) => return None,
+            Err(err) => {
+                warn!(path = %path.display(), %err, "failed to read module");
+                return None;
+            }
         };
 
-        let module = match CompiledModule::deserialize(bytes.as_slice()) {
-            Ok(module) => module,
-            Err(_) => return None,
+        match CompiledModule::deserialize(bytes.as_slice()) {
+            Ok(module) => Some((module, bytes)),
+            Err(err) => {
+                warn!(path = %path.display(), ?err, "failed to deserialize module");
+                None
+            }
+        }
+    }
+
+    /// Load compiled scripts (`.mv`) from a file or directory and describe
+    /// each with a script ABI derived from its signature
+    fn load_scripts_from_path(path: Option<PathBuf>) -> Vec<TransactionScriptABI> {
+        let Some(path) = path else {
+            return Vec::new();
+        };
+
+        let mut abis = Vec::new();
+        Self::collect_scripts(&path, &mut abis);
+        abis
+    }
+
+    fn collect_scripts(path: &Path, abis: &mut Vec<TransactionScriptABI>) {
+        if path.is_dir() {
+            let read_dir = match fs::read_dir(path) {
+                Ok(rd) => rd,
+                Err(_) => return,
+            };
+            for entry in read_dir.flatten() {
+                Self::collect_scripts(&entry.path(), abis);
+            }
+            return;
+        }
+
+        if path.extension().map(|ext| ext != "mv").unwrap_or(true) {
+            return;
+        }
+
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!(path = %path.display(), %err, "failed to read script");
+                return;
+            }
+        };
+        let script = match CompiledScript::deserialize(bytes.as_slice()) {
+            Ok(script) => script,
+            Err(err) => {
+                warn!(path = %path.display(), ?err, "failed to deserialize script");
+                return;
+            }
         };
 
-        let module_id = module.self_id();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut args = Vec::new();
+        // Signers are supplied by the transaction, not as arguments
+        for (i, token) in script.signature_at(script.parameters).0.iter().enumerate() {
+            if matches!(token, SignatureToken::Signer) ||
+                matches!(token, SignatureToken::Reference(inner) if matches!(inner.as_ref(), SignatureToken::Signer))
+            {
+                continue;
+            }
+            let Some(type_tag) = Self::primitive_type_tag(token) else {
+                warn!(script = %name, parameter = ?token, "skipping script with unsupported parameter");
+                return;
+            };
+            args.push(ArgumentABI::new(format!("arg{i}"), type_tag));
+        }
+        let ty_args = (0..script.type_parameters.len())
+            .map(|i| TypeArgumentABI::new(format!("T{i}")))
+            .collect();
 
-        Some((module_id, bytes))
+        info!(path = %path.display(), "loaded script");
+        abis.push(TransactionScriptABI::new(name, String::new(), bytes, ty_args, args));
+    }
+
+    fn is_signer(token: &SignatureToken) -> bool {
+        match token {
+            SignatureToken::Signer => true,
+            SignatureToken::Reference(inner) => matches!(inner.as_ref(), SignatureToken::Signer),
+            _ => false,
+        }
+    }
+
+    fn primitive_type_tag(token: &SignatureToken) -> Option<TypeTag> {
+        Some(match token {
+            SignatureToken::Bool => TypeTag::Bool,
+            SignatureToken::U8 => TypeTag::U8,
+            SignatureToken::U16 => TypeTag::U16,
+            SignatureToken::U32 => TypeTag::U32,
+            SignatureToken::U64 => TypeTag::U64,
+            SignatureToken::U128 => TypeTag::U128,
+            SignatureToken::U256 => TypeTag::U256,
+            SignatureToken::Address => TypeTag::Address,
+            SignatureToken::Vector(inner) => TypeTag::Vector(Box::new(Self::primitive_type_tag(inner)?)),
+            _ => return None,
+        })
+    }
+
+    /// Order modules so dependencies within the set are deployed first.
+    /// Dependencies outside the set (e.g. the framework) are ignored.
+    fn topological_order(modules: Vec<(CompiledModule, Vec<u8>)>) -> Vec<(CompiledModule, Vec<u8>)> {
+        let mut by_id: BTreeMap<ModuleId, (CompiledModule, Vec<u8>)> = modules
+            .into_iter()
+            .map(|(module, code)| (module.self_id(), (module, code)))
+            .collect();
+        let ids: Vec<ModuleId> = by_id.keys().cloned().collect();
+
+        let mut visited = BTreeSet::new();
+        let mut order = Vec::with_capacity(ids.len());
+        for id in &ids {
+            Self::visit_module(id, &by_id, &mut visited, &mut order);
+        }
+
+        order.into_iter().filter_map(|id| by_id.remove(&id)).collect()
+    }
+
+    fn visit_module(
+        id: &ModuleId,
+        modules: &BTreeMap<ModuleId, (CompiledModule, Vec<u8>)>,
+        visited: &mut BTreeSet<ModuleId>,
+        order: &mut Vec<ModuleId>,
+    ) {
+        // Move forbids cyclic dependencies, so marking on entry is enough
+        if !visited.insert(id.clone()) {
+            return;
+        }
+        let Some((module, _)) = modules.get(id) else {
+            return;
+        };
+        for dep in module.immediate_dependencies() {
+            if modules.contains_key(&dep) {
+                Self::visit_module(&dep, modules, visited, order);
+            }
+        }
+        order.push(id.clone());
     }
 }
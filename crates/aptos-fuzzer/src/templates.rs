@@ -0,0 +1,119 @@
+use aptos_move_core_types::language_storage::TypeTag;
+use aptos_types::transaction::TransactionPayload;
+
+use crate::input::{AptosCall, AptosFuzzerInput};
+
+/// Instances built of one template at most, so packages with many matching
+/// functions don't flood the corpus
+const MAX_INSTANCES_PER_TEMPLATE: usize = 16;
+
+/// Part a call plays in a [`SequenceTemplate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Borrow,
+    Repay,
+    Deposit,
+    Withdraw,
+    /// Trade moving a pool's price
+    Swap,
+    /// Any other entry function, run while the others set the stage
+    Target,
+}
+
+impl Role {
+    /// Words in a function name suggesting the role
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Borrow => &["borrow", "flash", "flashloan", "loan"],
+            Self::Repay => &["repay", "payback"],
+            Self::Deposit => &["deposit", "supply", "stake", "provide", "add"],
+            Self::Withdraw => &["withdraw", "redeem", "unstake", "remove"],
+            Self::Swap => &["swap", "trade", "exchange", "buy", "sell"],
+            Self::Target => &[],
+        }
+    }
+
+    /// Whether `call` looks like it plays the role: its function name has
+    /// one of the role's words, and it takes an amount
+    fn matches(self, call: &AptosCall) -> bool {
+        let TransactionPayload::EntryFunction(entry) = call.payload() else {
+            return false;
+        };
+        let name = entry.function().as_str().to_ascii_lowercase();
+        let named = name.split('_').any(|word| self.keywords().contains(&word));
+        let takes_amount = call.arg_types().iter().any(|ty| {
+            matches!(
+                ty,
+                TypeTag::U8 | TypeTag::U16 | TypeTag::U32 | TypeTag::U64 | TypeTag::U128 | TypeTag::U256
+            )
+        });
+        named && takes_amount
+    }
+}
+
+/// Sequence of calls with an economic meaning, e.g. a flash loan, that
+/// gives the sequence mutator a starting point a random sequence rarely hits
+#[derive(Debug, Clone, Copy)]
+pub struct SequenceTemplate {
+    pub name: &'static str,
+    pub roles: &'static [Role],
+}
+
+pub const TEMPLATES: &[SequenceTemplate] = &[
+    SequenceTemplate {
+        name: "flash loan",
+        roles: &[Role::Borrow, Role::Target, Role::Repay],
+    },
+    SequenceTemplate {
+        name: "price manipulation",
+        roles: &[Role::Deposit, Role::Swap, Role::Withdraw],
+    },
+    SequenceTemplate {
+        name: "sandwich",
+        roles: &[Role::Swap, Role::Target, Role::Swap],
+    },
+];
+
+/// Inputs instantiating [`TEMPLATES`] with the single calls of `seeds`,
+/// each role filled by a call whose name and signature suggest it. A
+/// target is any call playing none of the template's other roles.
+pub fn template_seeds(seeds: &[AptosFuzzerInput]) -> Vec<AptosFuzzerInput> {
+    let calls: Vec<&AptosCall> = seeds
+        .iter()
+        .filter(|seed| seed.calls().len() == 1)
+        .map(|seed| &seed.calls()[0])
+        .collect();
+
+    let mut inputs = Vec::new();
+    for template in TEMPLATES {
+        let candidates: Vec<Vec<&AptosCall>> = template
+            .roles
+            .iter()
+            .map(|&role| match role {
+                Role::Target => calls
+                    .iter()
+                    .filter(|call| !template.roles.iter().any(|&other| other.matches(call)))
+                    .copied()
+                    .collect(),
+                role => calls.iter().filter(|call| role.matches(call)).copied().collect(),
+            })
+            .collect();
+        if candidates.iter().any(Vec::is_empty) {
+            continue;
+        }
+
+        // Walk the candidates of all roles together, so each appears in
+        // some instance while their count stays linear
+        let instances = candidates.iter().map(Vec::len).max().unwrap_or(0);
+        for i in 0..instances.min(MAX_INSTANCES_PER_TEMPLATE) {
+            let mut calls = candidates.iter().map(|role| role[i % role.len()].clone());
+            let Some(first) = calls.next() else {
+                continue;
+            };
+            let mut input = AptosFuzzerInput::new(first);
+            input.calls_mut().extend(calls);
+            inputs.push(input);
+        }
+    }
+    inputs
+}
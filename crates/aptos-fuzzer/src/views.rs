@@ -0,0 +1,232 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::identifier::Identifier;
+use aptos_move_core_types::language_storage::{ModuleId, TypeTag};
+use aptos_types::transaction::EntryFunction;
+use serde::{Deserialize, Serialize};
+
+use crate::executor::aptos_custom_state::AptosCustomState;
+use crate::executor::bare_vm;
+use crate::layout::{self, LayoutCache};
+
+/// What a check asserts about the value a view function returns, compared
+/// before and after every call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "assert", rename_all = "snake_case")]
+pub enum ViewPredicate {
+    /// No call changes the value, e.g. a total supply that transfers only
+    /// move around
+    Conserved,
+    /// No call lowers the value
+    NonDecreasing,
+    /// No call raises the value
+    NonIncreasing,
+    /// The value stays within `min..=max` after every call
+    Bounds { min: Option<u128>, max: Option<u128> },
+}
+
+/// A view function called after every call, and what its result must satisfy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewCheck {
+    pub name: String,
+    /// Function to call, e.g. `0xcafe::pool::total_supply`
+    pub function: String,
+    #[serde(default)]
+    pub type_args: Vec<String>,
+    /// Hex-encoded BCS arguments
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Index of the checked return value
+    #[serde(default)]
+    pub output: usize,
+    /// Numeric field checked within a struct return value, nested fields
+    /// joined with dots; empty for a number
+    #[serde(default)]
+    pub field: String,
+    #[serde(flatten)]
+    pub predicate: ViewPredicate,
+}
+
+/// The call a check makes, and the type of the value it checks once the
+/// module is found
+#[derive(Debug, Clone)]
+struct ViewCall {
+    entry: EntryFunction,
+    output_type: Option<TypeTag>,
+}
+
+impl ViewCall {
+    fn resolve(check: &ViewCheck) -> anyhow::Result<Self> {
+        let mut parts = check.function.split("::");
+        let (Some(address), Some(module), Some(function), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            bail!("Invalid function {} in view check {}", check.function, check.name);
+        };
+        let module = ModuleId::new(
+            AccountAddress::from_str(address)
+                .with_context(|| format!("Invalid address in view check {}", check.name))?,
+            Identifier::new(module).with_context(|| format!("Invalid module in view check {}", check.name))?,
+        );
+        let function =
+            Identifier::new(function).with_context(|| format!("Invalid function in view check {}", check.name))?;
+        let type_args = check
+            .type_args
+            .iter()
+            .map(|ty| TypeTag::from_str(ty))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Invalid type argument in view check {}", check.name))?;
+        let args = check
+            .args
+            .iter()
+            .map(|arg| hex::decode(arg.trim_start_matches("0x")))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Invalid argument in view check {}", check.name))?;
+
+        Ok(Self {
+            entry: EntryFunction::new(module, function, type_args, args),
+            output_type: None,
+        })
+    }
+
+    /// The checked value in `state`; `None` if the call aborts or the value
+    /// doesn't decode
+    fn value(&mut self, check: &ViewCheck, layouts: &mut LayoutCache, state: &AptosCustomState) -> Option<u128> {
+        if self.output_type.is_none() {
+            self.output_type =
+                layout::return_types(state, self.entry.module(), self.entry.function(), self.entry.ty_args())?
+                    .into_iter()
+                    .nth(check.output);
+        }
+        let returned = bare_vm::execute_function(state, &self.entry, AccountAddress::ZERO).ok()?;
+        layouts
+            .numeric_fields(state, self.output_type.as_ref()?, returned.get(check.output)?)?
+            .get(&check.field)
+            .copied()
+    }
+}
+
+/// A view check a call broke
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ViewViolation {
+    pub check: String,
+    /// Index of the call within the input
+    pub call: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ViewViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} by call {}", self.check, self.reason, self.call)
+    }
+}
+
+#[derive(Deserialize)]
+struct ViewChecksFile {
+    #[serde(default)]
+    checks: Vec<ViewCheck>,
+}
+
+/// View functions checked around every call, loaded from a TOML or JSON
+/// file. They run in a bare MoveVM session, so views reading tables aren't
+/// supported.
+///
+/// ```toml
+/// [[checks]]
+/// name = "supply_conserved"
+/// function = "0xcafe::token::total_supply"
+/// assert = "conserved"
+///
+/// [[checks]]
+/// name = "k_never_drops"
+/// function = "0xcafe::pool::reserves_product"
+/// type_args = ["0x1::aptos_coin::AptosCoin"]
+/// assert = "non_decreasing"
+///
+/// [[checks]]
+/// name = "fee_capped"
+/// function = "0xcafe::pool::config"
+/// field = "fee_bps"
+/// assert = "bounds"
+/// max = 1000
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ViewChecks {
+    checks: Vec<(ViewCheck, ViewCall)>,
+}
+
+impl ViewChecks {
+    pub fn new(checks: Vec<ViewCheck>) -> anyhow::Result<Self> {
+        let checks = checks
+            .into_iter()
+            .map(|check| ViewCall::resolve(&check).map(|call| (check, call)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { checks })
+    }
+
+    /// Load checks from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read view checks {}", path.display()))?;
+
+        let file: ViewChecksFile = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                toml::from_str(&content).with_context(|| format!("Failed to parse view checks {}", path.display()))?
+            }
+            Some("json") => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse view checks {}", path.display()))?,
+            _ => bail!("Unsupported view checks format: {}", path.display()),
+        };
+        Self::new(file.checks)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// The value each check looks at in `state`, in order
+    pub fn values(&mut self, layouts: &mut LayoutCache, state: &AptosCustomState) -> Vec<Option<u128>> {
+        self.checks
+            .iter_mut()
+            .map(|(check, call)| call.value(check, layouts, state))
+            .collect()
+    }
+
+    /// Checks call `call` broke, taking the values from `before` to `after`
+    pub fn violations(&self, call: usize, before: &[Option<u128>], after: &[Option<u128>]) -> Vec<ViewViolation> {
+        self.checks
+            .iter()
+            .zip(before.iter().zip(after))
+            .filter_map(|((check, _), (before, after))| {
+                let after = (*after)?;
+                let reason = match &check.predicate {
+                    ViewPredicate::Conserved => {
+                        let before = (*before)?;
+                        (after != before).then(|| format!("changed from {before} to {after}"))?
+                    }
+                    ViewPredicate::NonDecreasing => {
+                        let before = (*before)?;
+                        (after < before).then(|| format!("decreased from {before} to {after}"))?
+                    }
+                    ViewPredicate::NonIncreasing => {
+                        let before = (*before)?;
+                        (after > before).then(|| format!("increased from {before} to {after}"))?
+                    }
+                    ViewPredicate::Bounds { min, max } => (min.is_some_and(|min| after < min) ||
+                        max.is_some_and(|max| after > max))
+                    .then(|| format!("{after} out of bounds"))?,
+                };
+                Some(ViewViolation {
+                    check: check.name.clone(),
+                    call,
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
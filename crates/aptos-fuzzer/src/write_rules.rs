@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use aptos_move_core_types::account_address::AccountAddress;
+use aptos_move_core_types::language_storage::{StructTag, TypeTag};
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::write_set::WriteSet;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::executor::aptos_custom_state::AptosCustomState;
+use crate::layout::LayoutCache;
+
+/// What a rule asserts about the writes to a resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "assert", rename_all = "snake_case")]
+pub enum WriteCheck {
+    /// The resource is never deleted once it exists
+    NeverDeleted,
+    /// A numeric field never goes down; deleting the resource counts as
+    /// going to 0. Nested fields are joined with dots, e.g. `coin.value`.
+    NonDecreasing { field: String },
+    /// A numeric field never goes up
+    NonIncreasing { field: String },
+    /// A numeric field is always written within `min..=max`
+    Bounds {
+        field: String,
+        min: Option<u128>,
+        max: Option<u128>,
+    },
+}
+
+impl WriteCheck {
+    fn field(&self) -> Option<&str> {
+        match self {
+            Self::NeverDeleted => None,
+            Self::NonDecreasing { field } | Self::NonIncreasing { field } | Self::Bounds { field, .. } => Some(field),
+        }
+    }
+}
+
+/// A rule on the writes to the resource of one account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteRule {
+    pub name: String,
+    pub address: String,
+    /// Resource type, e.g. `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`
+    pub resource: String,
+    /// Resource group the resource is a member of, e.g.
+    /// `0x1::object::ObjectGroup`
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(flatten)]
+    pub check: WriteCheck,
+}
+
+/// Where the resource of a rule is stored
+#[derive(Debug, Clone)]
+struct Watched {
+    key: StateKey,
+    tag: StructTag,
+    in_group: bool,
+}
+
+impl Watched {
+    fn resolve(rule: &WriteRule) -> anyhow::Result<Self> {
+        let address = AccountAddress::from_str(&rule.address)
+            .with_context(|| format!("Invalid address in write rule {}", rule.name))?;
+        let tag = StructTag::from_str(&rule.resource)
+            .with_context(|| format!("Invalid resource type in write rule {}", rule.name))?;
+        let (key, in_group) = match &rule.group {
+            Some(group) => {
+                let group = StructTag::from_str(group)
+                    .with_context(|| format!("Invalid resource group in write rule {}", rule.name))?;
+                (StateKey::resource_group(&address, &group), true)
+            }
+            None => (StateKey::resource(&address, &tag)?, false),
+        };
+        Ok(Self { key, tag, in_group })
+    }
+
+    /// Bytes of the resource within the value stored under the key
+    fn resource(&self, value: Option<&Bytes>) -> Option<Bytes> {
+        let value = value?;
+        if !self.in_group {
+            return Some(value.clone());
+        }
+        let group: BTreeMap<StructTag, Bytes> = bcs::from_bytes(value).ok()?;
+        group.get(&self.tag).cloned()
+    }
+}
+
+/// A change a call made to the resource of a rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub rule: String,
+    pub existed: bool,
+    pub exists: bool,
+    /// The checked field before and after the call, if it decoded
+    pub before: Option<u128>,
+    pub after: Option<u128>,
+}
+
+/// A write rule a call broke
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WriteViolation {
+    pub rule: String,
+    pub reason: String,
+}
+
+impl fmt::Display for WriteViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.reason)
+    }
+}
+
+#[derive(Deserialize)]
+struct WriteRulesFile {
+    #[serde(default)]
+    rules: Vec<WriteRule>,
+}
+
+/// Rules on the write set of every call, loaded from a TOML or JSON file
+///
+/// ```toml
+/// [[rules]]
+/// name = "alice_balance"
+/// address = "0xa"
+/// resource = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"
+/// assert = "non_decreasing"
+/// field = "coin.value"
+///
+/// [[rules]]
+/// name = "config_kept"
+/// address = "0xcafe"
+/// resource = "0xcafe::pool::Config"
+/// assert = "never_deleted"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WriteRules {
+    rules: Vec<(WriteRule, Watched)>,
+}
+
+impl WriteRules {
+    pub fn new(rules: Vec<WriteRule>) -> anyhow::Result<Self> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| Watched::resolve(&rule).map(|watched| (rule, watched)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Load rules from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read write rules {}", path.display()))?;
+
+        let file: WriteRulesFile = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                toml::from_str(&content).with_context(|| format!("Failed to parse write rules {}", path.display()))?
+            }
+            Some("json") => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse write rules {}", path.display()))?,
+            _ => bail!("Unsupported write rules format: {}", path.display()),
+        };
+        Self::new(file.rules)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Changes `write_set` makes to the resources of the rules, `state`
+    /// being the state it is applied to
+    pub fn changes(
+        &self,
+        layouts: &mut LayoutCache,
+        state: &AptosCustomState,
+        write_set: &WriteSet,
+    ) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+
+        for (rule, watched) in &self.rules {
+            let Some((_, write_op)) = write_set.write_op_iter().find(|(key, _)| **key == watched.key) else {
+                continue;
+            };
+            let before = watched.resource(state.get_state_value(&watched.key).as_ref().map(|value| value.bytes()));
+            let after = watched.resource(write_op.as_state_value().as_ref().map(|value| value.bytes()));
+            // Writes to other members of a group leave the resource as is
+            if before == after {
+                continue;
+            }
+
+            let tag = TypeTag::Struct(Box::new(watched.tag.clone()));
+            let mut field = |bytes: &Option<Bytes>| {
+                let field = rule.check.field()?;
+                layouts
+                    .numeric_fields(state, &tag, bytes.as_ref()?)?
+                    .get(field)
+                    .copied()
+            };
+            changes.push(FieldChange {
+                rule: rule.name.clone(),
+                existed: before.is_some(),
+                exists: after.is_some(),
+                before: field(&before),
+                after: field(&after),
+            });
+        }
+
+        changes
+    }
+
+    /// Rules the `changes` break
+    pub fn violations(&self, changes: &[FieldChange]) -> Vec<WriteViolation> {
+        changes
+            .iter()
+            .filter_map(|change| {
+                let (rule, _) = self.rules.iter().find(|(rule, _)| rule.name == change.rule)?;
+                let reason = match &rule.check {
+                    WriteCheck::NeverDeleted => (change.existed && !change.exists).then(|| "deleted".to_string())?,
+                    WriteCheck::NonDecreasing { field } => {
+                        let before = change.before?;
+                        let after = if change.exists { change.after? } else { 0 };
+                        (after < before).then(|| format!("{field} decreased from {before} to {after}"))?
+                    }
+                    WriteCheck::NonIncreasing { field } => {
+                        let (before, after) = (change.before?, change.after?);
+                        (after > before).then(|| format!("{field} increased from {before} to {after}"))?
+                    }
+                    WriteCheck::Bounds { field, min, max } => {
+                        let after = change.after?;
+                        (min.is_some_and(|min| after < min) || max.is_some_and(|max| after > max))
+                            .then(|| format!("{field} = {after} out of bounds"))?
+                    }
+                };
+                Some(WriteViolation {
+                    rule: change.rule.clone(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
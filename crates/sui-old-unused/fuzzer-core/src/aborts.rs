@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+/// How an abort code is treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AbortSeverity {
+    /// A known `assert!` guard rejecting bad input; not a finding
+    Expected,
+    /// An abort the code shouldn't reach; reported as a finding
+    #[default]
+    Bug,
+}
+
+impl fmt::Display for AbortSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expected => write!(f, "expected"),
+            Self::Bug => write!(f, "bug"),
+        }
+    }
+}
+
+/// Abort codes sorted by severity
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbortCodes {
+    pub expected: Vec<u64>,
+    pub bug: Vec<u64>,
+}
+
+impl AbortCodes {
+    fn get(&self, code: u64) -> Option<AbortSeverity> {
+        if self.bug.contains(&code) {
+            Some(AbortSeverity::Bug)
+        } else if self.expected.contains(&code) {
+            Some(AbortSeverity::Expected)
+        } else {
+            None
+        }
+    }
+}
+
+/// Severities of abort codes loaded from a TOML or JSON file. Module
+/// sections are keyed by module name or full module id and take precedence
+/// over the top-level codes; unlisted codes get `default`. Arithmetic
+/// failures carry no code and are always bugs.
+///
+/// ```toml
+/// default = "bug"
+/// expected = [1, 2]
+///
+/// [modules.pool]
+/// expected = [3]
+/// bug = [7]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbortPolicy {
+    pub default: AbortSeverity,
+    #[serde(flatten)]
+    pub codes: AbortCodes,
+    pub modules: BTreeMap<String, AbortCodes>,
+}
+
+impl AbortPolicy {
+    /// Load a policy from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read abort policy {}", path.display()))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                toml::from_str(&content).with_context(|| format!("Failed to parse abort policy {}", path.display()))
+            }
+            Some("json") => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse abort policy {}", path.display())),
+            _ => bail!("Unsupported abort policy format: {}", path.display()),
+        }
+    }
+
+    /// Severity of `code` raised by `module`, given as a name or
+    /// `address::name`
+    pub fn classify(&self, module: &str, code: u64) -> AbortSeverity {
+        let name = module.rsplit("::").next().unwrap_or(module);
+        self.modules
+            .get(module)
+            .or_else(|| self.modules.get(name))
+            .and_then(|codes| codes.get(code))
+            .or_else(|| self.codes.get(code))
+            .unwrap_or(self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let policy: AbortPolicy = toml::from_str(
+            r#"
+            default = "expected"
+            bug = [7]
+
+            [modules.pool]
+            expected = [7]
+            bug = [3]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.classify("0x1::coin", 7), AbortSeverity::Bug);
+        assert_eq!(policy.classify("0x42::pool", 7), AbortSeverity::Expected);
+        assert_eq!(policy.classify("pool", 3), AbortSeverity::Bug);
+        assert_eq!(policy.classify("0x42::pool", 9), AbortSeverity::Expected);
+        assert_eq!(AbortPolicy::default().classify("pool", 9), AbortSeverity::Bug);
+    }
+}
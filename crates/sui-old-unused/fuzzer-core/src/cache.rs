@@ -43,11 +43,11 @@ impl<A: ChainAdapter> ObjectCache<A> {
             let digest = self.adapter.compute_object_digest(&change.object);
             self.add_object_with_digest(change.id.clone(), change.object.clone(), digest);
             cached_count += 1;
-            debug!("Cached modified object: {:?}", change.id);
+            debug!(id = ?change.id, "cached modified object");
         }
 
         if cached_count > 0 {
-            info!("Cached {} modified objects", cached_count);
+            info!(cached = cached_count, "cached modified objects");
         }
     }
 
@@ -63,14 +63,13 @@ impl<A: ChainAdapter> ObjectCache<A> {
         cache.put(digest, object);
     }
 
-    pub fn get_random_version(&self, id: &A::ObjectId) -> Option<A::Object> {
+    pub fn get_random_version(&self, id: &A::ObjectId, rng: &mut impl Rng) -> Option<A::Object> {
         self.caches.get(id).and_then(|cache| {
             let items: Vec<_> = cache.iter().map(|(_, obj)| obj.clone()).collect();
 
             if items.is_empty() {
                 None
             } else {
-                let mut rng = rand::rng();
                 let index = rng.random_range(0..items.len());
                 Some(items[index].clone())
             }
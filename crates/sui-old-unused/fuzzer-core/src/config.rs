@@ -1,15 +1,24 @@
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 
-use crate::types::FuzzerConfig;
+use crate::objectives::ObjectivePolicy;
+use crate::stages::StageBudgets;
+use crate::steering::ParamSteering;
+use crate::sync::CorpusSyncConfig;
+use crate::types::{FuzzerConfig, InvariantCall};
 
 /// Configuration utilities for the fuzzer core
 impl FuzzerConfig {
     pub fn new(rpc_url: String, package_id: String, module_name: String, function_name: String) -> Self {
         Self {
             rpc_url,
+            fallback_rpc_urls: vec![],
+            chain: None,
             package_id,
+            package_path: None,
             module_name,
             function_name,
             type_arguments: vec![],
@@ -17,9 +26,41 @@ impl FuzzerConfig {
             iterations: 1_000_000,
             timeout_seconds: 300,
             sender: None,
+            keep_going: false,
+            objectives: ObjectivePolicy::default(),
+            max_exec_time_ms: None,
+            seed_from_chain: None,
+            seed_sources: None,
+            compare_package_id: None,
+            invariants: vec![],
+            result_cache_size: 4096,
+            execution_workers: 1,
+            unsafe_synthesize_objects: false,
+            param_steering: vec![],
+            seed: None,
+            findings_db: None,
+            corpus_sync: None,
+            stage_budgets: StageBudgets::default(),
+            dictionary: vec![],
+            exhaustive_limit: 65_536,
         }
     }
 
+    pub fn with_fallback_rpc_urls(mut self, urls: Vec<String>) -> Self {
+        self.fallback_rpc_urls = urls;
+        self
+    }
+
+    pub fn with_chain(mut self, chain: String) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    pub fn with_package_path(mut self, path: PathBuf) -> Self {
+        self.package_path = Some(path);
+        self
+    }
+
     pub fn with_type_arguments(mut self, type_args: Vec<String>) -> Self {
         self.type_arguments = type_args;
         self
@@ -45,6 +86,91 @@ impl FuzzerConfig {
         self
     }
 
+    pub fn with_keep_going(mut self, keep_going: bool) -> Self {
+        self.keep_going = keep_going;
+        self
+    }
+
+    pub fn with_objectives(mut self, objectives: ObjectivePolicy) -> Self {
+        self.objectives = objectives;
+        self
+    }
+
+    pub fn with_max_exec_time_ms(mut self, max_exec_time_ms: u64) -> Self {
+        self.max_exec_time_ms = Some(max_exec_time_ms);
+        self
+    }
+
+    pub fn with_seed_from_chain(mut self, limit: usize) -> Self {
+        self.seed_from_chain = Some(limit);
+        self
+    }
+
+    pub fn with_seed_sources(mut self, package_dir: PathBuf) -> Self {
+        self.seed_sources = Some(package_dir);
+        self
+    }
+
+    pub fn with_compare_package(mut self, package_id: String) -> Self {
+        self.compare_package_id = Some(package_id);
+        self
+    }
+
+    pub fn with_invariants(mut self, invariants: Vec<InvariantCall>) -> Self {
+        self.invariants = invariants;
+        self
+    }
+
+    pub fn with_result_cache_size(mut self, size: usize) -> Self {
+        self.result_cache_size = size;
+        self
+    }
+
+    pub fn with_execution_workers(mut self, workers: usize) -> Self {
+        self.execution_workers = workers;
+        self
+    }
+
+    pub fn with_unsafe_synthesize_objects(mut self, synthesize: bool) -> Self {
+        self.unsafe_synthesize_objects = synthesize;
+        self
+    }
+
+    pub fn with_param_steering(mut self, rules: Vec<ParamSteering>) -> Self {
+        self.param_steering = rules;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_findings_db(mut self, path: PathBuf) -> Self {
+        self.findings_db = Some(path);
+        self
+    }
+
+    pub fn with_corpus_sync(mut self, sync: CorpusSyncConfig) -> Self {
+        self.corpus_sync = Some(sync);
+        self
+    }
+
+    pub fn with_stage_budgets(mut self, budgets: StageBudgets) -> Self {
+        self.stage_budgets = budgets;
+        self
+    }
+
+    pub fn with_dictionary(mut self, values: Vec<u128>) -> Self {
+        self.dictionary = values;
+        self
+    }
+
+    pub fn with_exhaustive_limit(mut self, limit: u64) -> Self {
+        self.exhaustive_limit = limit;
+        self
+    }
+
     pub fn timeout_duration(&self) -> Duration {
         Duration::from_secs(self.timeout_seconds)
     }
@@ -54,7 +180,11 @@ impl FuzzerConfig {
             bail!("RPC URL cannot be empty");
         }
 
-        if self.package_id.is_empty() {
+        if self.fallback_rpc_urls.iter().any(String::is_empty) {
+            bail!("Fallback RPC URLs cannot be empty");
+        }
+
+        if self.package_id.is_empty() && self.package_path.is_none() {
             bail!("Package ID cannot be empty");
         }
 
@@ -74,10 +204,81 @@ impl FuzzerConfig {
             bail!("Timeout must be greater than 0");
         }
 
+        if self.execution_workers == 0 {
+            bail!("Execution workers must be greater than 0");
+        }
+
+        if self.max_exec_time_ms == Some(0) {
+            bail!("Max execution time must be greater than 0");
+        }
+
+        if self.seed_from_chain == Some(0) {
+            bail!("On-chain seed limit must be greater than 0");
+        }
+
+        if let Some(rule) = self
+            .param_steering
+            .iter()
+            .find(|rule| !self.args.is_empty() && rule.index >= self.args.len())
+        {
+            bail!(
+                "Parameter rule for index {} but only {} arguments",
+                rule.index,
+                self.args.len()
+            );
+        }
+
+        if let Some(sync) = &self.corpus_sync {
+            sync.validate()?;
+        }
+
+        if self.compare_package_id.as_ref() == Some(&self.package_id) {
+            bail!("Compared package must differ from the target package");
+        }
+
         Ok(())
     }
 }
 
+/// Parses `module::function` or `module::function(0, 2)`, the numbers being
+/// the indices of the target parameters passed along
+impl FromStr for InvariantCall {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (path, args) = match s.trim().split_once('(') {
+            Some((path, rest)) => {
+                let Some(args) = rest.strip_suffix(')') else {
+                    bail!("Unclosed argument list in invariant '{s}'");
+                };
+                let args = args
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| {
+                        arg.parse()
+                            .with_context(|| format!("Invalid parameter index '{arg}' in invariant '{s}'"))
+                    })
+                    .collect::<anyhow::Result<Vec<usize>>>()?;
+                (path.trim(), args)
+            }
+            None => (s.trim(), vec![]),
+        };
+        let Some((module_name, function_name)) = path.split_once("::") else {
+            bail!("Invariant '{s}' must be module::function");
+        };
+        if module_name.is_empty() || function_name.is_empty() || function_name.contains("::") {
+            bail!("Invariant '{s}' must be module::function");
+        }
+        Ok(Self {
+            module_name: module_name.to_string(),
+            function_name: function_name.to_string(),
+            type_arguments: vec![],
+            args,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,11 +293,13 @@ mod tests {
         )
         .with_iterations(5000)
         .with_timeout_seconds(60)
-        .with_sender("0xabc".to_string());
+        .with_sender("0xabc".to_string())
+        .with_keep_going(true);
 
         assert_eq!(config.iterations, 5000);
         assert_eq!(config.timeout_seconds, 60);
         assert_eq!(config.sender, Some("0xabc".to_string()));
+        assert!(config.keep_going);
     }
 
     #[test]
@@ -119,4 +322,16 @@ mod tests {
 
         assert!(invalid_config.validate().is_err());
     }
+
+    #[test]
+    fn test_parse_invariant() {
+        let invariant: InvariantCall = "pool::check_k(0, 2)".parse().unwrap();
+        assert_eq!(invariant.module_name, "pool");
+        assert_eq!(invariant.function_name, "check_k");
+        assert_eq!(invariant.args, vec![0, 2]);
+
+        assert!("pool::check_k".parse::<InvariantCall>().unwrap().args.is_empty());
+        assert!("check_k".parse::<InvariantCall>().is_err());
+        assert!("pool::check_k(a)".parse::<InvariantCall>().is_err());
+    }
 }
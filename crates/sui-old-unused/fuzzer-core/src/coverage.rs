@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Visited PCs keyed by fully qualified function name
+pub type VisitedPcs = BTreeMap<String, BTreeSet<u16>>;
+
+#[derive(Debug, Clone, Default)]
+struct FunctionCoverage {
+    instruction_count: usize,
+    visited: BTreeSet<u16>,
+}
+
+/// Per-function instruction coverage of the target package, accumulated over
+/// a campaign
+#[derive(Debug, Clone, Default)]
+pub struct CoverageMap {
+    functions: BTreeMap<String, FunctionCoverage>,
+}
+
+/// Coverage of a single function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCoverageSummary {
+    pub function: String,
+    pub instructions_hit: usize,
+    pub instruction_count: usize,
+}
+
+/// Final coverage report of a campaign
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverageSummary {
+    pub functions_total: usize,
+    pub functions_touched: usize,
+    pub instructions_total: usize,
+    pub instructions_hit: usize,
+    pub functions: Vec<FunctionCoverageSummary>,
+}
+
+impl CoverageSummary {
+    pub fn instruction_percentage(&self) -> f64 {
+        if self.instructions_total == 0 {
+            return 0.0;
+        }
+        self.instructions_hit as f64 * 100.0 / self.instructions_total as f64
+    }
+}
+
+impl CoverageMap {
+    /// Create a map tracking the given functions and their instruction counts.
+    /// PCs visited in other functions are ignored.
+    pub fn new(instruction_counts: BTreeMap<String, usize>) -> Self {
+        let functions = instruction_counts
+            .into_iter()
+            .map(|(function, instruction_count)| {
+                (
+                    function,
+                    FunctionCoverage {
+                        instruction_count,
+                        visited: BTreeSet::new(),
+                    },
+                )
+            })
+            .collect();
+
+        Self { functions }
+    }
+
+    /// Merge PCs visited by one execution, returning how many were new
+    pub fn merge(&mut self, visited: &VisitedPcs) -> usize {
+        let mut new_pcs = 0;
+
+        for (function, pcs) in visited {
+            if let Some(coverage) = self.functions.get_mut(function) {
+                for pc in pcs {
+                    if (*pc as usize) < coverage.instruction_count && coverage.visited.insert(*pc) {
+                        new_pcs += 1;
+                    }
+                }
+            }
+        }
+
+        new_pcs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    pub fn summary(&self) -> CoverageSummary {
+        let functions: Vec<FunctionCoverageSummary> = self
+            .functions
+            .iter()
+            .map(|(function, coverage)| FunctionCoverageSummary {
+                function: function.clone(),
+                instructions_hit: coverage.visited.len(),
+                instruction_count: coverage.instruction_count,
+            })
+            .collect();
+
+        CoverageSummary {
+            functions_total: functions.len(),
+            functions_touched: functions.iter().filter(|f| f.instructions_hit > 0).count(),
+            instructions_total: functions.iter().map(|f| f.instruction_count).sum(),
+            instructions_hit: functions.iter().map(|f| f.instructions_hit).sum(),
+            functions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_counts_new_pcs_of_known_functions() {
+        let mut coverage = CoverageMap::new(BTreeMap::from([
+            ("0x2::pool::swap".to_string(), 10),
+            ("0x2::pool::quote".to_string(), 4),
+        ]));
+
+        let visited = VisitedPcs::from([
+            ("0x2::pool::swap".to_string(), BTreeSet::from([0, 1, 2])),
+            ("0x1::vector::length".to_string(), BTreeSet::from([0])),
+        ]);
+        assert_eq!(coverage.merge(&visited), 3);
+        assert_eq!(coverage.merge(&visited), 0);
+
+        let summary = coverage.summary();
+        assert_eq!(summary.functions_total, 2);
+        assert_eq!(summary.functions_touched, 1);
+        assert_eq!(summary.instructions_total, 14);
+        assert_eq!(summary.instructions_hit, 3);
+    }
+}
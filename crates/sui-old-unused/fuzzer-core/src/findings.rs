@@ -0,0 +1,233 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS campaigns (
+    id INTEGER PRIMARY KEY,
+    target TEXT NOT NULL,
+    package_version TEXT,
+    config TEXT NOT NULL,
+    seed INTEGER,
+    started_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS findings (
+    id INTEGER PRIMARY KEY,
+    target TEXT NOT NULL,
+    key TEXT NOT NULL,
+    location TEXT NOT NULL,
+    description TEXT NOT NULL,
+    artifact TEXT,
+    first_campaign INTEGER NOT NULL REFERENCES campaigns(id),
+    last_campaign INTEGER NOT NULL REFERENCES campaigns(id),
+    occurrences INTEGER NOT NULL,
+    UNIQUE (target, key)
+);
+";
+
+/// One run against a target, as recorded in the findings database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub id: i64,
+    /// What was fuzzed, e.g. `<package>::<module>::<function>`
+    pub target: String,
+    /// Version of the target package, if the chain has one
+    pub package_version: Option<String>,
+    /// The campaign's configuration, for rerunning it
+    pub config: String,
+    pub seed: Option<u64>,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+}
+
+/// A distinct violation of a target, across every campaign that hit it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub id: i64,
+    pub target: String,
+    pub location: String,
+    pub description: String,
+    /// What reproduces it: a saved input or the parameters it was found
+    /// with, from the latest campaign hitting it
+    pub artifact: Option<String>,
+    pub first_campaign: i64,
+    pub last_campaign: i64,
+    /// Campaigns that hit it
+    pub occurrences: u64,
+}
+
+/// Violations of recurring campaigns against the same targets, kept in a
+/// SQLite file so a finding is triaged once and seen again as a regression
+/// rather than as a new bug
+pub struct FindingsDb {
+    conn: Connection,
+}
+
+impl FindingsDb {
+    /// Open the database at `path`, creating it if needed
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn =
+            Connection::open(path).with_context(|| format!("Failed to open findings database {}", path.display()))?;
+        conn.execute_batch(SCHEMA).context("Failed to create findings tables")?;
+        Ok(Self { conn })
+    }
+
+    pub fn start_campaign(
+        &self,
+        target: &str,
+        package_version: Option<&str>,
+        config: &str,
+        seed: Option<u64>,
+    ) -> anyhow::Result<Campaign> {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        // SQLite integers are signed; the seed's bits are kept as they are
+        self.conn.execute(
+            "INSERT INTO campaigns (target, package_version, config, seed, started_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                target,
+                package_version,
+                config,
+                seed.map(|seed| seed as i64),
+                started_at as i64
+            ],
+        )?;
+        Ok(Campaign {
+            id: self.conn.last_insert_rowid(),
+            target: target.to_string(),
+            package_version: package_version.map(str::to_string),
+            config: config.to_string(),
+            seed,
+            started_at,
+        })
+    }
+
+    /// Record a violation `campaign` hit, deduplicated by `key` within its
+    /// target. Returns whether no earlier campaign had hit it.
+    pub fn record(
+        &self,
+        campaign: &Campaign,
+        key: &str,
+        location: &str,
+        description: &str,
+        artifact: Option<&str>,
+    ) -> anyhow::Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE findings SET
+                 last_campaign = ?3,
+                 occurrences = occurrences + (last_campaign != ?3),
+                 artifact = COALESCE(?4, artifact)
+             WHERE target = ?1 AND key = ?2",
+            params![campaign.target, key, campaign.id, artifact],
+        )?;
+        if updated > 0 {
+            return Ok(false);
+        }
+        self.conn.execute(
+            "INSERT INTO findings
+                 (target, key, location, description, artifact, first_campaign, last_campaign, occurrences)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, 1)",
+            params![campaign.target, key, location, description, artifact, campaign.id],
+        )?;
+        Ok(true)
+    }
+
+    /// Every finding, most recently hit first
+    pub fn findings(&self) -> anyhow::Result<Vec<Finding>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, target, location, description, artifact, first_campaign, last_campaign, occurrences
+             FROM findings ORDER BY last_campaign DESC, id",
+        )?;
+        let findings = statement.query_map([], finding_from_row)?.collect::<Result<_, _>>()?;
+        Ok(findings)
+    }
+
+    pub fn finding(&self, id: i64) -> anyhow::Result<Option<Finding>> {
+        let finding = self
+            .conn
+            .query_row(
+                "SELECT id, target, location, description, artifact, first_campaign, last_campaign, occurrences
+                 FROM findings WHERE id = ?1",
+                [id],
+                finding_from_row,
+            )
+            .optional()?;
+        Ok(finding)
+    }
+
+    pub fn campaign(&self, id: i64) -> anyhow::Result<Option<Campaign>> {
+        let campaign = self
+            .conn
+            .query_row(
+                "SELECT id, target, package_version, config, seed, started_at FROM campaigns WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(Campaign {
+                        id: row.get(0)?,
+                        target: row.get(1)?,
+                        package_version: row.get(2)?,
+                        config: row.get(3)?,
+                        seed: row.get::<_, Option<i64>>(4)?.map(|seed| seed as u64),
+                        started_at: row.get::<_, i64>(5)? as u64,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(campaign)
+    }
+}
+
+fn finding_from_row(row: &Row<'_>) -> rusqlite::Result<Finding> {
+    Ok(Finding {
+        id: row.get(0)?,
+        target: row.get(1)?,
+        location: row.get(2)?,
+        description: row.get(3)?,
+        artifact: row.get(4)?,
+        first_campaign: row.get(5)?,
+        last_campaign: row.get(6)?,
+        occurrences: row.get::<_, i64>(7)? as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_across_campaigns() {
+        let db = FindingsDb::open(Path::new(":memory:")).unwrap();
+        let first = db
+            .start_campaign("0x1::pool::swap", Some("3"), "iterations = 10", Some(u64::MAX))
+            .unwrap();
+        assert!(db
+            .record(&first, "pool::swap:12#Shl", "pool::swap:12", "Shl 1 << 70", Some("[1]"))
+            .unwrap());
+        assert!(!db
+            .record(&first, "pool::swap:12#Shl", "pool::swap:12", "Shl 1 << 70", None)
+            .unwrap());
+
+        let second = db.start_campaign("0x1::pool::swap", Some("4"), "", None).unwrap();
+        assert!(!db
+            .record(&second, "pool::swap:12#Shl", "pool::swap:12", "Shl 1 << 71", None)
+            .unwrap());
+        // The same location in another target is another finding
+        let other = db.start_campaign("0x2::pool::swap", None, "", None).unwrap();
+        assert!(db
+            .record(&other, "pool::swap:12#Shl", "pool::swap:12", "Shl 1 << 70", None)
+            .unwrap());
+
+        let findings = db.findings().unwrap();
+        assert_eq!(findings.len(), 2);
+        let finding = db.finding(findings[1].id).unwrap().unwrap();
+        assert_eq!((finding.first_campaign, finding.last_campaign), (first.id, second.id));
+        assert_eq!(finding.occurrences, 2);
+        assert_eq!(finding.artifact.as_deref(), Some("[1]"));
+        assert_eq!(db.campaign(first.id).unwrap(), Some(first));
+        assert_eq!(db.finding(42).unwrap(), None);
+    }
+}
@@ -1,12 +1,54 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use futures::stream::{FuturesOrdered, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::sync::watch;
 use tokio::time::timeout;
-use tracing::{debug, info, warn};
+use tracing::{debug, debug_span, info, info_span, warn, Instrument};
 
 use crate::cache::ObjectCache;
-use crate::{ChainAdapter, ChainMutationStrategy, ChainValue, FunctionInfo, FuzzerConfig, FuzzingResult, Parameter};
+use crate::coverage::CoverageMap;
+use crate::findings::FindingsDb;
+use crate::guards::GuardTracker;
+use crate::progress::Progress;
+use crate::result_cache::ResultCache;
+use crate::schedule::PowerScheduler;
+use crate::stages::{InputSpace, StagedInputs};
+use crate::stats::ExecutionStats;
+use crate::steering::ParamPlan;
+use crate::sync::CorpusSync;
+use crate::{
+    seeding, ChainAdapter, ChainMutationStrategy, ChainValue, FunctionInfo, FuzzerConfig, FuzzingResult, Parameter,
+    ViolationInfo,
+};
+
+/// Share of mutated inputs whose parameters are moved past the known guards;
+/// the rest keep exercising the failing side
+const GUARD_BIAS: f64 = 0.75;
+
+/// Time past the deadline the executions in flight get to finish before the
+/// campaign is cut off, losing its results
+const DEADLINE_GRACE: Duration = Duration::from_secs(30);
+
+/// Time between the snapshots of a campaign published to its subscribers
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An input dispatched for execution, with its result
+struct Execution<A: ChainAdapter> {
+    iteration: u64,
+    parameters: Vec<Parameter<A::Value>>,
+    /// Result cache key of `parameters`
+    key: Option<u64>,
+    /// Whether `result` came from the result cache
+    is_repeat: bool,
+    result: anyhow::Result<A::ExecutionResult>,
+}
 
 /// Core fuzzer that orchestrates the fuzzing process using blockchain-specific
 /// adapters
@@ -16,47 +58,217 @@ pub struct CoreFuzzer<A: ChainAdapter> {
     function: FunctionInfo,
     parameters: Vec<Parameter<A::Value>>,
     mutator: A::Mutator,
+    /// How each parameter is mutated, by position
+    plans: Vec<ParamPlan>,
+    /// Seed of `rng` and the mutator
+    seed: u64,
+    /// Picks values from parameter value lists and guard-passing values
+    rng: StdRng,
     cache: ObjectCache<A>,
+    /// Distinct violations recorded in keep-going mode
+    violations: Vec<ViolationInfo>,
+    /// Dedup keys of recorded violations; these no longer count as objectives
+    seen_violations: HashSet<String>,
+    /// Iteration and parameters each violation was first found with, as
+    /// JSON, by dedup key
+    repros: HashMap<String, String>,
+    /// Execution time and gas statistics
+    stats: ExecutionStats,
+    /// Instruction coverage of the target package
+    coverage: CoverageMap,
+    /// Guards of the target function on its parameters
+    guards: GuardTracker,
+    /// Results of recent inputs, reused when an input repeats
+    results: Option<ResultCache<A::ExecutionResult>>,
+    /// Boundary and dictionary inputs run before havoc
+    stages: StagedInputs<A::Value>,
+    /// Every input of the target, if there are few enough to run them all
+    /// instead of fuzzing
+    space: Option<InputSpace<A::Value>>,
+    /// Inputs that reached new coverage, and which one to mutate next
+    schedule: PowerScheduler<Vec<Parameter<A::Value>>>,
+    /// Exchange of coverage-increasing inputs with other instances
+    sync: Option<CorpusSync<A::Value>>,
+    /// Peers' inputs waiting to be executed as they are
+    imports: VecDeque<Vec<Parameter<A::Value>>>,
+    /// Set to end the campaign before its budget runs out
+    stop: Arc<AtomicBool>,
+    /// Latest snapshot of the campaign, see [`Self::subscribe`]
+    progress: watch::Sender<Progress>,
 }
 
 impl<A: ChainAdapter> CoreFuzzer<A> {
     pub async fn new(adapter: A, config: FuzzerConfig) -> anyhow::Result<Self> {
-        info!("Initializing CoreFuzzer with config: {:?}", config);
+        info!(?config, "initializing CoreFuzzer");
 
         let adapter = Arc::new(adapter);
 
         // Initialize components using the adapter
         let function = adapter.resolve_function(&config).await?;
-        let parameters = adapter.initialize_parameters(&function, &config.args).await?;
-        let mutator = adapter.create_mutator();
+        let args = match config.seed_from_chain {
+            Some(limit) => Self::onchain_args(&adapter, &function, limit)
+                .await
+                .unwrap_or_else(|| config.args.clone()),
+            None => config.args.clone(),
+        };
+        let args = match &config.seed_sources {
+            Some(dir) => Self::source_args(dir, &function, args),
+            None => args,
+        };
+        let parameters = adapter.initialize_parameters(&function, &args).await?;
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mutator = adapter.create_mutator(rng.random());
+        let plans: Vec<ParamPlan> = parameters
+            .iter()
+            .map(|param| ParamPlan::new(param.index, &config.param_steering))
+            .collect();
         let cache = ObjectCache::new(adapter.clone());
+        let results = ResultCache::new(config.result_cache_size);
+        let instruction_counts = adapter
+            .function_instruction_counts(&function)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "coverage tracking disabled");
+                Default::default()
+            });
+
+        let sync = config
+            .corpus_sync
+            .as_ref()
+            .map(|sync| CorpusSync::new(sync, Self::target_of(&function)))
+            .transpose()?;
+
+        let guards = adapter.parameter_guards(&function).await.unwrap_or_else(|e| {
+            warn!(error = %e, "guard analysis failed");
+            Vec::new()
+        });
+        for guard in &guards {
+            debug!(pc = guard.pc, condition = %guard.condition(), "found guard");
+        }
 
         info!(
-            "CoreFuzzer initialized for {}::{}::{} with {} parameters",
-            function.package_id,
-            function.module_name,
-            function.function_name,
-            parameters.len()
+            package = %function.package_id,
+            module = %function.module_name,
+            function = %function.function_name,
+            parameters = parameters.len(),
+            guards = guards.len(),
+            seed,
+            "CoreFuzzer initialized"
         );
 
+        let guards = GuardTracker::new(guards);
+        let swept = parameters
+            .iter()
+            .zip(&plans)
+            .enumerate()
+            .filter(|(_, (param, plan))| param.is_integer() && plan.rounds() > 0)
+            .map(|(position, _)| position)
+            .collect();
+        let dictionary = config
+            .dictionary
+            .iter()
+            .copied()
+            .chain(guards.constants())
+            .chain(parameters.iter().filter_map(|param| param.value.integer_value()))
+            .collect();
+        let stages = StagedInputs::new(parameters.clone(), swept, dictionary, config.stage_budgets);
+        let space = InputSpace::of(&parameters, config.exhaustive_limit);
+        if let Some(space) = &space {
+            info!(inputs = space.size(), "input space small enough to enumerate");
+        }
+
         Ok(Self {
             adapter,
             config,
             function,
+            stages,
+            space,
+            schedule: PowerScheduler::new(parameters.clone()),
             parameters,
             mutator,
+            plans,
+            seed,
+            rng,
             cache,
+            violations: Vec::new(),
+            seen_violations: HashSet::new(),
+            repros: HashMap::new(),
+            stats: ExecutionStats::new(),
+            coverage: CoverageMap::new(instruction_counts),
+            guards,
+            results,
+            sync,
+            imports: VecDeque::new(),
+            stop: Arc::default(),
+            progress: watch::channel(Progress::default()).0,
         })
     }
 
+    /// Flag that ends the campaign once set, e.g. from a Ctrl-C handler. The
+    /// inputs in flight finish, then the campaign is saved and reported as
+    /// at its time limit.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Snapshots of the campaign while it runs, published about once a
+    /// second and once more when it ends
+    pub fn subscribe(&self) -> watch::Receiver<Progress> {
+        self.progress.subscribe()
+    }
+
+    /// `<package>::<module>::<function>`, naming the campaign's target in the
+    /// findings database and to peers
+    fn target_of(function: &FunctionInfo) -> String {
+        format!(
+            "{}::{}::{}",
+            function.package_id, function.module_name, function.function_name
+        )
+    }
+
+    /// Arguments of the latest successful on-chain call, if any was found
+    async fn onchain_args(adapter: &A, function: &FunctionInfo, limit: usize) -> Option<Vec<String>> {
+        match adapter.historical_args(function, limit).await {
+            Ok(calls) => {
+                info!(calls = calls.len(), "found on-chain calls to seed from");
+                calls.into_iter().next()
+            }
+            Err(e) => {
+                warn!(error = %e, "failed to seed from on-chain calls");
+                None
+            }
+        }
+    }
+
+    /// `args` with the literal arguments filled in from the call to the
+    /// function in the Move sources under `dir` that has the most of them
+    fn source_args(dir: &Path, function: &FunctionInfo, mut args: Vec<String>) -> Vec<String> {
+        let calls = seeding::scan_call_literals(dir, &function.module_name, &function.function_name);
+        info!(calls = calls.len(), "found calls in Move sources to seed from");
+        let Some(call) = calls.into_iter().max_by_key(|call| call.iter().flatten().count()) else {
+            return args;
+        };
+        if args.len() < call.len() {
+            args.resize(call.len(), String::new());
+        }
+        for (arg, literal) in args.iter_mut().zip(call) {
+            if let Some(literal) = literal {
+                *arg = literal.to_arg_string();
+            }
+        }
+        args
+    }
+
     pub async fn run(&mut self) -> anyhow::Result<FuzzingResult> {
         let start_time = Instant::now();
         let max_iterations = self.config.iterations;
-        let timeout_duration = std::time::Duration::from_secs(self.config.timeout_seconds);
+        let deadline = start_time + self.config.timeout_duration();
 
         info!(
-            "Starting fuzzing: {} iterations, timeout: {}s",
-            max_iterations, self.config.timeout_seconds
+            iterations = max_iterations,
+            timeout_secs = self.config.timeout_seconds,
+            "starting fuzzing"
         );
 
         // Shared counter for tracking iterations across timeout scenarios
@@ -65,88 +277,498 @@ impl<A: ChainAdapter> CoreFuzzer<A> {
 
         let sender = self.adapter.get_sender_from_config(&self.config);
 
+        let span = info_span!(
+            "campaign",
+            module = %self.function.module_name,
+            function = %self.function.function_name
+        );
+        // The loop stops itself at the deadline; this only cuts off executions
+        // that hang past it
         let result = timeout(
-            timeout_duration,
-            self.fuzzing_loop(sender, max_iterations, counter_clone),
+            self.config.timeout_duration() + DEADLINE_GRACE,
+            self.fuzzing_loop(sender, max_iterations, deadline, counter_clone)
+                .instrument(span),
         )
         .await;
 
         let total_execution_time = start_time.elapsed();
 
-        match result {
+        let result = match result {
             Ok(loop_result) => match loop_result {
                 Ok(fuzzing_result) => {
-                    info!("Fuzzing completed in {:.2}s", total_execution_time.as_secs_f64());
-                    Ok(fuzzing_result)
+                    info!(elapsed_secs = total_execution_time.as_secs_f64(), "fuzzing completed");
+                    self.finalize(fuzzing_result)
                 }
                 Err(error) => {
-                    warn!("Fuzzing failed: {}", error);
-                    Ok(FuzzingResult::error(error.to_string()))
+                    warn!(%error, "fuzzing failed");
+                    self.finalize(FuzzingResult::error(error.to_string()))
                 }
             },
             Err(_) => {
-                warn!("Fuzzing timed out after {:.2}s", total_execution_time.as_secs_f64());
-                Ok(FuzzingResult::error("Timeout".to_string()))
+                // What ran before the hung executions still counts
+                warn!(
+                    elapsed_secs = total_execution_time.as_secs_f64(),
+                    "executions hung past the deadline, cutting the campaign off"
+                );
+                self.finalize(
+                    FuzzingResult::completed(
+                        self.violations.clone(),
+                        iteration_counter.load(Ordering::Relaxed),
+                        max_iterations,
+                    )
+                    .with_timed_out(),
+                )
+            }
+        };
+
+        if let Some(sync) = &mut self.sync {
+            if let Err(error) = sync.push().await {
+                warn!(error = %format!("{error:#}"), "failed to push inputs to peers");
+            }
+        }
+        if let Some(path) = &self.config.findings_db {
+            if let Err(error) = self.save_findings(path, &result).await {
+                warn!(error = %format!("{error:#}"), "failed to record findings");
+            }
+        }
+        self.publish(
+            iteration_counter.load(Ordering::Relaxed),
+            max_iterations,
+            total_execution_time,
+            true,
+        );
+        Ok(result)
+    }
+
+    /// Record the campaign and the violations of `result` in the findings
+    /// database at `path`
+    async fn save_findings(&self, path: &Path, result: &FuzzingResult) -> anyhow::Result<()> {
+        let package_version = self.adapter.package_version(&self.function).await.unwrap_or_else(|e| {
+            warn!(error = %e, "failed to look up package version");
+            None
+        });
+        let target = Self::target_of(&self.function);
+
+        let db = FindingsDb::open(path)?;
+        let campaign = db.start_campaign(
+            &target,
+            package_version.as_deref(),
+            &format!("{:?}", self.config),
+            Some(self.seed),
+        )?;
+        let mut new_count = 0;
+        for violation in &result.violations {
+            let key = violation.dedup_key();
+            let description = format!(
+                "{} ({}, {})",
+                violation.operation, violation.left_operand, violation.right_operand
+            );
+            let repro = self.repros.get(&key).map(String::as_str);
+            if db.record(&campaign, &key, &violation.location, &description, repro)? {
+                new_count += 1;
             }
         }
+
+        info!(
+            campaign = campaign.id,
+            violations = result.violations.len(),
+            new = new_count,
+            path = %path.display(),
+            "recorded findings"
+        );
+        Ok(())
+    }
+
+    /// Attach the seed, campaign statistics, corpus size, coverage and RPC
+    /// endpoint usage to a result
+    fn finalize(&self, result: FuzzingResult) -> FuzzingResult {
+        let mut result = result
+            .with_seed(self.seed)
+            .with_stats(self.stats.summary())
+            .with_endpoints(self.adapter.endpoint_stats());
+        if let Some(results) = &self.results {
+            result = result.with_result_cache(results.stats());
+        }
+        if !self.guards.is_empty() {
+            result = result.with_guards(self.guards.summary());
+        }
+        result = result.with_corpus_size(self.schedule.len());
+        if let Some(space) = self.space.as_ref().filter(|space| space.is_exhausted()) {
+            result = result.with_input_space(space.size());
+        }
+        if self.coverage.is_empty() {
+            return result;
+        }
+        result.with_coverage(self.coverage.summary())
+    }
+
+    /// Replace the snapshot seen by the campaign's subscribers
+    fn publish(&self, iterations: u64, total_iterations: u64, elapsed: Duration, finished: bool) {
+        self.progress.send_replace(Progress {
+            iterations,
+            total_iterations,
+            elapsed,
+            corpus_size: self.schedule.len(),
+            violations: self.violations.len(),
+            last_violation: self.violations.last().map(Progress::describe_violation),
+            coverage: (!self.coverage.is_empty()).then(|| self.coverage.summary()),
+            endpoints: self.adapter.endpoint_stats(),
+            finished,
+        });
     }
 
     async fn fuzzing_loop(
         &mut self,
         sender: A::Address,
-        max_iterations: u64,
+        mut max_iterations: u64,
+        deadline: Instant,
         iteration_counter: Arc<AtomicU64>,
     ) -> anyhow::Result<FuzzingResult> {
         let start_time = Instant::now();
+        let workers = self.config.execution_workers.max(1);
+        let function = Arc::new(self.function.clone());
+        let mut in_flight = FuturesOrdered::new();
+        let mut next_iteration = 1;
+        let mut timed_out = false;
+        let mut interrupted = false;
+        let mut published = Instant::now();
+
+        loop {
+            // Step 1: Keep up to `workers` inputs executing, preparing the
+            // input of each
+            while in_flight.len() < workers && next_iteration <= max_iterations {
+                // Past the deadline, the inputs in flight still finish so
+                // their results count
+                if Instant::now() >= deadline {
+                    timed_out = true;
+                    break;
+                }
+                if self.stop.load(Ordering::Relaxed) {
+                    interrupted = true;
+                    break;
+                }
+                if !self.prepare_input(next_iteration)? {
+                    // Every input ran, so fuzzing further would only repeat them
+                    info!(iterations = next_iteration - 1, "input space fully covered");
+                    max_iterations = next_iteration - 1;
+                    break;
+                }
+                in_flight.push_back(self.dispatch(next_iteration, sender.clone(), function.clone()));
+                next_iteration += 1;
+            }
 
-        for iteration in 1..=max_iterations {
+            // Results come back in iteration order, whichever finishes first
+            let Some(execution) = in_flight.next().await else {
+                break;
+            };
+            let iteration = execution.iteration;
             iteration_counter.store(iteration, Ordering::Relaxed);
-            debug!("Starting iteration {}/{}", iteration, max_iterations);
+            // One input the adapter can't run doesn't end the campaign
+            let execution_result = match execution.result {
+                Ok(result) => result,
+                Err(error) => {
+                    warn!(iteration, error = %format!("{error:#}"), "execution failed");
+                    self.stats.record_failure();
+                    continue;
+                }
+            };
+            let _entered = debug_span!("iteration", iteration).entered();
 
             if iteration % 10_000 == 0 {
-                info!("Progress: {}/{} iterations", iteration, max_iterations);
+                info!(
+                    iteration,
+                    iterations = max_iterations,
+                    corpus = self.schedule.len(),
+                    "progress"
+                );
             }
 
-            // Step 1: Execute the function with current parameters
-            let execution_result = self.adapter.execute(&sender, &self.function, &self.parameters).await?;
+            if execution.is_repeat {
+                debug!("repeated input, reusing cached result");
+            } else {
+                if let (Some(results), Some(key)) = (&mut self.results, execution.key) {
+                    results.insert(key, execution_result.clone());
+                }
+                // Timing a cached result again would skew the distribution
+                self.record_stats(iteration, &execution.parameters, &execution_result);
+            }
 
             let object_changes = self.adapter.extract_object_changes(&execution_result);
             if !object_changes.is_empty() {
-                debug!("Processing {} object changes to update cache", object_changes.len());
+                debug!(changes = object_changes.len(), "updating object cache");
                 self.cache.process_changes(&object_changes);
             }
 
-            // Step 2: Check for shift violations
-            if self.adapter.has_shift_violations(&execution_result) {
-                info!(
-                    "🎯 Shift violation detected on iteration {}/{}!",
-                    iteration, max_iterations
+            let visited_pcs = self.adapter.visited_pcs(&execution_result);
+            self.guards.record(&visited_pcs);
+            let new_pcs = self.coverage.merge(&visited_pcs);
+            let abort_path = self.adapter.abort_path(&execution_result);
+            self.schedule.record_path(abort_path.as_deref());
+            if new_pcs > 0 {
+                debug!(new_pcs, "new coverage");
+                self.schedule.add(
+                    execution.parameters.clone(),
+                    iteration,
+                    new_pcs,
+                    self.adapter.execution_time(&execution_result),
+                    abort_path,
                 );
+                if let Some(sync) = &mut self.sync {
+                    sync.queue(&execution.parameters);
+                }
+            }
+            self.sync_corpus().await;
 
-                let violations = self.adapter.extract_violations(&execution_result);
-                return Ok(FuzzingResult::violation_found(violations, iteration));
+            // Step 2: Check for findings and pathologically slow executions
+            let mut violations = Vec::new();
+            if self.adapter.has_violations(&execution_result) {
+                violations.extend(self.adapter.extract_violations(&execution_result));
+            }
+            if let Some(violation) = self.check_exec_time(&execution_result) {
+                violations.push(violation);
             }
+            let keep_going = self.config.keep_going;
+            violations.retain(|violation| !self.config.objectives.is_ignored(violation, keep_going));
+
+            if !violations.is_empty() {
+                self.keep_repros(&violations, iteration, &execution.parameters);
+                let new_count = self.record_violations(violations);
+                if new_count > 0 {
+                    info!(new = new_count, distinct = self.violations.len(), "new violations");
+                }
 
-            debug!("Iteration {} completed - no violations found", iteration);
+                if let Some(class) = self.config.objectives.stop_reached(&self.violations, keep_going) {
+                    info!(%class, violations = self.violations.len(), "violation detected");
+                    return Ok(FuzzingResult::violation_found(self.violations.clone(), iteration));
+                }
+            }
 
-            // Step 3: Mutate parameters for next iteration
-            if iteration < max_iterations {
-                self.update_cached_objects()?;
-                self.mutate_parameters()?;
+            if published.elapsed() >= PROGRESS_INTERVAL {
+                self.publish(iteration, max_iterations, start_time.elapsed(), false);
+                published = Instant::now();
             }
+
+            debug!("iteration completed");
         }
 
-        // All iterations completed without finding violations
         let total_time = start_time.elapsed();
+
+        if timed_out {
+            let completed = next_iteration - 1;
+            info!(
+                iterations = completed,
+                elapsed_secs = total_time.as_secs_f64(),
+                violations = self.violations.len(),
+                "time limit reached"
+            );
+            return Ok(FuzzingResult::completed(self.violations.clone(), completed, max_iterations).with_timed_out());
+        }
+        if interrupted {
+            let completed = next_iteration - 1;
+            info!(
+                iterations = completed,
+                elapsed_secs = total_time.as_secs_f64(),
+                violations = self.violations.len(),
+                "stopped"
+            );
+            return Ok(FuzzingResult::completed(self.violations.clone(), completed, max_iterations).with_interrupted());
+        }
+
+        // Recorded violations of classes that don't stop the campaign are
+        // reported even without keep_going
+        if self.config.keep_going || !self.violations.is_empty() {
+            info!(
+                iterations = max_iterations,
+                elapsed_secs = total_time.as_secs_f64(),
+                violations = self.violations.len(),
+                "completed all iterations"
+            );
+            return Ok(FuzzingResult::completed(
+                self.violations.clone(),
+                max_iterations,
+                max_iterations,
+            ));
+        }
+
+        // All iterations completed without finding violations
         info!(
-            "Completed all {} iterations in {:.2}s - no violations found",
-            max_iterations,
-            total_time.as_secs_f64()
+            iterations = max_iterations,
+            elapsed_secs = total_time.as_secs_f64(),
+            violations = 0,
+            "completed all iterations"
         );
 
         Ok(FuzzingResult::no_violation_found())
     }
 
+    /// Set the parameters to `iteration`'s input: the next of an enumerated
+    /// input space, a peer's or a deterministic stage's as it is, or else a
+    /// mutation of a corpus entry. Returns false once the input space has
+    /// been enumerated.
+    fn prepare_input(&mut self, iteration: u64) -> anyhow::Result<bool> {
+        if let Some(space) = &mut self.space {
+            let Some(parameters) = space.next() else {
+                return Ok(false);
+            };
+            self.parameters = parameters;
+            self.update_cached_objects()?;
+            return Ok(true);
+        }
+        if iteration == 1 {
+            return Ok(true);
+        }
+        if let Some(parameters) = self.imports.pop_front().or_else(|| self.stages.next()) {
+            self.parameters = parameters;
+            self.update_cached_objects()?;
+            return Ok(true);
+        }
+        // Without coverage the corpus can't grow, so mutations keep walking
+        // from the last input instead
+        if !self.coverage.is_empty() {
+            self.parameters = self.schedule.next(iteration).input.clone();
+        }
+        self.update_cached_objects()?;
+        self.mutate_parameters()?;
+        Ok(true)
+    }
+
+    /// Start executing the current parameters as `iteration`, or answer from
+    /// the result cache if they repeat an earlier input. The returned future
+    /// owns everything it needs, so several can run while the parameters
+    /// are mutated further.
+    fn dispatch(
+        &mut self,
+        iteration: u64,
+        sender: A::Address,
+        function: Arc<FunctionInfo>,
+    ) -> impl Future<Output = Execution<A>> + Send {
+        let parameters = self.parameters.clone();
+        let key = self
+            .results
+            .as_ref()
+            .and_then(|_| ResultCache::<A::ExecutionResult>::key(&parameters));
+        let cached = match (&mut self.results, key) {
+            (Some(results), Some(key)) => results.get(key),
+            _ => None,
+        };
+        let adapter = self.adapter.clone();
+
+        async move {
+            let is_repeat = cached.is_some();
+            let result = match cached {
+                Some(result) => Ok(result),
+                None => adapter.execute(&sender, &function, &parameters).await,
+            };
+            Execution {
+                iteration,
+                parameters,
+                key,
+                is_repeat,
+                result,
+            }
+        }
+        .instrument(debug_span!("iteration", iteration))
+    }
+
+    /// Push the inputs that reached new coverage and queue the peers' ones,
+    /// once the sync interval has passed
+    async fn sync_corpus(&mut self) {
+        let Some(sync) = self.sync.as_mut().filter(|sync| sync.is_due()) else {
+            return;
+        };
+        match sync.exchange().await {
+            Ok(imported) => {
+                // Inputs of another version of the function don't fit
+                let fitting: Vec<_> = imported
+                    .into_iter()
+                    .filter(|parameters| {
+                        parameters.len() == self.parameters.len() &&
+                            parameters
+                                .iter()
+                                .zip(&self.parameters)
+                                .all(|(theirs, ours)| theirs.type_name == ours.type_name)
+                    })
+                    .collect();
+                if !fitting.is_empty() {
+                    info!(inputs = fitting.len(), "imported peer inputs");
+                }
+                self.imports.extend(fitting);
+            }
+            Err(error) => warn!(error = %format!("{error:#}"), "corpus sync failed"),
+        }
+    }
+
+    fn record_stats(
+        &mut self,
+        iteration: u64,
+        parameters: &[Parameter<A::Value>],
+        execution_result: &A::ExecutionResult,
+    ) {
+        let execution_time = self.adapter.execution_time(execution_result);
+        let gas_used = self.adapter.gas_used(execution_result);
+
+        self.stats.record(iteration, execution_time, gas_used, || {
+            serde_json::to_string(parameters).unwrap_or_default()
+        });
+    }
+
+    /// Report executions exceeding `max_exec_time_ms` as a finding, since they
+    /// are potential gas-griefing / DoS vectors
+    fn check_exec_time(&self, execution_result: &A::ExecutionResult) -> Option<ViolationInfo> {
+        let max_exec_time = Duration::from_millis(self.config.max_exec_time_ms?);
+        let execution_time = self.adapter.execution_time(execution_result);
+        if execution_time <= max_exec_time {
+            return None;
+        }
+
+        warn!(
+            elapsed_ms = execution_time.as_millis() as u64,
+            limit_ms = max_exec_time.as_millis() as u64,
+            "slow execution"
+        );
+
+        Some(ViolationInfo {
+            location: format!("{}::{}", self.function.module_name, self.function.function_name),
+            operation: "SlowExecution".to_string(),
+            left_operand: execution_time.as_millis() as u64,
+            right_operand: max_exec_time.as_millis() as u64,
+            call_chain: Vec::new(),
+        })
+    }
+
+    /// Remember what reproduces violations not found before, for the
+    /// findings database
+    fn keep_repros(&mut self, violations: &[ViolationInfo], iteration: u64, parameters: &[Parameter<A::Value>]) {
+        if self.config.findings_db.is_none() {
+            return;
+        }
+        for violation in violations {
+            let key = violation.dedup_key();
+            if self.repros.contains_key(&key) {
+                continue;
+            }
+            let repro = serde_json::json!({ "iteration": iteration, "parameters": parameters });
+            self.repros.insert(key, repro.to_string());
+        }
+    }
+
+    /// Record violations not seen before, returning how many were new.
+    /// Already recorded violations are dropped so they stop acting as
+    /// objectives.
+    fn record_violations(&mut self, violations: Vec<ViolationInfo>) -> usize {
+        let mut new_count = 0;
+
+        for violation in violations {
+            if self.seen_violations.insert(violation.dedup_key()) {
+                debug!(location = %violation.location, "recorded new violation");
+                self.violations.push(violation);
+                new_count += 1;
+            }
+        }
+
+        new_count
+    }
+
     /// Update cached objects from the object cache for mutable shared objects
     fn update_cached_objects(&mut self) -> anyhow::Result<()> {
         let mut updated_count = 0;
@@ -155,11 +777,11 @@ impl<A: ChainAdapter> CoreFuzzer<A> {
             if param.value.is_mutable_object() {
                 if let Some(obj_id_bytes) = param.value.get_object_id() {
                     if let Ok(object_id) = self.adapter.bytes_to_object_id(&obj_id_bytes) {
-                        if let Some(cached_obj) = self.cache.get_random_version(&object_id) {
+                        if let Some(cached_obj) = self.cache.get_random_version(&object_id, &mut self.rng) {
                             self.adapter
                                 .update_value_with_cached_object(&mut param.value, &cached_obj)?;
                             updated_count += 1;
-                            debug!("Updated parameter {} with cached object for ObjectId", param.index);
+                            debug!(parameter = param.index, "updated parameter with cached object");
                         }
                     }
                 }
@@ -167,22 +789,31 @@ impl<A: ChainAdapter> CoreFuzzer<A> {
         }
 
         if updated_count > 0 {
-            debug!("Updated {} parameters with cached objects", updated_count);
+            debug!(updated = updated_count, "updated parameters with cached objects");
         }
 
         Ok(())
     }
 
     fn mutate_parameters(&mut self) -> anyhow::Result<()> {
-        debug!("Mutating {} parameters", self.parameters.len());
+        debug!(parameters = self.parameters.len(), "mutating parameters");
 
-        for param in &mut self.parameters {
-            self.mutator.mutate(&mut param.value)?;
+        for (param, plan) in self.parameters.iter_mut().zip(&self.plans) {
+            if plan.rounds() == 0 {
+                continue;
+            }
+            for _ in 0..plan.rounds() {
+                self.mutator.mutate(&mut param.value)?;
+            }
+            if self.rng.random_bool(GUARD_BIAS) && self.guards.satisfy(param.index, &mut param.value, &mut self.rng) {
+                debug!(parameter = param.index, "moved parameter past its guards");
+            }
+            plan.constrain(&mut param.value, &mut self.rng);
             debug!(
-                "Mutated parameter {}: {} = {:?}",
-                param.index,
-                param.type_name(),
-                param.value
+                parameter = param.index,
+                ty = %param.type_name(),
+                value = ?param.value,
+                "mutated parameter"
             );
         }
 
@@ -201,6 +832,18 @@ impl<A: ChainAdapter> CoreFuzzer<A> {
         &self.parameters
     }
 
+    pub fn stats(&self) -> &ExecutionStats {
+        &self.stats
+    }
+
+    pub fn coverage(&self) -> &CoverageMap {
+        &self.coverage
+    }
+
+    pub fn violations(&self) -> &[ViolationInfo] {
+        &self.violations
+    }
+
     pub fn cache_stats(&self) -> (usize, Vec<A::ObjectId>) {
         (self.cache.total_cached_objects(), self.cache.cached_object_ids())
     }
@@ -0,0 +1,299 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::coverage::VisitedPcs;
+use crate::ChainValue;
+
+/// Comparison a parameter must satisfy against a constant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GuardOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Neq,
+}
+
+impl GuardOp {
+    /// The comparison with its operands swapped, `c < x` being `x > c`
+    pub fn flip(self) -> Self {
+        match self {
+            Self::Lt => Self::Gt,
+            Self::Le => Self::Ge,
+            Self::Gt => Self::Lt,
+            Self::Ge => Self::Le,
+            Self::Eq | Self::Neq => self,
+        }
+    }
+
+    /// The comparison holding exactly when this one doesn't
+    pub fn negate(self) -> Self {
+        match self {
+            Self::Lt => Self::Ge,
+            Self::Le => Self::Gt,
+            Self::Gt => Self::Le,
+            Self::Ge => Self::Lt,
+            Self::Eq => Self::Neq,
+            Self::Neq => Self::Eq,
+        }
+    }
+
+    pub fn holds(self, value: u128, constant: u128) -> bool {
+        match self {
+            Self::Lt => value < constant,
+            Self::Le => value <= constant,
+            Self::Gt => value > constant,
+            Self::Ge => value >= constant,
+            Self::Eq => value == constant,
+            Self::Neq => value != constant,
+        }
+    }
+}
+
+impl fmt::Display for GuardOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Neq => "!=",
+        };
+        write!(f, "{op}")
+    }
+}
+
+/// Assert-style check of a target parameter against a constant that aborts
+/// when it fails, found in the function's bytecode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guard {
+    /// Function the guard is in, keyed as in [`VisitedPcs`]
+    pub function: String,
+    /// Branch deciding the guard
+    pub pc: u16,
+    /// First instruction run when the guard passes
+    pub pass_pc: u16,
+    pub param: usize,
+    /// Condition for passing: `param <op> constant`
+    pub op: GuardOp,
+    pub constant: u128,
+}
+
+impl Guard {
+    pub fn condition(&self) -> String {
+        format!("param_{} {} {}", self.param, self.op, self.constant)
+    }
+
+    fn passed(&self, visited: &VisitedPcs) -> bool {
+        visited
+            .get(&self.function)
+            .is_some_and(|pcs| pcs.contains(&self.pass_pc))
+    }
+}
+
+/// Iterations that got past one guard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardSummary {
+    /// `function:pc` of the guard's branch
+    pub location: String,
+    pub condition: String,
+    pub passed: u64,
+}
+
+/// The guards of a campaign's target function and how often each passed
+#[derive(Debug, Clone, Default)]
+pub struct GuardTracker {
+    guards: Vec<Guard>,
+    passed: Vec<u64>,
+}
+
+impl GuardTracker {
+    pub fn new(guards: Vec<Guard>) -> Self {
+        let passed = vec![0; guards.len()];
+        Self { guards, passed }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guards.is_empty()
+    }
+
+    /// Constants the parameters are compared with, and their neighbours
+    pub fn constants(&self) -> impl Iterator<Item = u128> + '_ {
+        self.guards.iter().flat_map(|guard| {
+            [
+                guard.constant.checked_sub(1),
+                Some(guard.constant),
+                guard.constant.checked_add(1),
+            ]
+            .into_iter()
+            .flatten()
+        })
+    }
+
+    /// Count the guards an execution visiting `visited` got past
+    pub fn record(&mut self, visited: &VisitedPcs) {
+        for (guard, passed) in self.guards.iter().zip(&mut self.passed) {
+            if guard.passed(visited) {
+                *passed += 1;
+            }
+        }
+    }
+
+    /// Replace `value` of the parameter at `param` with one passing all of
+    /// its guards, unless it already does. Returns whether it was changed.
+    pub fn satisfy<V: ChainValue>(&self, param: usize, value: &mut V, rng: &mut impl Rng) -> bool {
+        let Some(current) = value.integer_value() else {
+            return false;
+        };
+        let guards: Vec<&Guard> = self.guards.iter().filter(|guard| guard.param == param).collect();
+        if guards.iter().all(|guard| guard.op.holds(current, guard.constant)) {
+            return false;
+        }
+
+        let (mut low, mut high) = (0u128, u128::MAX);
+        let mut excluded = BTreeSet::new();
+        for guard in &guards {
+            let c = guard.constant;
+            match guard.op {
+                GuardOp::Lt if c == 0 => return false,
+                GuardOp::Lt => high = high.min(c - 1),
+                GuardOp::Le => high = high.min(c),
+                GuardOp::Gt if c == u128::MAX => return false,
+                GuardOp::Gt => low = low.max(c + 1),
+                GuardOp::Ge => low = low.max(c),
+                GuardOp::Eq => (low, high) = (low.max(c), high.min(c)),
+                GuardOp::Neq => {
+                    excluded.insert(c);
+                }
+            }
+        }
+        if low > high {
+            return false;
+        }
+
+        // Values right at the bounds first, as they are the likeliest to be
+        // interesting and to fit the parameter's type
+        let span = high - low;
+        let candidates = [
+            low,
+            low.saturating_add(1).min(high),
+            high,
+            low + rng.random_range(0..=span.min(u64::MAX as u128)),
+        ];
+        let start = rng.random_range(0..candidates.len());
+        for offset in 0..candidates.len() {
+            let candidate = candidates[(start + offset) % candidates.len()];
+            if excluded.contains(&candidate) {
+                continue;
+            }
+            if let Some(satisfying) = value.with_integer_value(candidate) {
+                *value = satisfying;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn summary(&self) -> Vec<GuardSummary> {
+        self.guards
+            .iter()
+            .zip(&self.passed)
+            .map(|(guard, passed)| GuardSummary {
+                location: format!("{}:{}", guard.function, guard.pc),
+                condition: guard.condition(),
+                passed: *passed,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct U8(u8);
+
+    impl ChainValue for U8 {
+        fn is_integer(&self) -> bool {
+            true
+        }
+
+        fn is_integer_vector(&self) -> bool {
+            false
+        }
+
+        fn contains_integers(&self) -> bool {
+            true
+        }
+
+        fn is_mutable_object(&self) -> bool {
+            false
+        }
+
+        fn get_object_id(&self) -> Option<Vec<u8>> {
+            None
+        }
+
+        fn type_name(&self) -> &'static str {
+            "u8"
+        }
+
+        fn integer_value(&self) -> Option<u128> {
+            Some(self.0 as u128)
+        }
+
+        fn with_integer_value(&self, value: u128) -> Option<Self> {
+            u8::try_from(value).ok().map(U8)
+        }
+    }
+
+    fn guard(param: usize, op: GuardOp, constant: u128, pass_pc: u16) -> Guard {
+        Guard {
+            function: "0x1::pool::swap".to_string(),
+            pc: pass_pc - 1,
+            pass_pc,
+            param,
+            op,
+            constant,
+        }
+    }
+
+    #[test]
+    fn test_satisfy() {
+        let tracker = GuardTracker::new(vec![
+            guard(0, GuardOp::Gt, 10, 4),
+            guard(0, GuardOp::Neq, 11, 8),
+            guard(1, GuardOp::Lt, 0, 12),
+        ]);
+        let mut rng = rand::rng();
+
+        for _ in 0..20 {
+            let mut value = U8(3);
+            assert!(tracker.satisfy(0, &mut value, &mut rng));
+            assert!(value.0 > 11);
+        }
+        let mut value = U8(50);
+        assert!(!tracker.satisfy(0, &mut value, &mut rng));
+        // Unsatisfiable guards are left failing
+        assert!(!tracker.satisfy(1, &mut U8(3), &mut rng));
+    }
+
+    #[test]
+    fn test_record() {
+        let mut tracker = GuardTracker::new(vec![guard(0, GuardOp::Gt, 10, 4), guard(0, GuardOp::Neq, 11, 8)]);
+        let visited = VisitedPcs::from([("0x1::pool::swap".to_string(), BTreeSet::from([0, 1, 2, 3, 4, 5]))]);
+        tracker.record(&visited);
+        tracker.record(&visited);
+
+        let summary = tracker.summary();
+        assert_eq!(summary[0].passed, 2);
+        assert_eq!(summary[0].condition, "param_0 > 10");
+        assert_eq!(summary[1].passed, 0);
+    }
+}
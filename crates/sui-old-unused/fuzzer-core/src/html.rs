@@ -0,0 +1,292 @@
+use std::fmt::{self, Write};
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::coverage::CoverageSummary;
+use crate::stats::{Distribution, ExecutionStatsSummary};
+use crate::types::{FuzzingResult, FuzzingStatus, ViolationInfo};
+
+/// Line of Move source a bytecode location was compiled from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: String,
+    /// 1-based
+    pub line: usize,
+    pub text: String,
+}
+
+/// Maps bytecode locations, `module::function:pc` as in violations and
+/// guards, back to source lines
+pub trait SourceLocator {
+    fn locate(&self, location: &str) -> Option<SourceLocation>;
+}
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f0f0f0}code,pre{font-family:monospace}pre{margin:0}\
+.source{color:#555}.found{color:#b00020}.clean{color:#1b5e20}";
+
+/// Self-contained HTML report of a campaign for sharing with auditors:
+/// violations, per-function coverage and execution statistics, with
+/// locations shown as source lines when a [`SourceLocator`] is given
+#[derive(Default)]
+pub struct HtmlReporter<'a> {
+    locator: Option<&'a dyn SourceLocator>,
+}
+
+impl<'a> HtmlReporter<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source_locator(mut self, locator: &'a dyn SourceLocator) -> Self {
+        self.locator = Some(locator);
+        self
+    }
+
+    pub fn write(&self, path: &Path, result: &FuzzingResult) -> anyhow::Result<()> {
+        std::fs::write(path, self.render(result))
+            .with_context(|| format!("Failed to write HTML report to {}", path.display()))
+    }
+
+    pub fn render(&self, result: &FuzzingResult) -> String {
+        let mut html = String::new();
+        // Writing to a String can't fail
+        let _ = self.render_into(&mut html, result);
+        html
+    }
+
+    fn render_into(&self, out: &mut String, result: &FuzzingResult) -> fmt::Result {
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(
+            out,
+            "<html><head><meta charset=\"utf-8\"><title>Fuzzing report</title><style>{STYLE}</style></head><body>"
+        )?;
+        writeln!(out, "<h1>Fuzzing report</h1>")?;
+
+        let status = match &result.status {
+            FuzzingStatus::ViolationFound => format!(
+                "<span class=\"found\">{} violation(s) found</span>",
+                result.violations.len()
+            ),
+            FuzzingStatus::NoViolationFound => "<span class=\"clean\">No violations found</span>".to_string(),
+            FuzzingStatus::InProgress => "In progress".to_string(),
+            FuzzingStatus::Error(error) => format!("<span class=\"found\">Error: {}</span>", escape(error)),
+        };
+        writeln!(out, "<p>Status: {status}</p>")?;
+        writeln!(
+            out,
+            "<p>Iterations: {}/{}{}</p>",
+            result.iterations_completed,
+            result.total_iterations,
+            if result.timed_out {
+                " (time limit reached)"
+            } else if result.interrupted {
+                " (stopped)"
+            } else {
+                ""
+            }
+        )?;
+        if let Some(seed) = result.seed {
+            writeln!(out, "<p>Seed: <code>{seed}</code></p>")?;
+        }
+
+        if !result.violations.is_empty() {
+            self.render_violations(out, &result.violations)?;
+        }
+        if let Some(coverage) = &result.coverage {
+            self.render_coverage(out, coverage)?;
+        }
+        if let Some(stats) = &result.stats {
+            self.render_stats(out, stats)?;
+        }
+        if !result.guards.is_empty() {
+            writeln!(out, "<h2>Guards</h2>")?;
+            writeln!(
+                out,
+                "<table><tr><th>Location</th><th>Source</th><th>Condition</th><th>Passed</th></tr>"
+            )?;
+            for guard in &result.guards {
+                writeln!(
+                    out,
+                    "<tr><td><code>{}</code></td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                    escape(&guard.location),
+                    self.source_cell(&guard.location),
+                    escape(&guard.condition),
+                    guard.passed
+                )?;
+            }
+            writeln!(out, "</table>")?;
+        }
+
+        writeln!(out, "</body></html>")
+    }
+
+    fn render_violations(&self, out: &mut String, violations: &[ViolationInfo]) -> fmt::Result {
+        writeln!(out, "<h2>Violations</h2>")?;
+        writeln!(
+            out,
+            "<table><tr><th>#</th><th>Location</th><th>Source</th><th>Operation</th><th>Operands</th><th>Call \
+             chain</th></tr>"
+        )?;
+        for (i, violation) in violations.iter().enumerate() {
+            writeln!(
+                out,
+                "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}, {}</td><td>{}</td></tr>",
+                i + 1,
+                escape(&violation.location),
+                self.source_cell(&violation.location),
+                escape(&violation.operation),
+                violation.left_operand,
+                violation.right_operand,
+                escape(&violation.call_chain.join(" → "))
+            )?;
+        }
+        writeln!(out, "</table>")
+    }
+
+    fn render_coverage(&self, out: &mut String, coverage: &CoverageSummary) -> fmt::Result {
+        writeln!(out, "<h2>Coverage</h2>")?;
+        writeln!(
+            out,
+            "<p>{}/{} functions touched, {}/{} instructions hit ({:.1}%)</p>",
+            coverage.functions_touched,
+            coverage.functions_total,
+            coverage.instructions_hit,
+            coverage.instructions_total,
+            coverage.instruction_percentage()
+        )?;
+        writeln!(
+            out,
+            "<table><tr><th>Function</th><th>Defined at</th><th>Instructions hit</th><th>%</th></tr>"
+        )?;
+        for function in &coverage.functions {
+            let percentage = if function.instruction_count == 0 {
+                0.0
+            } else {
+                function.instructions_hit as f64 * 100.0 / function.instruction_count as f64
+            };
+            writeln!(
+                out,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}/{}</td><td>{:.1}</td></tr>",
+                escape(&function.function),
+                self.source_cell(&format!("{}:0", function.function)),
+                function.instructions_hit,
+                function.instruction_count,
+                percentage
+            )?;
+        }
+        writeln!(out, "</table>")
+    }
+
+    fn render_stats(&self, out: &mut String, stats: &ExecutionStatsSummary) -> fmt::Result {
+        if stats.failed > 0 {
+            writeln!(out, "<p>Failed executions: {}</p>", stats.failed)?;
+        }
+        if stats.samples == 0 {
+            return Ok(());
+        }
+        writeln!(out, "<h2>Execution statistics ({} samples)</h2>", stats.samples)?;
+        writeln!(
+            out,
+            "<table><tr><th></th><th>min</th><th>p50</th><th>p90</th><th>p99</th><th>max</th><th>mean</th></tr>"
+        )?;
+        distribution_row(out, "Execution time (us)", &stats.execution_time_us)?;
+        distribution_row(out, "Gas used", &stats.gas_used)?;
+        writeln!(out, "</table>")?;
+
+        if !stats.slowest_inputs.is_empty() {
+            writeln!(out, "<h3>Slowest inputs</h3>")?;
+            writeln!(
+                out,
+                "<table><tr><th>Iteration</th><th>Time (us)</th><th>Gas</th><th>Parameters</th></tr>"
+            )?;
+            for slow in &stats.slowest_inputs {
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                    slow.iteration,
+                    slow.execution_time_us,
+                    slow.gas_used,
+                    escape(&slow.parameters)
+                )?;
+            }
+            writeln!(out, "</table>")?;
+        }
+        Ok(())
+    }
+
+    fn source_cell(&self, location: &str) -> String {
+        match self.locator.and_then(|locator| locator.locate(location)) {
+            Some(source) => format!(
+                "<span class=\"source\">{}:{}</span><pre>{}</pre>",
+                escape(&source.file),
+                source.line,
+                escape(&source.text)
+            ),
+            None => String::new(),
+        }
+    }
+}
+
+fn distribution_row(out: &mut String, label: &str, distribution: &Distribution) -> fmt::Result {
+    writeln!(
+        out,
+        "<tr><th>{label}</th><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+        distribution.min, distribution.p50, distribution.p90, distribution.p99, distribution.max, distribution.mean
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Locator;
+
+    impl SourceLocator for Locator {
+        fn locate(&self, location: &str) -> Option<SourceLocation> {
+            (location == "0x1::pool::swap:12").then(|| SourceLocation {
+                file: "sources/pool.move".to_string(),
+                line: 42,
+                text: "let shares = amount << 64;".to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_render_violation_source() {
+        let result = FuzzingResult::violation_found(
+            vec![ViolationInfo {
+                location: "0x1::pool::swap:12".to_string(),
+                operation: "Shl<u64>".to_string(),
+                left_operand: 1,
+                right_operand: 64,
+                call_chain: Vec::new(),
+            }],
+            7,
+        );
+
+        let html = HtmlReporter::new().with_source_locator(&Locator).render(&result);
+        assert!(html.contains("sources/pool.move:42"));
+        assert!(html.contains("let shares = amount &lt;&lt; 64;"));
+        assert!(html.contains("Shl&lt;u64&gt;"));
+        assert!(HtmlReporter::new().render(&result).contains("0x1::pool::swap:12"));
+    }
+}
@@ -1,14 +1,33 @@
+pub mod aborts;
 pub mod cache;
 pub mod config;
+pub mod coverage;
+pub mod findings;
 pub mod fuzzer;
+pub mod guards;
+pub mod html;
+pub mod objectives;
+pub mod progress;
 pub mod reporter;
+pub mod result_cache;
+pub mod schedule;
+pub mod seeding;
+pub mod stages;
+pub mod stats;
+pub mod steering;
+pub mod sync;
 pub mod types;
+pub mod writes;
 
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use coverage::VisitedPcs;
+use guards::Guard;
 use serde::{Deserialize, Serialize};
 pub use types::*;
 
@@ -33,6 +52,23 @@ pub trait ChainValue: Clone + Debug + Send + Sync + Serialize + for<'de> Deseria
 
     /// Get the type name for debugging/logging
     fn type_name(&self) -> &'static str;
+
+    /// Integer value if this is an integer fitting in a `u128`, for
+    /// parameter ranges and value lists
+    fn integer_value(&self) -> Option<u128> {
+        None
+    }
+
+    /// Integer of the same type holding `value`, if it fits
+    fn with_integer_value(&self, _value: u128) -> Option<Self> {
+        None
+    }
+
+    /// Every value of this value's type, if it has few enough to enumerate
+    /// (`bool`, `u8`, small enums)
+    fn domain(&self) -> Option<Vec<Self>> {
+        None
+    }
 }
 
 /// Core trait for mutation strategies
@@ -43,7 +79,7 @@ pub trait ChainMutationStrategy<V: ChainValue>: Send + Sync {
 
 /// Core abstraction trait for blockchain adapters
 #[async_trait]
-pub trait ChainAdapter: Sized {
+pub trait ChainAdapter: Sized + Send + Sync {
     /// Blockchain-specific value type (e.g., CloneableValue for Sui)
     type Value: ChainValue;
 
@@ -64,8 +100,9 @@ pub trait ChainAdapter: Sized {
 
     // === Initialization Interface ===
 
-    /// Create a chain-specific mutation strategy
-    fn create_mutator(&self) -> Self::Mutator;
+    /// Create a chain-specific mutation strategy whose random choices all
+    /// derive from `seed`
+    fn create_mutator(&self, seed: u64) -> Self::Mutator;
 
     /// Resolve function information from the given configuration
     async fn resolve_function(&self, config: &FuzzerConfig) -> Result<FunctionInfo>;
@@ -103,16 +140,68 @@ pub trait ChainAdapter: Sized {
 
     // === Result Analysis Interface ===
 
-    /// Check if the execution result contains shift violations
-    fn has_shift_violations(&self, result: &Self::ExecutionResult) -> bool;
+    /// Check if the execution result contains any finding
+    /// [`ChainAdapter::extract_violations`] reports: shift violations and
+    /// the adapter's other tracer and oracle findings
+    fn has_violations(&self, result: &Self::ExecutionResult) -> bool;
 
     /// Extract violation information from the execution result
     fn extract_violations(&self, result: &Self::ExecutionResult) -> Vec<ViolationInfo>;
 
+    /// Wall-clock time spent executing the transaction
+    fn execution_time(&self, result: &Self::ExecutionResult) -> Duration;
+
+    /// Gas consumed by the execution. Adapters running calls unmetered
+    /// return their stand-in for it, see their implementations.
+    fn gas_used(&self, result: &Self::ExecutionResult) -> u64;
+
+    // === Coverage Interface ===
+
+    /// Instruction count of every function in the target package, keyed the
+    /// same way as [`ChainAdapter::visited_pcs`]
+    async fn function_instruction_counts(&self, _function: &FunctionInfo) -> Result<BTreeMap<String, usize>> {
+        Ok(BTreeMap::new())
+    }
+
+    /// PCs visited per function during the execution
+    fn visited_pcs(&self, _result: &Self::ExecutionResult) -> VisitedPcs {
+        VisitedPcs::new()
+    }
+
+    /// Where the execution aborted, such as `module::code`, if it did;
+    /// inputs ending on rarely hit paths get more mutations
+    fn abort_path(&self, _result: &Self::ExecutionResult) -> Option<String> {
+        None
+    }
+
+    /// Assert-style checks of the function's parameters against constants,
+    /// for steering inputs past them
+    async fn parameter_guards(&self, _function: &FunctionInfo) -> Result<Vec<Guard>> {
+        Ok(Vec::new())
+    }
+
     /// Extract object changes from the execution result for cache updates
     fn extract_object_changes(&self, result: &Self::ExecutionResult)
         -> Vec<ObjectChange<Self::ObjectId, Self::Object>>;
 
     /// Get the sender address from the configuration
     fn get_sender_from_config(&self, config: &FuzzerConfig) -> Self::Address;
+
+    /// Version of the target package, recorded with its findings
+    async fn package_version(&self, _function: &FunctionInfo) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Usage of the RPC endpoints so far, reported with the result
+    fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        Vec::new()
+    }
+
+    // === Seeding Interface ===
+
+    /// Arguments of up to `limit` recent successful on-chain calls of the
+    /// function, latest first, in the format of [`FuzzerConfig::args`]
+    async fn historical_args(&self, _function: &FunctionInfo, _limit: usize) -> Result<Vec<Vec<String>>> {
+        Ok(Vec::new())
+    }
 }
@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::ViolationInfo;
+
+/// Kind of finding, told apart by its operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectiveClass {
+    /// Shift losing high-order bits
+    Shift,
+    /// Truncating integer cast
+    Cast,
+    PrecisionLoss,
+    /// Invariant function aborting after the target call
+    Invariant,
+    /// Abort the abort policy doesn't expect, or an arithmetic error
+    Abort,
+    /// Outcome differing from a compared package or chain
+    Divergence,
+    /// Return values a registered oracle rejects
+    Oracle,
+    /// Write to a type the write policy doesn't allow
+    UnexpectedWrite,
+    /// Value created or destroyed outside mints, burns and fee sinks
+    Conservation,
+    /// Racing call on shared objects diverging from its correctly versioned
+    /// replay
+    VersionRace,
+    SlowExecution,
+}
+
+impl ObjectiveClass {
+    pub fn of(violation: &ViolationInfo) -> Self {
+        let operation = violation.operation.as_str();
+        if operation == "SlowExecution" {
+            Self::SlowExecution
+        } else if operation == "PrecisionLoss" {
+            Self::PrecisionLoss
+        } else if operation.starts_with("InvariantAbort") {
+            Self::Invariant
+        } else if operation.starts_with("Abort(") || operation == "ArithmeticError" {
+            Self::Abort
+        } else if operation.starts_with("OracleViolation") {
+            Self::Oracle
+        } else if operation.starts_with("UnexpectedWrite") {
+            Self::UnexpectedWrite
+        } else if operation.starts_with("ValueCreated") || operation.starts_with("ValueDestroyed") {
+            Self::Conservation
+        } else if operation.starts_with("VersionRace") {
+            Self::VersionRace
+        } else if operation.contains("Divergence") {
+            Self::Divergence
+        } else if operation.starts_with("Cast") {
+            Self::Cast
+        } else {
+            Self::Shift
+        }
+    }
+}
+
+impl fmt::Display for ObjectiveClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shift => write!(f, "shift"),
+            Self::Cast => write!(f, "cast"),
+            Self::PrecisionLoss => write!(f, "precision_loss"),
+            Self::Invariant => write!(f, "invariant"),
+            Self::Abort => write!(f, "abort"),
+            Self::Divergence => write!(f, "divergence"),
+            Self::Oracle => write!(f, "oracle"),
+            Self::UnexpectedWrite => write!(f, "unexpected_write"),
+            Self::Conservation => write!(f, "conservation"),
+            Self::VersionRace => write!(f, "version_race"),
+            Self::SlowExecution => write!(f, "slow_execution"),
+        }
+    }
+}
+
+/// What a finding of a class does to the campaign
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectiveAction {
+    /// Recorded, and the campaign stops once enough were found
+    Stop,
+    /// Recorded while the campaign goes on
+    Record,
+    /// Dropped
+    Ignore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Objective {
+    pub action: ObjectiveAction,
+    /// Distinct findings of the class a `stop` waits for
+    #[serde(default = "Objective::default_after")]
+    pub after: usize,
+}
+
+impl Objective {
+    fn default_after() -> usize {
+        1
+    }
+}
+
+/// Per-class handling of findings, loaded from the `objectives` section of a
+/// TOML or JSON file. Unlisted classes stop the campaign on their first
+/// finding, or are only recorded with `keep_going`.
+///
+/// ```toml
+/// [objectives.invariant]
+/// action = "stop"
+///
+/// [objectives.abort]
+/// action = "stop"
+/// after = 3
+///
+/// [objectives.shift]
+/// action = "record"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectivePolicy {
+    pub objectives: BTreeMap<ObjectiveClass, Objective>,
+}
+
+impl ObjectivePolicy {
+    /// Load a policy from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read objectives {}", path.display()))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                toml::from_str(&content).with_context(|| format!("Failed to parse objectives {}", path.display()))
+            }
+            Some("json") => {
+                serde_json::from_str(&content).with_context(|| format!("Failed to parse objectives {}", path.display()))
+            }
+            _ => bail!("Unsupported objectives format: {}", path.display()),
+        }
+    }
+
+    pub fn objective(&self, class: ObjectiveClass, keep_going: bool) -> Objective {
+        self.objectives.get(&class).copied().unwrap_or(Objective {
+            action: if keep_going {
+                ObjectiveAction::Record
+            } else {
+                ObjectiveAction::Stop
+            },
+            after: 1,
+        })
+    }
+
+    pub fn is_ignored(&self, violation: &ViolationInfo, keep_going: bool) -> bool {
+        self.objective(ObjectiveClass::of(violation), keep_going).action == ObjectiveAction::Ignore
+    }
+
+    /// Class whose stop condition the distinct `violations` found so far
+    /// meet, if any
+    pub fn stop_reached(&self, violations: &[ViolationInfo], keep_going: bool) -> Option<ObjectiveClass> {
+        let mut counts = BTreeMap::new();
+        for violation in violations {
+            *counts.entry(ObjectiveClass::of(violation)).or_insert(0) += 1;
+        }
+        counts.into_iter().find_map(|(class, count)| {
+            let objective = self.objective(class, keep_going);
+            (objective.action == ObjectiveAction::Stop && count >= objective.after).then_some(class)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(operation: &str) -> ViolationInfo {
+        ViolationInfo {
+            location: "pool::swap:4".to_string(),
+            operation: operation.to_string(),
+            left_operand: 0,
+            right_operand: 0,
+            call_chain: vec![],
+        }
+    }
+
+    #[test]
+    fn test_stop_reached() {
+        let policy: ObjectivePolicy = toml::from_str(
+            r#"
+            [objectives.invariant]
+            action = "stop"
+
+            [objectives.abort]
+            action = "stop"
+            after = 2
+
+            [objectives.shift]
+            action = "record"
+
+            [objectives.precision_loss]
+            action = "ignore"
+            "#,
+        )
+        .unwrap();
+
+        let shifts = [violation("Shl"), violation("Shr")];
+        assert_eq!(policy.stop_reached(&shifts, false), None);
+        assert!(policy.is_ignored(&violation("PrecisionLoss"), false));
+
+        let aborts = [violation("Abort(3, bug)")];
+        assert_eq!(policy.stop_reached(&aborts, false), None);
+        let aborts = [violation("Abort(3, bug)"), violation("ArithmeticError")];
+        assert_eq!(policy.stop_reached(&aborts, false), Some(ObjectiveClass::Abort));
+        assert_eq!(
+            policy.stop_reached(&[violation("InvariantAbort(1)")], false),
+            Some(ObjectiveClass::Invariant)
+        );
+        assert_eq!(
+            policy.stop_reached(&[violation("OracleViolation(solvent)")], false),
+            Some(ObjectiveClass::Oracle)
+        );
+
+        // Unlisted classes follow keep_going
+        let casts = [violation("CastU8")];
+        assert_eq!(policy.stop_reached(&casts, false), Some(ObjectiveClass::Cast));
+        assert_eq!(policy.stop_reached(&casts, true), None);
+    }
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::coverage::CoverageSummary;
+use crate::types::{EndpointStats, ViolationInfo};
+
+/// Snapshot of a running campaign, published by [`CoreFuzzer`] for
+/// dashboards to follow it
+///
+/// [`CoreFuzzer`]: crate::fuzzer::CoreFuzzer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Progress {
+    /// Iterations whose results came back
+    pub iterations: u64,
+    pub total_iterations: u64,
+    pub elapsed: Duration,
+    pub corpus_size: usize,
+    /// Distinct violations recorded so far
+    pub violations: usize,
+    /// `operation at location` of the violation recorded last
+    pub last_violation: Option<String>,
+    pub coverage: Option<CoverageSummary>,
+    /// Usage of the adapter's RPC endpoints, see
+    /// [`ChainAdapter::endpoint_stats`]
+    ///
+    /// [`ChainAdapter::endpoint_stats`]: crate::ChainAdapter::endpoint_stats
+    pub endpoints: Vec<EndpointStats>,
+    /// Whether the campaign ended; nothing is published after this one
+    pub finished: bool,
+}
+
+impl Progress {
+    pub fn execs_per_sec(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.iterations as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Mean latency of the requests to every endpoint, if any were sent
+    pub fn rpc_latency(&self) -> Option<Duration> {
+        let requests: u64 = self.endpoints.iter().map(|endpoint| endpoint.requests).sum();
+        let total_ms: u64 = self
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.mean_latency_ms * endpoint.requests)
+            .sum();
+        total_ms.checked_div(requests).map(Duration::from_millis)
+    }
+
+    pub fn describe_violation(violation: &ViolationInfo) -> String {
+        format!("{} at {}", violation.operation, violation.location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(requests: u64, mean_latency_ms: u64) -> EndpointStats {
+        EndpointStats {
+            url: "http://localhost:8080".to_string(),
+            requests,
+            failures: 0,
+            mean_latency_ms,
+            healthy: true,
+        }
+    }
+
+    #[test]
+    fn test_rates_and_latency() {
+        let mut progress = Progress::default();
+        assert_eq!(progress.execs_per_sec(), 0.0);
+        assert_eq!(progress.rpc_latency(), None);
+
+        progress.iterations = 500;
+        progress.elapsed = Duration::from_secs(2);
+        progress.endpoints = vec![endpoint(3, 10), endpoint(1, 50), endpoint(0, 0)];
+        assert_eq!(progress.execs_per_sec(), 250.0);
+        assert_eq!(progress.rpc_latency(), Some(Duration::from_millis(20)));
+    }
+}
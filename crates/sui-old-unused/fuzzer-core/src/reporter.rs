@@ -1,7 +1,9 @@
 use std::io::{self, Write};
 use std::time::Duration;
 
-use crate::types::{FunctionInfo, FuzzingResult, FuzzingStatus, Parameter};
+use crate::coverage::CoverageSummary;
+use crate::stats::ExecutionStatsSummary;
+use crate::types::{EndpointStats, FunctionInfo, FuzzingResult, FuzzingStatus, Parameter};
 use crate::ChainValue;
 
 /// Console reporter for fuzzing results
@@ -72,8 +74,25 @@ impl ConsoleReporter {
                     println!("  Operation: {}", violation.operation);
                     println!("  Left operand: {}", violation.left_operand);
                     println!("  Right operand: {}", violation.right_operand);
+                    if !violation.call_chain.is_empty() {
+                        println!("  Call chain: {}", violation.call_chain.join(" -> "));
+                    }
                 }
             }
+            FuzzingStatus::NoViolationFound if result.timed_out => {
+                println!("✅ STATUS: NO VIOLATIONS FOUND");
+                println!(
+                    "Stopped at the time limit after {} of {} iterations without detecting violations",
+                    result.iterations_completed, result.total_iterations
+                );
+            }
+            FuzzingStatus::NoViolationFound if result.interrupted => {
+                println!("✅ STATUS: NO VIOLATIONS FOUND");
+                println!(
+                    "Stopped after {} of {} iterations without detecting violations",
+                    result.iterations_completed, result.total_iterations
+                );
+            }
             FuzzingStatus::NoViolationFound => {
                 println!("✅ STATUS: NO VIOLATIONS FOUND");
                 println!(
@@ -95,10 +114,120 @@ impl ConsoleReporter {
             result.iterations_completed, result.total_iterations
         );
 
+        if let Some(size) = result.corpus_size {
+            println!("Corpus: {} inputs", size);
+        }
+
+        if let Some(stats) = &result.stats {
+            self.print_execution_stats(stats)?;
+        }
+
+        if let Some(coverage) = &result.coverage {
+            self.print_coverage_summary(coverage)?;
+        }
+
+        if let Some(size) = result.input_space {
+            println!("\nInput space fully covered: all {} inputs ran", size);
+        }
+
+        if let Some(seed) = result.seed {
+            println!("\nSeed: {} (rerun with it to reproduce)", seed);
+        }
+
+        if !result.guards.is_empty() {
+            println!("\nGuards passed:");
+            for guard in &result.guards {
+                println!("  {} ({}): {}", guard.location, guard.condition, guard.passed);
+            }
+        }
+
+        if result.endpoints.len() > 1 {
+            self.print_endpoint_stats(&result.endpoints)?;
+        }
+
+        if let Some(cache) = &result.result_cache {
+            println!(
+                "\nResult cache: {} repeated inputs not simulated ({:.1}% of {})",
+                cache.hits,
+                cache.hit_percentage(),
+                cache.hits + cache.misses
+            );
+        }
+
         println!("\n{}", "=".repeat(80));
         Ok(())
     }
 
+    pub fn print_coverage_summary(&self, coverage: &CoverageSummary) -> anyhow::Result<()> {
+        println!(
+            "\nCoverage: {}/{} functions touched, {}/{} instructions hit ({:.1}%)",
+            coverage.functions_touched,
+            coverage.functions_total,
+            coverage.instructions_hit,
+            coverage.instructions_total,
+            coverage.instruction_percentage()
+        );
+
+        for function in &coverage.functions {
+            println!(
+                "  {}: {}/{}",
+                function.function, function.instructions_hit, function.instruction_count
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn print_endpoint_stats(&self, endpoints: &[EndpointStats]) -> anyhow::Result<()> {
+        println!("\nRPC endpoints:");
+        for endpoint in endpoints {
+            println!(
+                "  {}: {} requests, {} failed, {}ms mean latency{}",
+                endpoint.url,
+                endpoint.requests,
+                endpoint.failures,
+                endpoint.mean_latency_ms,
+                if endpoint.healthy { "" } else { " (unhealthy)" }
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn print_execution_stats(&self, stats: &ExecutionStatsSummary) -> anyhow::Result<()> {
+        if stats.failed > 0 {
+            println!("\nFailed executions: {}", stats.failed);
+        }
+        if stats.samples == 0 {
+            return Ok(());
+        }
+
+        let time = &stats.execution_time_us;
+        let gas = &stats.gas_used;
+
+        println!("\nExecution statistics ({} samples):", stats.samples);
+        println!(
+            "  Execution time (us): min {} / p50 {} / p90 {} / p99 {} / max {} / mean {}",
+            time.min, time.p50, time.p90, time.p99, time.max, time.mean
+        );
+        println!(
+            "  Gas used:            min {} / p50 {} / p90 {} / p99 {} / max {} / mean {}",
+            gas.min, gas.p50, gas.p90, gas.p99, gas.max, gas.mean
+        );
+
+        if !stats.slowest_inputs.is_empty() {
+            println!("\nSlowest inputs:");
+            for slow in &stats.slowest_inputs {
+                println!(
+                    "  #{}: {}us, gas {} - {}",
+                    slow.iteration, slow.execution_time_us, slow.gas_used, slow.parameters
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn print_function_info<V: ChainValue>(
         &self,
         function: &FunctionInfo,
@@ -0,0 +1,94 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+
+use crate::{ChainValue, Parameter};
+
+/// Lookups of a campaign's result cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultCacheStats {
+    /// Inputs seen before, whose simulation was skipped
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl ResultCacheStats {
+    pub fn hit_percentage(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / lookups as f64 * 100.0
+    }
+}
+
+/// Execution results of recent inputs, keyed by a hash of the parameters.
+/// Mutations keep producing the same boundary values, so exact repeats are
+/// common and their simulation can be skipped.
+pub struct ResultCache<R> {
+    entries: LruCache<u64, R>,
+    stats: ResultCacheStats,
+}
+
+impl<R: Clone> ResultCache<R> {
+    /// Cache of up to `capacity` results, `None` if it is 0
+    pub fn new(capacity: usize) -> Option<Self> {
+        Some(Self {
+            entries: LruCache::new(NonZeroUsize::new(capacity)?),
+            stats: ResultCacheStats::default(),
+        })
+    }
+
+    /// Hash of the parameters, including the state of any object they hold
+    pub fn key<V: ChainValue>(parameters: &[Parameter<V>]) -> Option<u64> {
+        let bytes = serde_json::to_vec(parameters).ok()?;
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    pub fn get(&mut self, key: u64) -> Option<R> {
+        match self.entries.get(&key) {
+            Some(result) => {
+                self.stats.hits += 1;
+                Some(result.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: u64, result: R) {
+        self.entries.put(key, result);
+    }
+
+    pub fn stats(&self) -> ResultCacheStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_cache() {
+        assert!(ResultCache::<u64>::new(0).is_none());
+
+        let mut cache = ResultCache::new(1).unwrap();
+        assert_eq!(cache.get(1), None);
+        cache.insert(1, 10);
+        assert_eq!(cache.get(1), Some(10));
+        cache.insert(2, 20);
+        assert_eq!(cache.get(1), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+        assert!((stats.hit_percentage() - 100.0 / 3.0).abs() < 1e-9);
+    }
+}
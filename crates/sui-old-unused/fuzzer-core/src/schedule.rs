@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Mutations an entry gets per turn, before scaling
+const BASE_ENERGY: f64 = 16.0;
+
+/// Bounds of an entry's energy per turn
+const MIN_ENERGY: u32 = 1;
+const MAX_ENERGY: u32 = 256;
+
+/// Iterations an entry counts as recent for after it was added
+const RECENT_ITERATIONS: u64 = 5_000;
+
+/// Entries kept at most; the least promising are evicted beyond it
+const MAX_ENTRIES: usize = 1_024;
+
+/// Input kept in the corpus, with what its energy is computed from
+#[derive(Debug, Clone)]
+pub struct CorpusEntry<T> {
+    pub input: T,
+    /// Iteration it was added in
+    pub added_at: u64,
+    /// PCs it was the first to cover
+    pub new_pcs: usize,
+    pub exec_time: Duration,
+    /// Where it aborted, if it did, as given by
+    /// [`ChainAdapter::abort_path`](crate::ChainAdapter::abort_path)
+    pub abort_path: Option<String>,
+    /// Turns it got so far
+    pub turns: u64,
+}
+
+/// Picks the corpus entry each input is mutated from, in turns like AFL's
+/// queue cycles. An entry's turn lasts as many mutations as its energy,
+/// which is higher for entries that are recent, covered more new code, run
+/// fast or end on a rarely hit abort path, and decays with its turns.
+pub struct PowerScheduler<T> {
+    entries: Vec<CorpusEntry<T>>,
+    /// Executions ending on each path, `None` being success
+    path_hits: HashMap<Option<String>, u64>,
+    current: usize,
+    /// Mutations left in the current entry's turn
+    energy: u32,
+}
+
+impl<T> PowerScheduler<T> {
+    /// Corpus holding only the campaign's initial input
+    pub fn new(initial: T) -> Self {
+        Self {
+            entries: vec![CorpusEntry {
+                input: initial,
+                added_at: 0,
+                new_pcs: 0,
+                exec_time: Duration::ZERO,
+                abort_path: None,
+                turns: 0,
+            }],
+            path_hits: HashMap::new(),
+            current: 0,
+            energy: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Count an execution ending on `abort_path`
+    pub fn record_path(&mut self, abort_path: Option<&str>) {
+        *self.path_hits.entry(abort_path.map(str::to_string)).or_default() += 1;
+    }
+
+    /// Keep an input that covered `new_pcs` new PCs
+    pub fn add(&mut self, input: T, iteration: u64, new_pcs: usize, exec_time: Duration, abort_path: Option<String>) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.evict(iteration);
+        }
+        self.entries.push(CorpusEntry {
+            input,
+            added_at: iteration,
+            new_pcs,
+            exec_time,
+            abort_path,
+            turns: 0,
+        });
+    }
+
+    /// Entry the next input is mutated from, starting the next entry's turn
+    /// once the current one's energy is spent
+    pub fn next(&mut self, iteration: u64) -> &CorpusEntry<T> {
+        if self.energy == 0 {
+            self.current = (self.current + 1) % self.entries.len();
+            self.energy = self.energy_of(&self.entries[self.current], iteration);
+            self.entries[self.current].turns += 1;
+        }
+        self.energy -= 1;
+        &self.entries[self.current]
+    }
+
+    /// Mutations `entry` gets in its next turn
+    pub fn energy_of(&self, entry: &CorpusEntry<T>, iteration: u64) -> u32 {
+        let count = self.entries.len() as f64;
+        let mean_time = self
+            .entries
+            .iter()
+            .map(|entry| entry.exec_time.as_secs_f64())
+            .sum::<f64>() /
+            count;
+        let mean_pcs = self.entries.iter().map(|entry| entry.new_pcs as f64).sum::<f64>() / count;
+
+        let speed = if entry.exec_time.is_zero() || mean_time == 0.0 {
+            1.0
+        } else {
+            (mean_time / entry.exec_time.as_secs_f64()).clamp(0.25, 3.0)
+        };
+        let coverage = ((1.0 + entry.new_pcs as f64) / (1.0 + mean_pcs)).clamp(0.5, 4.0);
+        let recency = if iteration.saturating_sub(entry.added_at) < RECENT_ITERATIONS {
+            2.0
+        } else {
+            1.0
+        };
+        let rarity = match self.path_hits.get(&entry.abort_path) {
+            Some(&hits) if hits > 0 => {
+                let mean_hits = self.path_hits.values().sum::<u64>() as f64 / self.path_hits.len() as f64;
+                (mean_hits / hits as f64).clamp(0.5, 4.0)
+            }
+            _ => 1.0,
+        };
+        let decay = 1.0 + (1.0 + entry.turns as f64).log2();
+
+        let energy = BASE_ENERGY * speed * coverage * recency * rarity / decay;
+        (energy as u32).clamp(MIN_ENERGY, MAX_ENERGY)
+    }
+
+    /// Drop the entry with the least energy, other than the current one
+    fn evict(&mut self, iteration: u64) {
+        let Some(index) = (0..self.entries.len())
+            .filter(|&index| index != self.current)
+            .min_by_key(|&index| self.energy_of(&self.entries[index], iteration))
+        else {
+            return;
+        };
+        self.entries.remove(index);
+        if index < self.current {
+            self.current -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energy() {
+        let mut scheduler = PowerScheduler::new(0);
+        let ms = Duration::from_millis;
+        scheduler.add(1, 10, 4, ms(10), None);
+        scheduler.add(2, 10, 4, ms(1), None);
+        scheduler.add(3, 10, 4, ms(10), Some("pool::7".to_string()));
+        for _ in 0..99 {
+            scheduler.record_path(None);
+        }
+        scheduler.record_path(Some("pool::7"));
+
+        let energy = |index: usize| scheduler.energy_of(&scheduler.entries[index], 100);
+        // Faster and rarer entries get more
+        assert!(energy(2) > energy(1));
+        assert!(energy(3) > energy(1));
+        // So do recent ones
+        assert!(energy(1) > scheduler.energy_of(&scheduler.entries[1], 100_000));
+
+        // The first turn is the next entry's, lasting its energy
+        let turn = energy(1);
+        for _ in 0..turn {
+            assert_eq!(scheduler.next(100).input, 1);
+        }
+        assert_eq!(scheduler.next(100).input, 2);
+        assert_eq!(scheduler.entries[1].turns, 1);
+    }
+}
@@ -0,0 +1,363 @@
+use std::fs;
+use std::path::Path;
+
+/// Integer suffixes of Move literals, longest first
+const INTEGER_SUFFIXES: [&str; 6] = ["u128", "u256", "u16", "u32", "u64", "u8"];
+
+/// A literal argument found at a call site in Move source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveLiteral {
+    /// Integer of any width; literals wider than `u128` are skipped
+    Integer(u128),
+    Bool(bool),
+    /// Numeric address written as `@0x..`
+    Address(String),
+    /// `b"..."` or `x"..."` byte string
+    Bytes(Vec<u8>),
+    /// `vector[..]` of literals
+    Vector(Vec<MoveLiteral>),
+}
+
+impl MoveLiteral {
+    /// The literal in the argument format of
+    /// [`FuzzerConfig::args`](crate::FuzzerConfig::args)
+    pub fn to_arg_string(&self) -> String {
+        match self {
+            Self::Integer(value) => value.to_string(),
+            Self::Bool(value) => value.to_string(),
+            Self::Address(address) => address.clone(),
+            Self::Bytes(bytes) => format!(
+                "[{}]",
+                bytes.iter().map(|byte| byte.to_string()).collect::<Vec<_>>().join(",")
+            ),
+            Self::Vector(items) => format!(
+                "[{}]",
+                items.iter().map(Self::to_arg_string).collect::<Vec<_>>().join(",")
+            ),
+        }
+    }
+}
+
+/// Arguments of every call to `module::function` in the `.move` files under
+/// `dir`, such as a package's sources and tests, with `None` for arguments
+/// that aren't literals. Unqualified calls count in the files declaring
+/// `module`.
+pub fn scan_call_literals(dir: &Path, module: &str, function: &str) -> Vec<Vec<Option<MoveLiteral>>> {
+    let mut calls = Vec::new();
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return calls;
+    };
+    let mut paths: Vec<_> = read_dir.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            // Dependencies and compiled output aren't the package's own code
+            if path.file_name().is_some_and(|name| name == "build") {
+                continue;
+            }
+            calls.extend(scan_call_literals(&path, module, function));
+        } else if path.extension().is_some_and(|ext| ext == "move") {
+            if let Ok(source) = fs::read_to_string(&path) {
+                calls.extend(call_literals(&source, module, function));
+            }
+        }
+    }
+    calls
+}
+
+/// Arguments of the calls to `module::function` in one Move source file
+pub fn call_literals(source: &str, module: &str, function: &str) -> Vec<Vec<Option<MoveLiteral>>> {
+    let code = strip_comments(source);
+    let declares_module = declares_module(&code, module);
+    let bytes = code.as_bytes();
+
+    let mut calls = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = code[from..].find(function) {
+        let start = from + offset;
+        let end = start + function.len();
+        from = end;
+        if start > 0 && is_ident_byte(bytes[start - 1]) || end < bytes.len() && is_ident_byte(bytes[end]) {
+            continue;
+        }
+
+        let before = code[..start].trim_end();
+        let qualified = match before.strip_suffix("::") {
+            Some(path) => {
+                let path = path.trim_end();
+                let qualifier_start = path.rfind(|c: char| !is_ident_byte(c as u8)).map_or(0, |i| i + 1);
+                if &path[qualifier_start..] != module {
+                    continue;
+                }
+                true
+            }
+            None => false,
+        };
+        // Skip the definition and method-style calls, whose receiver is an
+        // argument not written in the parentheses
+        if !qualified && (!declares_module || before.ends_with("fun") || before.ends_with('.')) {
+            continue;
+        }
+
+        let Some(args) = call_arguments(&code[end..]) else {
+            continue;
+        };
+        calls.push(args.iter().map(|arg| parse_literal(arg)).collect());
+    }
+    calls
+}
+
+fn is_ident_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// `source` with comments blanked out, keeping byte strings intact
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Whether the code declares `module <address>::<name>` or `module <name>`
+fn declares_module(code: &str, name: &str) -> bool {
+    code.split("module").skip(1).any(|rest| {
+        let path: String = rest
+            .trim_start()
+            .chars()
+            .take_while(|c| is_ident_byte(*c as u8) || *c == ':')
+            .collect();
+        path.rsplit("::").next() == Some(name)
+    })
+}
+
+/// Top-level arguments of the call whose name `rest` follows, after optional
+/// type arguments
+fn call_arguments(rest: &str) -> Option<Vec<String>> {
+    let mut rest = rest.trim_start();
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        let close = rest.find(|c| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        })?;
+        rest = rest[close + 1..].trim_start();
+    }
+    let inner = rest.strip_prefix('(')?;
+    let args = split_top_level(inner, ')')?;
+    Some(args.into_iter().filter(|arg| !arg.is_empty()).collect())
+}
+
+/// Split `s` at commas outside brackets and strings up to the unmatched
+/// `close`
+fn split_top_level(s: &str, close: char) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if in_string {
+            current.push(c);
+            match c {
+                '\\' => current.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            c if c == close && depth == 0 => {
+                args.push(current.trim().to_string());
+                return Some(args);
+            }
+            ')' | ']' | '}' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                args.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    None
+}
+
+/// The literal an argument expression is, if it is one
+pub fn parse_literal(arg: &str) -> Option<MoveLiteral> {
+    let arg = arg.trim();
+    match arg {
+        "true" => return Some(MoveLiteral::Bool(true)),
+        "false" => return Some(MoveLiteral::Bool(false)),
+        _ => {}
+    }
+    if let Some(address) = arg.strip_prefix('@') {
+        let digits = address.strip_prefix("0x")?;
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        return Some(MoveLiteral::Address(address.to_string()));
+    }
+    if let Some(string) = arg.strip_prefix("b\"").and_then(|s| s.strip_suffix('"')) {
+        return unescape(string).map(MoveLiteral::Bytes);
+    }
+    if let Some(string) = arg.strip_prefix("x\"").and_then(|s| s.strip_suffix('"')) {
+        return hex::decode(string).ok().map(MoveLiteral::Bytes);
+    }
+    if let Some(rest) = arg.strip_prefix("vector") {
+        let rest = rest.trim_start();
+        let rest = match rest.strip_prefix('<') {
+            Some(typed) => &typed[typed.find('>')? + 1..],
+            None => rest,
+        };
+        let inner = rest.trim_start().strip_prefix('[')?;
+        let items = split_top_level(inner, ']')?;
+        return items
+            .iter()
+            .filter(|item| !item.is_empty())
+            .map(|item| parse_literal(item))
+            .collect::<Option<Vec<_>>>()
+            .map(MoveLiteral::Vector);
+    }
+    parse_integer(arg).map(MoveLiteral::Integer)
+}
+
+fn parse_integer(arg: &str) -> Option<u128> {
+    let digits = INTEGER_SUFFIXES
+        .iter()
+        .find_map(|suffix| arg.strip_suffix(suffix))
+        .unwrap_or(arg)
+        .replace('_', "");
+    match digits.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16).ok(),
+        None if digits.starts_with(|c: char| c.is_ascii_digit()) => digits.parse().ok(),
+        None => None,
+    }
+}
+
+/// Bytes of a `b"..."` string body
+fn unescape(string: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next()? {
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            't' => bytes.push(b'\t'),
+            '0' => bytes.push(0),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+            }
+            escaped => bytes.push(escaped as u8),
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+        module 0x42::pool {
+            public entry fun swap(account: &signer, amount_in: u64, min_out: u64) {}
+
+            #[test(account = @0x42)]
+            fun test_swap(account: &signer) {
+                swap(account, 1_000u64, 0x10); // swap(account, 1, 2)
+                pool::swap(account, 5, x"ff");
+                other::swap(account, 7, 8);
+                /* swap(account, 9, 9) */
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_call_literals() {
+        let calls = call_literals(SOURCE, "pool", "swap");
+        assert_eq!(
+            calls,
+            vec![
+                vec![None, Some(MoveLiteral::Integer(1_000)), Some(MoveLiteral::Integer(16))],
+                vec![
+                    None,
+                    Some(MoveLiteral::Integer(5)),
+                    Some(MoveLiteral::Bytes(vec![0xff]))
+                ],
+            ]
+        );
+        // Unqualified calls only count inside the module
+        assert!(call_literals("fun f() { swap(1, 2); }", "pool", "swap").is_empty());
+    }
+
+    #[test]
+    fn test_parse_literal() {
+        assert_eq!(parse_literal("true"), Some(MoveLiteral::Bool(true)));
+        assert_eq!(
+            parse_literal("@0xcafe"),
+            Some(MoveLiteral::Address("0xcafe".to_string()))
+        );
+        assert_eq!(parse_literal("@admin"), None);
+        assert_eq!(parse_literal("b\"hi\\n\""), Some(MoveLiteral::Bytes(b"hi\n".to_vec())));
+        assert_eq!(
+            parse_literal("vector<u64>[1, 2u64]"),
+            Some(MoveLiteral::Vector(vec![
+                MoveLiteral::Integer(1),
+                MoveLiteral::Integer(2)
+            ]))
+        );
+        assert_eq!(parse_literal("vector[amount]"), None);
+        assert_eq!(parse_literal("amount"), None);
+        assert_eq!(MoveLiteral::Bytes(vec![1, 2]).to_arg_string(), "[1,2]");
+    }
+}
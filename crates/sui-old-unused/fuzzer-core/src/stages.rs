@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{ChainValue, Parameter};
+
+/// Phase of a campaign's inputs, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    /// Each integer parameter set to each boundary of its type
+    Boundary,
+    /// Each integer parameter set to each dictionary value
+    Dictionary,
+    /// Random mutations of corpus entries, for the rest of the campaign
+    Havoc,
+}
+
+/// Executions the deterministic stages may use at most, so a function with
+/// many parameters still gets to havoc
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageBudgets {
+    pub boundary: u64,
+    pub dictionary: u64,
+}
+
+impl Default for StageBudgets {
+    fn default() -> Self {
+        Self {
+            boundary: 2_000,
+            dictionary: 2_000,
+        }
+    }
+}
+
+/// Small values, and the values around the sign bit and the maximum of
+/// every integer width
+fn boundaries() -> Vec<u128> {
+    let mut values = vec![0, 1, 2];
+    for bits in [8, 16, 32, 64] {
+        let half = 1u128 << (bits - 1);
+        let max = (1u128 << bits) - 1;
+        values.extend([half - 1, half, max - 1, max]);
+    }
+    let half = 1u128 << 127;
+    values.extend([half - 1, half, u128::MAX - 1, u128::MAX]);
+    values
+}
+
+/// Deterministic inputs run before havoc. Each sweeps one parameter over
+/// the stage's values while the others keep their initial values.
+pub struct StagedInputs<V: ChainValue> {
+    base: Vec<Parameter<V>>,
+    /// Positions of the parameters swept
+    swept: Vec<usize>,
+    boundaries: Vec<u128>,
+    dictionary: Vec<u128>,
+    budgets: StageBudgets,
+    stage: Stage,
+    /// Next parameter and value of the stage
+    param: usize,
+    value: usize,
+    /// Inputs of the stage so far
+    executions: u64,
+}
+
+impl<V: ChainValue> StagedInputs<V> {
+    /// Sweep the integer parameters at `swept` of `base`
+    pub fn new(base: Vec<Parameter<V>>, swept: Vec<usize>, mut dictionary: Vec<u128>, budgets: StageBudgets) -> Self {
+        dictionary.sort_unstable();
+        dictionary.dedup();
+        Self {
+            base,
+            swept,
+            boundaries: boundaries(),
+            dictionary,
+            budgets,
+            stage: Stage::Boundary,
+            param: 0,
+            value: 0,
+            executions: 0,
+        }
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    fn finish_stage(&mut self) {
+        info!(stage = ?self.stage, executions = self.executions, "stage finished");
+        self.stage = match self.stage {
+            Stage::Boundary => Stage::Dictionary,
+            Stage::Dictionary | Stage::Havoc => Stage::Havoc,
+        };
+        self.param = 0;
+        self.value = 0;
+        self.executions = 0;
+    }
+}
+
+impl<V: ChainValue> Iterator for StagedInputs<V> {
+    type Item = Vec<Parameter<V>>;
+
+    /// The stage's next input, or `None` once havoc has started
+    fn next(&mut self) -> Option<Vec<Parameter<V>>> {
+        loop {
+            let (values, budget) = match self.stage {
+                Stage::Boundary => (&self.boundaries, self.budgets.boundary),
+                Stage::Dictionary => (&self.dictionary, self.budgets.dictionary),
+                Stage::Havoc => return None,
+            };
+            if self.executions >= budget || self.param >= self.swept.len() {
+                self.finish_stage();
+                continue;
+            }
+            let Some(&value) = values.get(self.value) else {
+                self.param += 1;
+                self.value = 0;
+                continue;
+            };
+            self.value += 1;
+
+            let mut input = self.base.clone();
+            let param = &mut input[self.swept[self.param]];
+            // Values the type can't hold, or the initial one, add nothing
+            if param.value.integer_value() == Some(value) {
+                continue;
+            }
+            let Some(swept) = param.value.with_integer_value(value) else {
+                continue;
+            };
+            param.value = swept;
+            self.executions += 1;
+            return Some(input);
+        }
+    }
+}
+
+/// Every input of a target whose parameters all have small types, such as
+/// `bool` and `u8`
+pub struct InputSpace<V: ChainValue> {
+    base: Vec<Parameter<V>>,
+    domains: Vec<Vec<V>>,
+    /// Index into each domain of the next input, `None` once all were given
+    next: Option<Vec<usize>>,
+    size: u64,
+}
+
+impl<V: ChainValue> InputSpace<V> {
+    /// The cross-product of the parameters' domains, if each has one and it
+    /// holds at most `limit` inputs
+    pub fn of(parameters: &[Parameter<V>], limit: u64) -> Option<Self> {
+        let domains = parameters
+            .iter()
+            .map(|param| param.value.domain())
+            .collect::<Option<Vec<_>>>()?;
+        let size = domains
+            .iter()
+            .try_fold(1u64, |size, domain| size.checked_mul(domain.len() as u64))?;
+        if size == 0 || size > limit {
+            return None;
+        }
+        Some(Self {
+            base: parameters.to_vec(),
+            next: Some(vec![0; domains.len()]),
+            domains,
+            size,
+        })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Whether every input was given
+    pub fn is_exhausted(&self) -> bool {
+        self.next.is_none()
+    }
+}
+
+impl<V: ChainValue> Iterator for InputSpace<V> {
+    type Item = Vec<Parameter<V>>;
+
+    fn next(&mut self) -> Option<Vec<Parameter<V>>> {
+        let indices = self.next.as_mut()?;
+        let mut input = self.base.clone();
+        for ((param, domain), &index) in input.iter_mut().zip(&self.domains).zip(indices.iter()) {
+            param.value = domain[index].clone();
+        }
+
+        // Count up like an odometer, the last parameter turning fastest
+        let mut position = indices.len();
+        loop {
+            if position == 0 {
+                self.next = None;
+                break;
+            }
+            position -= 1;
+            indices[position] += 1;
+            if indices[position] < self.domains[position].len() {
+                break;
+            }
+            indices[position] = 0;
+        }
+        Some(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct U8(u8);
+
+    impl ChainValue for U8 {
+        fn is_integer(&self) -> bool {
+            true
+        }
+
+        fn is_integer_vector(&self) -> bool {
+            false
+        }
+
+        fn contains_integers(&self) -> bool {
+            true
+        }
+
+        fn is_mutable_object(&self) -> bool {
+            false
+        }
+
+        fn get_object_id(&self) -> Option<Vec<u8>> {
+            None
+        }
+
+        fn type_name(&self) -> &'static str {
+            "u8"
+        }
+
+        fn integer_value(&self) -> Option<u128> {
+            Some(self.0 as u128)
+        }
+
+        fn with_integer_value(&self, value: u128) -> Option<Self> {
+            u8::try_from(value).ok().map(U8)
+        }
+
+        fn domain(&self) -> Option<Vec<Self>> {
+            Some((0..=u8::MAX).map(U8).collect())
+        }
+    }
+
+    fn param(index: usize, value: u8) -> Parameter<U8> {
+        Parameter {
+            index,
+            name: format!("p{index}"),
+            type_name: "u8".to_string(),
+            value: U8(value),
+        }
+    }
+
+    #[test]
+    fn test_stages() {
+        let budgets = StageBudgets {
+            boundary: 5,
+            dictionary: 100,
+        };
+        let mut stages = StagedInputs::new(vec![param(0, 1), param(1, 2)], vec![1], vec![42, 7, 42, 300], budgets);
+
+        let mut swept = Vec::new();
+        for input in stages.by_ref() {
+            assert_eq!(input[0].value, U8(1));
+            swept.push(input[1].value.0);
+        }
+        // Boundaries up to the budget, skipping the initial value, then the
+        // dictionary values a u8 holds
+        assert_eq!(swept, vec![0, 1, 127, 128, 254, 7, 42]);
+        assert_eq!(stages.stage(), Stage::Havoc);
+    }
+
+    #[test]
+    fn test_input_space() {
+        let params = vec![param(0, 5), param(1, 9)];
+        assert!(InputSpace::of(&params, 65_535).is_none());
+
+        let mut space = InputSpace::of(&params, 65_536).unwrap();
+        assert_eq!(space.size(), 65_536);
+        let inputs: Vec<(u8, u8)> = space
+            .by_ref()
+            .map(|input| (input[0].value.0, input[1].value.0))
+            .collect();
+        assert_eq!(inputs.len(), 65_536);
+        assert_eq!(inputs[..2], [(0, 0), (0, 1)]);
+        assert_eq!(inputs[256], (1, 0));
+        assert_eq!(inputs.last(), Some(&(255, 255)));
+        assert!(space.is_exhausted());
+
+        // Without parameters, the one input is the empty one
+        let mut space = InputSpace::<U8>::of(&[], 1).unwrap();
+        assert_eq!(space.next().map(|input| input.len()), Some(0));
+        assert!(space.is_exhausted());
+    }
+}
@@ -0,0 +1,260 @@
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of slowest inputs kept for the final report
+const MAX_SLOW_INPUTS: usize = 5;
+
+/// Samples kept per distribution; past it the percentiles are estimated
+/// from a uniform random subset of this size
+const MAX_SAMPLES: usize = 10_000;
+
+/// An input that took unusually long to execute
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowInput {
+    pub iteration: u64,
+    pub execution_time_us: u64,
+    pub gas_used: u64,
+    /// JSON-serialized parameters of the input
+    pub parameters: String,
+}
+
+/// Percentile summary of a set of samples
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub min: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+    pub mean: u64,
+}
+
+impl Distribution {
+    pub fn from_samples(samples: &[u64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        let sum: u128 = sorted.iter().map(|v| *v as u128).sum();
+
+        Self {
+            min: sorted[0],
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: sorted[sorted.len() - 1],
+            mean: (sum / sorted.len() as u128) as u64,
+        }
+    }
+}
+
+/// Reservoir of a bounded number of samples, see [`MAX_SAMPLES`]. Min, max
+/// and mean stay exact over every sample.
+#[derive(Debug, Clone)]
+struct Reservoir {
+    kept: Vec<u64>,
+    seen: u64,
+    min: u64,
+    max: u64,
+    sum: u128,
+    rng: StdRng,
+}
+
+impl Default for Reservoir {
+    fn default() -> Self {
+        Self {
+            kept: Vec::new(),
+            seen: 0,
+            min: u64::MAX,
+            max: 0,
+            sum: 0,
+            // Fixed so the same campaign reports the same percentiles
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+}
+
+impl Reservoir {
+    fn push(&mut self, sample: u64) {
+        self.seen += 1;
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+        self.sum += sample as u128;
+        if self.kept.len() < MAX_SAMPLES {
+            self.kept.push(sample);
+            return;
+        }
+        // Keep each of the samples seen so far with equal probability
+        let index = self.rng.random_range(0..self.seen);
+        if let Some(slot) = self.kept.get_mut(index as usize) {
+            *slot = sample;
+        }
+    }
+
+    fn distribution(&self) -> Distribution {
+        if self.seen == 0 {
+            return Distribution::default();
+        }
+        Distribution {
+            min: self.min,
+            max: self.max,
+            mean: (self.sum / self.seen as u128) as u64,
+            ..Distribution::from_samples(&self.kept)
+        }
+    }
+}
+
+/// Final execution statistics of a campaign
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionStatsSummary {
+    pub samples: usize,
+    /// Executions the adapter failed to run, left out of the samples
+    pub failed: u64,
+    pub execution_time_us: Distribution,
+    pub gas_used: Distribution,
+    pub slowest_inputs: Vec<SlowInput>,
+}
+
+/// Collects per-iteration execution time and gas usage
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionStats {
+    execution_times_us: Reservoir,
+    gas_used: Reservoir,
+    /// Slowest inputs, sorted by execution time in descending order
+    slowest: Vec<SlowInput>,
+    failed: u64,
+}
+
+impl ExecutionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one execution. `parameters` is only evaluated when the input
+    /// makes it into the slowest inputs list.
+    pub fn record<F>(&mut self, iteration: u64, execution_time: Duration, gas_used: u64, parameters: F)
+    where
+        F: FnOnce() -> String,
+    {
+        let execution_time_us = execution_time.as_micros() as u64;
+        self.execution_times_us.push(execution_time_us);
+        self.gas_used.push(gas_used);
+
+        let is_slow = self.slowest.len() < MAX_SLOW_INPUTS ||
+            self.slowest
+                .last()
+                .is_some_and(|slowest| execution_time_us > slowest.execution_time_us);
+        if !is_slow {
+            return;
+        }
+
+        let position = self
+            .slowest
+            .iter()
+            .position(|slow| execution_time_us > slow.execution_time_us)
+            .unwrap_or(self.slowest.len());
+        self.slowest.insert(
+            position,
+            SlowInput {
+                iteration,
+                execution_time_us,
+                gas_used,
+                parameters: parameters(),
+            },
+        );
+        self.slowest.truncate(MAX_SLOW_INPUTS);
+    }
+
+    /// Record an execution the adapter failed to run
+    pub fn record_failure(&mut self) {
+        self.failed += 1;
+    }
+
+    pub fn samples(&self) -> usize {
+        self.execution_times_us.seen as usize
+    }
+
+    pub fn summary(&self) -> ExecutionStatsSummary {
+        ExecutionStatsSummary {
+            samples: self.samples(),
+            failed: self.failed,
+            execution_time_us: self.execution_times_us.distribution(),
+            gas_used: self.gas_used.distribution(),
+            slowest_inputs: self.slowest.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_percentiles() {
+        let samples: Vec<u64> = (1..=100).collect();
+        let dist = Distribution::from_samples(&samples);
+
+        assert_eq!(dist.min, 1);
+        assert_eq!(dist.p50, 50);
+        assert_eq!(dist.p90, 90);
+        assert_eq!(dist.p99, 99);
+        assert_eq!(dist.max, 100);
+        assert_eq!(dist.mean, 50);
+
+        assert_eq!(Distribution::from_samples(&[]), Distribution::default());
+    }
+
+    #[test]
+    fn test_slowest_inputs_are_bounded_and_sorted() {
+        let mut stats = ExecutionStats::new();
+        for i in 0..20u64 {
+            stats.record(i, Duration::from_micros(i * 10), i, || format!("input-{}", i));
+        }
+
+        let summary = stats.summary();
+        assert_eq!(summary.samples, 20);
+        assert_eq!(summary.slowest_inputs.len(), MAX_SLOW_INPUTS);
+        assert_eq!(summary.slowest_inputs[0].iteration, 19);
+        assert_eq!(summary.slowest_inputs[MAX_SLOW_INPUTS - 1].iteration, 15);
+        assert_eq!(summary.slowest_inputs[0].parameters, "input-19");
+    }
+
+    #[test]
+    fn test_reservoir_is_bounded() {
+        let mut reservoir = Reservoir::default();
+        let count = MAX_SAMPLES as u64 * 3;
+        for sample in 1..=count {
+            reservoir.push(sample);
+        }
+
+        assert_eq!(reservoir.kept.len(), MAX_SAMPLES);
+        let dist = reservoir.distribution();
+        assert_eq!(dist.min, 1);
+        assert_eq!(dist.max, count);
+        assert_eq!(dist.mean, count.div_ceil(2));
+        // A uniform subset puts the median near the middle
+        assert!(dist.p50.abs_diff(count / 2) < count / 20);
+    }
+
+    #[test]
+    fn test_failures_are_not_samples() {
+        let mut stats = ExecutionStats::new();
+        stats.record(1, Duration::from_micros(10), 1, String::new);
+        stats.record_failure();
+        stats.record_failure();
+
+        let summary = stats.summary();
+        assert_eq!(summary.samples, 1);
+        assert_eq!(summary.failed, 2);
+    }
+}
@@ -0,0 +1,240 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::ChainValue;
+
+/// Constraint on how one parameter is mutated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamRule {
+    /// Never mutated, e.g. the ID of the pool under test
+    Frozen,
+    /// Integer kept within `min..=max`
+    Range { min: u128, max: u128 },
+    /// Integer drawn from these values
+    Values(Vec<u128>),
+    /// Mutated `rounds` times per input instead of once
+    Interesting { rounds: u32 },
+}
+
+/// Rule for the target parameter at `index`. A parameter may have several,
+/// such as a range and a larger budget.
+///
+/// Parsed from `<index>=frozen`, `<index>=range:<min>..<max>`,
+/// `<index>=values:<a>,<b>,...` or `<index>=interesting[:<rounds>]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamSteering {
+    pub index: usize,
+    pub rule: ParamRule,
+}
+
+/// Rounds an `interesting` parameter gets when none are given
+const DEFAULT_INTERESTING_ROUNDS: u32 = 4;
+
+impl FromStr for ParamSteering {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let Some((index, rule)) = s.trim().split_once('=') else {
+            bail!("Parameter rule '{s}' must be <index>=<rule>");
+        };
+        let index = index
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid parameter index in rule '{s}'"))?;
+        let (kind, spec) = match rule.trim().split_once(':') {
+            Some((kind, spec)) => (kind, Some(spec.trim())),
+            None => (rule.trim(), None),
+        };
+        let parse_int = |v: &str| -> anyhow::Result<u128> {
+            v.trim()
+                .parse()
+                .with_context(|| format!("Invalid integer '{v}' in rule '{s}'"))
+        };
+
+        let rule = match (kind, spec) {
+            ("frozen", None) => ParamRule::Frozen,
+            ("range", Some(spec)) => {
+                let Some((min, max)) = spec.split_once("..") else {
+                    bail!("Range in rule '{s}' must be <min>..<max>");
+                };
+                let (min, max) = (parse_int(min)?, parse_int(max)?);
+                if min > max {
+                    bail!("Range in rule '{s}' is empty");
+                }
+                ParamRule::Range { min, max }
+            }
+            ("values", Some(spec)) => {
+                let values = spec.split(',').map(parse_int).collect::<anyhow::Result<Vec<_>>>()?;
+                ParamRule::Values(values)
+            }
+            ("interesting", None) => ParamRule::Interesting {
+                rounds: DEFAULT_INTERESTING_ROUNDS,
+            },
+            ("interesting", Some(rounds)) => {
+                let rounds = rounds
+                    .parse()
+                    .with_context(|| format!("Invalid rounds in rule '{s}'"))?;
+                if rounds == 0 {
+                    bail!("Rounds in rule '{s}' must be greater than 0");
+                }
+                ParamRule::Interesting { rounds }
+            }
+            _ => bail!("Unknown parameter rule '{s}'"),
+        };
+        Ok(Self { index, rule })
+    }
+}
+
+/// Mutation settings of one parameter, combined from its rules
+#[derive(Debug, Clone, Default)]
+pub struct ParamPlan {
+    frozen: bool,
+    rounds: u32,
+    range: Option<(u128, u128)>,
+    values: Vec<u128>,
+}
+
+impl ParamPlan {
+    /// Plan of the parameter at `index` under `rules`
+    pub fn new(index: usize, rules: &[ParamSteering]) -> Self {
+        let mut plan = Self {
+            rounds: 1,
+            ..Self::default()
+        };
+        for rule in rules.iter().filter(|rule| rule.index == index) {
+            match &rule.rule {
+                ParamRule::Frozen => plan.frozen = true,
+                ParamRule::Range { min, max } => plan.range = Some((*min, *max)),
+                ParamRule::Values(values) => plan.values.extend(values),
+                ParamRule::Interesting { rounds } => plan.rounds = plan.rounds.max(*rounds),
+            }
+        }
+        plan
+    }
+
+    /// Times the parameter is mutated per input, 0 if frozen
+    pub fn rounds(&self) -> u32 {
+        if self.frozen {
+            0
+        } else {
+            self.rounds
+        }
+    }
+
+    /// Bring a mutated integer back within the allowed values. A value list
+    /// wins over a range; values out of range are wrapped into it rather
+    /// than clamped, so the bounds aren't overrepresented.
+    pub fn constrain<V: ChainValue>(&self, value: &mut V, rng: &mut impl Rng) {
+        let Some(current) = value.integer_value() else {
+            return;
+        };
+        let target = if !self.values.is_empty() {
+            self.values[rng.random_range(0..self.values.len())]
+        } else if let Some((min, max)) = self.range {
+            if (min..=max).contains(&current) {
+                return;
+            }
+            // Can't overflow: the full range would have contained `current`
+            min + current % (max - min + 1)
+        } else {
+            return;
+        };
+        if let Some(constrained) = value.with_integer_value(target) {
+            *value = constrained;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_steering() {
+        let rule: ParamSteering = "1=range:100..1000".parse().unwrap();
+        assert_eq!(rule.index, 1);
+        assert_eq!(rule.rule, ParamRule::Range { min: 100, max: 1000 });
+        assert_eq!(
+            "0=interesting".parse::<ParamSteering>().unwrap().rule,
+            ParamRule::Interesting { rounds: 4 }
+        );
+        assert_eq!(
+            "2=values:1, 10".parse::<ParamSteering>().unwrap().rule,
+            ParamRule::Values(vec![1, 10])
+        );
+        assert!("0=range:5..1".parse::<ParamSteering>().is_err());
+        assert!("0=frozen:1".parse::<ParamSteering>().is_err());
+        assert!("frozen".parse::<ParamSteering>().is_err());
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct U8(u8);
+
+    impl ChainValue for U8 {
+        fn is_integer(&self) -> bool {
+            true
+        }
+
+        fn is_integer_vector(&self) -> bool {
+            false
+        }
+
+        fn contains_integers(&self) -> bool {
+            true
+        }
+
+        fn is_mutable_object(&self) -> bool {
+            false
+        }
+
+        fn get_object_id(&self) -> Option<Vec<u8>> {
+            None
+        }
+
+        fn type_name(&self) -> &'static str {
+            "u8"
+        }
+
+        fn integer_value(&self) -> Option<u128> {
+            Some(self.0 as u128)
+        }
+
+        fn with_integer_value(&self, value: u128) -> Option<Self> {
+            u8::try_from(value).ok().map(U8)
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let rules: Vec<ParamSteering> = [
+            "0=frozen",
+            "0=interesting:8",
+            "1=interesting:8",
+            "1=range:10..19",
+            "2=values:300",
+        ]
+        .iter()
+        .map(|rule| rule.parse().unwrap())
+        .collect();
+        assert_eq!(ParamPlan::new(0, &rules).rounds(), 0);
+        assert_eq!(ParamPlan::new(3, &rules).rounds(), 1);
+
+        let plan = ParamPlan::new(1, &rules);
+        assert_eq!(plan.rounds(), 8);
+        let mut rng = rand::rng();
+        for (value, expected) in [(15, 15), (25, 15), (9, 19)] {
+            let mut value = U8(value);
+            plan.constrain(&mut value, &mut rng);
+            assert_eq!(value, U8(expected));
+        }
+
+        // Values that don't fit the type leave the parameter unchanged
+        let mut value = U8(1);
+        ParamPlan::new(2, &rules).constrain(&mut value, &mut rng);
+        assert_eq!(value, U8(1));
+    }
+}
@@ -1,9 +1,19 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use crate::coverage::CoverageSummary;
+use crate::guards::GuardSummary;
+use crate::objectives::ObjectivePolicy;
+use crate::result_cache::ResultCacheStats;
+use crate::stages::StageBudgets;
+use crate::stats::ExecutionStatsSummary;
+use crate::steering::ParamSteering;
+use crate::sync::CorpusSyncConfig;
 use crate::ChainValue;
 
 /// Generic function parameter using blockchain-specific value types
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct Parameter<V: ChainValue> {
     pub index: usize,
@@ -33,6 +43,26 @@ pub struct FunctionInfo {
     pub module_name: String,
     pub function_name: String,
     pub type_arguments: Vec<String>,
+    /// Another version of the package the same calls are compared against
+    #[serde(default)]
+    pub compare_package_id: Option<String>,
+    /// Checks run after each call of the function
+    #[serde(default)]
+    pub invariants: Vec<InvariantCall>,
+}
+
+/// Read-only function of the target package called right after the target
+/// function in the same transaction; an abort means the invariant broke
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvariantCall {
+    pub module_name: String,
+    pub function_name: String,
+    #[serde(default)]
+    pub type_arguments: Vec<String>,
+    /// Indices of the target function's parameters passed to the invariant,
+    /// usually the shared objects it inspects
+    #[serde(default)]
+    pub args: Vec<usize>,
 }
 
 /// Violation information
@@ -42,6 +72,17 @@ pub struct ViolationInfo {
     pub operation: String,
     pub left_operand: u64,
     pub right_operand: u64,
+    /// Frames from the entry function down to the violating one
+    #[serde(default)]
+    pub call_chain: Vec<String>,
+}
+
+impl ViolationInfo {
+    /// Key used to deduplicate violations: the same instruction at the same
+    /// `module::function:pc` is reported only once.
+    pub fn dedup_key(&self) -> String {
+        format!("{}#{}", self.location, self.operation)
+    }
 }
 
 /// Object change information for cache updates
@@ -55,7 +96,17 @@ pub struct ObjectChange<Id, Obj> {
 #[derive(Debug, Clone)]
 pub struct FuzzerConfig {
     pub rpc_url: String,
+    /// Further RPC endpoints requests fail over to when `rpc_url` is slow
+    /// or unhealthy
+    pub fallback_rpc_urls: Vec<String>,
+    /// Network the RPC serves, for chains sharing an adapter, e.g. `iota`
+    /// for the Sui one; the adapter's default if unset
+    pub chain: Option<String>,
     pub package_id: String,
+    /// Locally built Move package to fuzz, for code not deployed yet. If
+    /// `package_id` is set as well, the build is fuzzed as an upgrade of
+    /// that package, against its on-chain objects.
+    pub package_path: Option<PathBuf>,
     pub module_name: String,
     pub function_name: String,
     pub type_arguments: Vec<String>,
@@ -63,6 +114,54 @@ pub struct FuzzerConfig {
     pub iterations: u64,
     pub timeout_seconds: u64,
     pub sender: Option<String>,
+    /// Keep fuzzing after the first violation and collect all distinct ones
+    pub keep_going: bool,
+    /// Per-class handling of findings, overriding `keep_going`
+    pub objectives: ObjectivePolicy,
+    /// Executions slower than this are reported as findings (potential DoS)
+    pub max_exec_time_ms: Option<u64>,
+    /// Start from the arguments of the latest successful on-chain call
+    /// among this many, instead of `args`
+    pub seed_from_chain: Option<usize>,
+    /// Move package whose sources and tests are scanned for literal
+    /// arguments of the target function, which override `args`
+    pub seed_sources: Option<PathBuf>,
+    /// Another version of the package, such as the pre-upgrade one, run
+    /// with the same arguments; structural differences in the outcome are
+    /// reported as findings
+    pub compare_package_id: Option<String>,
+    /// Invariant functions checked after every call
+    pub invariants: Vec<InvariantCall>,
+    /// Execution results kept for inputs that repeat, 0 to always simulate
+    pub result_cache_size: usize,
+    /// Inputs executing at once; results are still processed in iteration
+    /// order
+    pub execution_workers: usize,
+    /// Mint objects with random contents for struct parameters given no
+    /// object, bypassing their constructors. Only for white-box testing:
+    /// such objects may be in states the package never produces.
+    pub unsafe_synthesize_objects: bool,
+    /// Parameters to freeze, restrict to ranges or value lists, or mutate
+    /// more
+    pub param_steering: Vec<ParamSteering>,
+    /// Seed of every random choice the fuzzer and its mutator make; a random
+    /// one if unset. Rerunning with the seed of a campaign against the same
+    /// chain state replays its inputs.
+    pub seed: Option<u64>,
+    /// Database recording the campaign and its violations, to track them
+    /// across campaigns
+    pub findings_db: Option<PathBuf>,
+    /// Exchange of coverage-increasing inputs with other instances fuzzing
+    /// the same target
+    pub corpus_sync: Option<CorpusSyncConfig>,
+    /// Executions of the boundary and dictionary stages before havoc
+    pub stage_budgets: StageBudgets,
+    /// Integers the dictionary stage tries on every integer parameter, on
+    /// top of the guards' constants and the initial arguments
+    pub dictionary: Vec<u128>,
+    /// Inputs at most for a target whose parameters all have small types to
+    /// be enumerated instead of fuzzed; 0 never enumerates
+    pub exhaustive_limit: u64,
 }
 
 /// Fuzzing result status
@@ -81,15 +180,142 @@ pub struct FuzzingResult {
     pub violations: Vec<ViolationInfo>,
     pub iterations_completed: u64,
     pub total_iterations: u64,
+    #[serde(default)]
+    pub stats: Option<ExecutionStatsSummary>,
+    #[serde(default)]
+    pub coverage: Option<CoverageSummary>,
+    /// Usage of each RPC endpoint, for adapters with several
+    #[serde(default)]
+    pub endpoints: Vec<EndpointStats>,
+    /// Repeated inputs whose simulation was skipped
+    #[serde(default)]
+    pub result_cache: Option<ResultCacheStats>,
+    /// Iterations past each guard of the target function
+    #[serde(default)]
+    pub guards: Vec<GuardSummary>,
+    /// Seed the campaign ran with, to reproduce its findings
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Size of the target's input space, if it was enumerated and every
+    /// input in it ran
+    #[serde(default)]
+    pub input_space: Option<u64>,
+    /// Whether the campaign stopped at its time limit before running all
+    /// its iterations
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Whether the campaign was stopped before its budget ran out
+    #[serde(default)]
+    pub interrupted: bool,
+    /// Inputs in the corpus when the campaign ended
+    #[serde(default)]
+    pub corpus_size: Option<usize>,
+}
+
+/// Requests served by one RPC endpoint over a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointStats {
+    pub url: String,
+    pub requests: u64,
+    pub failures: u64,
+    pub mean_latency_ms: u64,
+    /// Whether it was healthy when the campaign ended
+    pub healthy: bool,
 }
 
 impl FuzzingResult {
+    pub fn with_stats(mut self, stats: ExecutionStatsSummary) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    pub fn with_coverage(mut self, coverage: CoverageSummary) -> Self {
+        self.coverage = Some(coverage);
+        self
+    }
+
+    pub fn with_endpoints(mut self, endpoints: Vec<EndpointStats>) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    pub fn with_result_cache(mut self, stats: ResultCacheStats) -> Self {
+        self.result_cache = Some(stats);
+        self
+    }
+
+    pub fn with_guards(mut self, guards: Vec<GuardSummary>) -> Self {
+        self.guards = guards;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_input_space(mut self, size: u64) -> Self {
+        self.input_space = Some(size);
+        self
+    }
+
+    pub fn with_timed_out(mut self) -> Self {
+        self.timed_out = true;
+        self
+    }
+
+    pub fn with_interrupted(mut self) -> Self {
+        self.interrupted = true;
+        self
+    }
+
+    pub fn with_corpus_size(mut self, size: usize) -> Self {
+        self.corpus_size = Some(size);
+        self
+    }
+
     pub fn violation_found(violations: Vec<ViolationInfo>, iterations: u64) -> Self {
         Self {
             status: FuzzingStatus::ViolationFound,
             violations,
             iterations_completed: iterations,
             total_iterations: iterations,
+            stats: None,
+            coverage: None,
+            endpoints: vec![],
+            result_cache: None,
+            guards: vec![],
+            seed: None,
+            input_space: None,
+            timed_out: false,
+            interrupted: false,
+            corpus_size: None,
+        }
+    }
+
+    /// Result of a campaign that ran to the end, reporting every distinct
+    /// violation collected along the way.
+    pub fn completed(violations: Vec<ViolationInfo>, iterations_completed: u64, total_iterations: u64) -> Self {
+        let status = if violations.is_empty() {
+            FuzzingStatus::NoViolationFound
+        } else {
+            FuzzingStatus::ViolationFound
+        };
+        Self {
+            status,
+            violations,
+            iterations_completed,
+            total_iterations,
+            stats: None,
+            coverage: None,
+            endpoints: vec![],
+            result_cache: None,
+            guards: vec![],
+            seed: None,
+            input_space: None,
+            timed_out: false,
+            interrupted: false,
+            corpus_size: None,
         }
     }
 
@@ -99,6 +325,16 @@ impl FuzzingResult {
             violations: vec![],
             iterations_completed: 0,
             total_iterations: 0,
+            stats: None,
+            coverage: None,
+            endpoints: vec![],
+            result_cache: None,
+            guards: vec![],
+            seed: None,
+            input_space: None,
+            timed_out: false,
+            interrupted: false,
+            corpus_size: None,
         }
     }
 
@@ -108,6 +344,16 @@ impl FuzzingResult {
             violations: vec![],
             iterations_completed: 0,
             total_iterations: 0,
+            stats: None,
+            coverage: None,
+            endpoints: vec![],
+            result_cache: None,
+            guards: vec![],
+            seed: None,
+            input_space: None,
+            timed_out: false,
+            interrupted: false,
+            corpus_size: None,
         }
     }
 }
@@ -0,0 +1,130 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::ViolationInfo;
+
+/// Object or resource types the target function is supposed to write,
+/// loaded from a TOML or JSON file. Writes to any other type, such as an
+/// admin capability or a treasury, are `UnexpectedWrite` findings.
+///
+/// Entries are a type, a type without arguments for all its
+/// instantiations, or `address::module::*` for all types of a module.
+/// Addresses may be given short.
+///
+/// ```toml
+/// allowed = ["0x42::pool::Pool", "0x2::coin::Coin", "0x42::position::*"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WritePolicy {
+    pub allowed: Vec<String>,
+}
+
+impl WritePolicy {
+    /// Load a policy from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read write policy {}", path.display()))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                toml::from_str(&content).with_context(|| format!("Failed to parse write policy {}", path.display()))
+            }
+            Some("json") => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse write policy {}", path.display())),
+            _ => bail!("Unsupported write policy format: {}", path.display()),
+        }
+    }
+
+    pub fn allows(&self, type_name: &str) -> bool {
+        let type_name = normalize(type_name);
+        let base = type_name.split('<').next().unwrap_or(&type_name);
+        let module = base.rsplit_once("::").map(|(module, _)| module);
+        self.allowed.iter().any(|allowed| {
+            let allowed = normalize(allowed);
+            match allowed.strip_suffix("::*") {
+                Some(allowed_module) => module == Some(allowed_module),
+                None => allowed == type_name || allowed == base,
+            }
+        })
+    }
+
+    /// Findings of the call at `location` writing types in `written` the
+    /// policy doesn't allow, one per type
+    pub fn violations<'a>(&self, location: &str, written: impl IntoIterator<Item = &'a str>) -> Vec<ViolationInfo> {
+        let unexpected: BTreeSet<String> = written
+            .into_iter()
+            .filter(|type_name| !self.allows(type_name))
+            .map(normalize)
+            .collect();
+        unexpected
+            .into_iter()
+            .map(|type_name| ViolationInfo {
+                location: location.to_string(),
+                operation: format!("UnexpectedWrite({type_name})"),
+                left_operand: 0,
+                right_operand: 0,
+                call_chain: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+/// `type_name` without whitespace and with its addresses short, so types
+/// printed by different chains and tools compare equal
+fn normalize(type_name: &str) -> String {
+    let type_name: String = type_name.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut normalized = String::with_capacity(type_name.len());
+    let mut rest = type_name.as_str();
+    while let Some(start) = rest.find("0x") {
+        let (before, after) = rest.split_at(start);
+        normalized.push_str(before);
+        normalized.push_str("0x");
+        rest = &after[2..];
+        // Only a literal standing on its own is an address
+        if before.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(rest.len());
+        let address = rest[..digits].trim_start_matches('0').to_ascii_lowercase();
+        normalized.push_str(if address.is_empty() { "0" } else { &address });
+        rest = &rest[digits..];
+    }
+    normalized.push_str(rest);
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows() {
+        let policy = WritePolicy {
+            allowed: vec![
+                "0x42::pool::Pool".to_string(),
+                "0x2::coin::Coin".to_string(),
+                "0x42::position::*".to_string(),
+            ],
+        };
+
+        let sui_coin = "0x0000000000000000000000000000000000000000000000000000000000000002::coin::Coin<\
+                        0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI>";
+        assert!(policy.allows(sui_coin));
+        assert!(policy.allows("0x42::pool::Pool"));
+        assert!(policy.allows("0x0042::position::Position<0x2::sui::SUI>"));
+        assert!(!policy.allows("0x42::pool::AdminCap"));
+        assert!(!policy.allows("0x43::pool::Pool"));
+
+        let violations = policy.violations(
+            "pool::swap",
+            ["0x42::pool::Pool", "0x042::pool::AdminCap", "0x42::pool::AdminCap"],
+        );
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].operation, "UnexpectedWrite(0x42::pool::AdminCap)");
+    }
+}
@@ -1,12 +1,14 @@
 use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
 use fuzzer_core::{ChainAdapter, FunctionInfo, FuzzerConfig, ObjectChange, Parameter, ViolationInfo};
-use sui_json_rpc_types::{SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedType};
+use sui_json_rpc_types::{
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedType, SuiTransactionBlockEffectsAPI,
+};
 use sui_move_core_types::language_storage::TypeTag;
 use sui_move_core_types::u256::U256;
 use sui_sdk::{SuiClient, SuiClientBuilder};
@@ -357,7 +359,7 @@ impl ChainAdapter for SuiAdapter {
         })
     }
 
-    fn has_shift_violations(&self, result: &Self::ExecutionResult) -> bool {
+    fn has_violations(&self, result: &Self::ExecutionResult) -> bool {
         !result.shift_violations.is_empty()
     }
 
@@ -383,6 +385,14 @@ impl ChainAdapter for SuiAdapter {
             .collect()
     }
 
+    fn execution_time(&self, result: &Self::ExecutionResult) -> Duration {
+        result.execution_time
+    }
+
+    fn gas_used(&self, result: &Self::ExecutionResult) -> u64 {
+        result.simulate_result.effects.gas_cost_summary().gas_used()
+    }
+
     fn extract_object_changes(
         &self,
         result: &Self::ExecutionResult,
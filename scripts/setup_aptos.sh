@@ -75,8 +75,11 @@ if [[ ! -d "$ABI_PATH" ]]; then
     exit 1
 fi
 
-echo "[+] Step 4: Running libafl-aptos fuzzer..."
+echo "[+] Step 4: Running the adapter tests against the demo module..."
 cd "$PROJECT_ROOT"
+cargo test --release -p aptos-adapter -- --ignored
+
+echo "[+] Step 5: Running libafl-aptos fuzzer..."
 
 echo "[*] Running command:"
 echo "[*] timeout 20 $LIBAFL_APTOS_BIN --module-path \"$MODULE_PATH\" --abi-path \"$ABI_PATH\""